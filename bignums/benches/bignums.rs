@@ -0,0 +1,150 @@
+use std::hint::black_box;
+
+use bignums::bigfloat::BigFloat;
+use bignums::biguint::{BigUInt, DivRem, Divider, MulTo};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+
+/// Operand sizes, in limbs
+const SIZES: [usize; 5] = [1, 4, 16, 64, 256];
+
+/// Deterministic pseudo-random number with the given number of limbs
+fn make_biguint(len: usize, seed: u64) -> BigUInt {
+	// xorshift64
+	let mut state = seed.wrapping_mul(0x9E3779B97F4A7C15) | 1;
+	let mut limbs = Vec::with_capacity(len);
+	for _ in 0..len {
+		state ^= state << 13;
+		state ^= state >> 7;
+		state ^= state << 17;
+		limbs.push(state);
+	}
+	// Make sure the number really has len limbs
+	if let Some(hi) = limbs.last_mut() {
+		*hi |= 1 << 63;
+	}
+	BigUInt::from_le_limbs(&limbs)
+}
+
+fn bench_mul_to(c: &mut Criterion) {
+	let mut group = c.benchmark_group("mul_to");
+	for len in SIZES {
+		let a = make_biguint(len, 1);
+		let b = make_biguint(len, 2);
+		let mut res = BigUInt::ZERO;
+		res.mul_to(&a, &b);
+		assert_eq!(
+			(&mut res.clone()).div_rem(&mut a.clone()),
+			(b.clone(), BigUInt::ZERO)
+		);
+
+		group.bench_with_input(BenchmarkId::from_parameter(len), &len, |bench, _| {
+			bench.iter(|| res.mul_to(black_box(&a), black_box(&b)))
+		});
+	}
+	group.finish();
+}
+
+fn bench_div_rem(c: &mut Criterion) {
+	let mut group = c.benchmark_group("div_rem");
+	for len in SIZES {
+		let mut n = make_biguint(2 * len, 3);
+		let mut d = make_biguint(len, 4);
+		let (q, r) = (&mut n).div_rem(&mut d);
+		assert!(r < d);
+		let mut back = &q * &d;
+		back += &r;
+		assert_eq!(back, n);
+
+		group.bench_with_input(BenchmarkId::from_parameter(len), &len, |bench, _| {
+			bench.iter(|| (&mut n).div_rem(black_box(&mut d)))
+		});
+	}
+	group.finish();
+}
+
+/// Same operands as div_rem, with the reciprocal of the divisor precomputed
+fn bench_divider(c: &mut Criterion) {
+	let mut group = c.benchmark_group("Divider::divide");
+	for len in SIZES {
+		let mut n = make_biguint(2 * len, 3);
+		let mut d = make_biguint(len, 4);
+		let divider = Divider::new(d.clone());
+		assert_eq!(divider.divide(&n), (&mut n).div_rem(&mut d));
+
+		group.bench_with_input(BenchmarkId::from_parameter(len), &len, |bench, _| {
+			bench.iter(|| divider.divide(black_box(&n)))
+		});
+	}
+	group.finish();
+}
+
+fn bench_to_string_radix(c: &mut Criterion) {
+	let mut group = c.benchmark_group("to_string_radix");
+	for len in SIZES {
+		let a = make_biguint(len, 5);
+		let s = a.to_string_radix(10, false);
+		assert_eq!(BigUInt::from_str_radix(&s, 10).unwrap(), a);
+
+		group.bench_with_input(BenchmarkId::from_parameter(len), &len, |bench, _| {
+			bench.iter(|| black_box(&a).to_string_radix(10, false))
+		});
+	}
+	group.finish();
+}
+
+fn bench_from_str(c: &mut Criterion) {
+	let mut group = c.benchmark_group("from_str");
+	for len in SIZES {
+		let a = make_biguint(len, 6);
+		let s = a.to_string_radix(10, false);
+		assert_eq!(s.parse::<BigUInt>().unwrap(), a);
+
+		group.bench_with_input(BenchmarkId::from_parameter(len), &len, |bench, _| {
+			bench.iter(|| black_box(s.as_str()).parse::<BigUInt>().unwrap())
+		});
+	}
+	group.finish();
+}
+
+fn bench_reciprocal(c: &mut Criterion) {
+	let mut group = c.benchmark_group("BigFloat::reciprocal");
+	let x = BigFloat::from(3);
+	for len in SIZES {
+		let prec = len as i64 * 64;
+		let r = x.reciprocal(prec);
+		let err = (&(&r * &x) - &BigFloat::ONE).abs();
+		assert!(err < (BigFloat::ONE >> (prec - 2)));
+
+		group.bench_with_input(BenchmarkId::from_parameter(prec), &prec, |bench, &prec| {
+			bench.iter(|| black_box(&x).reciprocal(prec))
+		});
+	}
+	group.finish();
+}
+
+fn bench_pi(c: &mut Criterion) {
+	let mut group = c.benchmark_group("BigFloat::pi");
+	let pi_f64 = BigFloat::try_from(core::f64::consts::PI).unwrap();
+	for len in SIZES {
+		let prec = len as i64 * 64;
+		let err = (&BigFloat::pi(prec) - &pi_f64).abs();
+		assert!(err < (BigFloat::ONE >> 50));
+
+		group.bench_with_input(BenchmarkId::from_parameter(prec), &prec, |bench, &prec| {
+			bench.iter(|| BigFloat::pi(black_box(prec)))
+		});
+	}
+	group.finish();
+}
+
+criterion_group!(
+	benches,
+	bench_mul_to,
+	bench_div_rem,
+	bench_divider,
+	bench_to_string_radix,
+	bench_from_str,
+	bench_reciprocal,
+	bench_pi
+);
+criterion_main!(benches);
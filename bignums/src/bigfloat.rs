@@ -3,6 +3,7 @@ mod bits;
 mod cmp;
 mod consts;
 mod convert;
+mod ctx;
 mod div;
 mod log;
 mod misc;
@@ -14,9 +15,15 @@ mod sqrt;
 mod str;
 mod sub;
 
+pub use ctx::BigFloatCtx;
+
 use crate::bigint::BigInt;
 
 /// An arbitrary precision floating-point number.
+///
+/// Unlike IEEE 754 floats, there is only one zero, which is neither positive nor negative
+/// for the purposes of `is_negative`. Converting -0.0 gives the same zero as 0.0,
+/// negating zero is a no-op and zero is always converted to +0.0.
 #[derive(Debug, Hash)]
 pub struct BigFloat {
 	// Representation: m * 2^e.
@@ -26,6 +33,26 @@ pub struct BigFloat {
 	e: i64,
 }
 
+/// Working precision settings for sqrt_opts, exp_opts and ln_opts.
+/// The default gives the same results as sqrt, exp and ln.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TranscendentalOptions {
+	/// Bits of working precision on top of the built-in guard bits.
+	/// Negative values remove guard bits, which can break the error bound of the result.
+	pub extra_guard_bits: i64,
+	/// Cap on the number of Newton or AGM iterations, or of Taylor series terms for exp.
+	/// None uses a bound derived from the precision, which is never reached.
+	/// A result cut off by a lower cap is less accurate than requested.
+	pub max_iterations: Option<u32>,
+}
+
+impl TranscendentalOptions {
+	fn iteration_limit(&self, prec: i64) -> u32 {
+		self.max_iterations
+			.unwrap_or_else(|| BigFloat::max_iterations(prec))
+	}
+}
+
 impl BigFloat {
 	pub const ZERO: Self = Self {
 		m: BigInt::ZERO,
@@ -42,6 +69,10 @@ impl BigFloat {
 		e: 0,
 	};
 
+	/// Creates mantissa * 2^exponent. Trailing zero bits of the mantissa are moved into the exponent,
+	/// so equal values always have the same representation.
+	/// # Panics
+	/// If that overflows the exponent
 	pub fn from_mantissa_exponent(mantissa: BigInt, exponent: i64) -> Self {
 		let mut res = Self {
 			m: mantissa,
@@ -51,6 +82,13 @@ impl BigFloat {
 		res
 	}
 
+	/// Same as from_mantissa_exponent, but returns None instead of panicking if the exponent overflows
+	pub fn from_mantissa_exponent_checked(mantissa: BigInt, exponent: i64) -> Option<Self> {
+		let trailing_zeros = i64::try_from(mantissa.magnitude.trailing_zeros()).ok()?;
+		exponent.checked_add(trailing_zeros)?;
+		Some(Self::from_mantissa_exponent(mantissa, exponent))
+	}
+
 	pub fn mantissa(&self) -> &BigInt {
 		&self.m
 	}
@@ -63,6 +101,9 @@ impl BigFloat {
 		(&self.m, self.e)
 	}
 
+	/// # Safety
+	/// The mantissa must be left odd, or zero with a zero exponent.
+	/// Equality and hashing compare the representation, so they are wrong for other values.
 	pub unsafe fn inner_mut(&mut self) -> (&mut BigInt, &mut i64) {
 		(&mut self.m, &mut self.e)
 	}
@@ -97,6 +138,12 @@ impl BigFloat {
 		self.m.is_positive()
 	}
 
+	/// Always false, as zero is unsigned. See the type-level documentation.
+	pub fn is_negative_zero(&self) -> bool {
+		debug_assert!(!(self.is_zero() && self.is_negative()), "zero has a sign");
+		false
+	}
+
 	pub fn set_sign(&mut self, is_negative: bool) {
 		self.m.set_sign(is_negative);
 	}
@@ -114,8 +161,25 @@ impl BigFloat {
 		!self.e.is_negative()
 	}
 
+	/// Upper bound for the number of iterations of a loop converging quadratically
+	/// to `prec` bits, with a generous margin for slow starts. Loops stop there even if
+	/// they haven't converged, which can only happen due to a precision bookkeeping bug.
+	fn max_iterations(prec: i64) -> u32 {
+		2 * (prec.unsigned_abs() + 2).ilog2() + 64
+	}
+
+	fn is_normalized(&self) -> bool {
+		if self.m.is_zero() {
+			self.e == 0 && !self.m.is_negative()
+		} else {
+			self.m.magnitude.bit(0)
+		}
+	}
+
+	/// Makes the mantissa odd, or sets the exponent to 0 and clears the sign if it is zero.
 	fn normalize(&mut self) {
 		if self.m.is_zero() {
+			self.m.normalize();
 			self.e = 0;
 		} else {
 			let trailing_zeros = self.m.magnitude.trailing_zeros();
@@ -1,5 +1,6 @@
 use core::ops::{Add, AddAssign};
 
+use crate::SetVal;
 use crate::bigfloat::BigFloat;
 use crate::bigint::BigInt;
 use crate::biguint::BigUInt;
@@ -10,6 +11,29 @@ impl BigFloat {
 		res.round_to_precision(prec);
 		res
 	}
+
+	/// Sets self to lhs + rhs, which is exact, reusing the buffer of self
+	pub fn add_to(&mut self, lhs: &BigFloat, rhs: &BigFloat) {
+		if lhs.is_zero() {
+			self.set_val(rhs);
+			return;
+		}
+		if rhs.is_zero() {
+			self.set_val(lhs);
+			return;
+		}
+		// Align the operand with the larger exponent to the other one
+		let (high, low) = if lhs.e >= rhs.e {
+			(lhs, rhs)
+		} else {
+			(rhs, lhs)
+		};
+		self.set_val(high);
+		self.m.magnitude <<= high.e - low.e;
+		self.e = low.e;
+		self.m += &low.m;
+		self.normalize();
+	}
 }
 
 impl Add<&BigFloat> for &BigFloat {
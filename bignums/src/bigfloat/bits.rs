@@ -6,6 +6,10 @@ macro_rules! impl_shr {
 	($($t:ty),*) => {$(
 		impl ShrAssign<$t> for BigFloat {
 			fn shr_assign(&mut self, rhs: $t) {
+				// Zero has exponent 0, shifting it would denormalize it
+				if self.is_zero() {
+					return;
+				}
 				self.e = (self.e as i128)
 					.strict_sub(rhs.try_into().unwrap())
 					.try_into()
@@ -29,6 +33,10 @@ macro_rules! impl_shl {
 	($($t:ty),*) => {$(
 		impl ShlAssign<$t> for BigFloat {
 			fn shl_assign(&mut self, rhs: $t) {
+				// Zero has exponent 0, shifting it would denormalize it
+				if self.is_zero() {
+					return;
+				}
 				self.e = (self.e as i128)
 					.strict_add(rhs.try_into().unwrap())
 					.try_into()
@@ -47,3 +55,21 @@ macro_rules! impl_shl {
 }
 
 impl_shl! { u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_shift_zero() {
+		let shifted = BigFloat::ZERO >> 5u32;
+		assert!(shifted.is_normalized());
+		assert_eq!(shifted.exponent(), 0);
+		let shifted = BigFloat::ZERO << 70i64;
+		assert!(shifted.is_normalized());
+		assert_eq!(shifted, BigFloat::ZERO);
+
+		assert_eq!(BigFloat::ONE << 3u8, BigFloat::from(8));
+		assert_eq!(BigFloat::from(12) >> 2i32, BigFloat::from(3));
+	}
+}
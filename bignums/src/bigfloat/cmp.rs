@@ -3,9 +3,33 @@ use core::cmp::Ordering;
 use crate::bigfloat::BigFloat;
 use crate::bigint::BigInt;
 use crate::biguint::BigUInt;
+use crate::error::TryFromFloatError;
 use crate::rational::Rational;
 
 impl BigFloat {
+	/// The position of the leading bit, i.e. floor(log2(|self|)) as an i128.
+	/// Returns i128::MIN for 0, so that it orders below every non-zero magnitude.
+	pub fn magnitude_order(&self) -> i128 {
+		match self.m.magnitude.checked_ilog2() {
+			Some(log) => self.e as i128 + log as i128,
+			None => i128::MIN,
+		}
+	}
+
+	/// A cheap key that is consistent with Ord: a < b implies a.ord_key() <= b.ord_key().
+	/// Sorting by the key first and only comparing values within equal keys
+	/// avoids most full comparisons.
+	pub fn ord_key(&self) -> (i8, i128) {
+		if self.is_zero() {
+			(0, 0)
+		} else if self.is_negative() {
+			// Larger magnitudes are smaller negative numbers
+			(-1, -self.magnitude_order())
+		} else {
+			(1, self.magnitude_order())
+		}
+	}
+
 	pub fn cmp_abs(&self, other: &BigFloat) -> Ordering {
 		match (self.is_zero(), other.is_zero()) {
 			(true, true) => Ordering::Equal,
@@ -18,6 +42,11 @@ impl BigFloat {
 
 impl PartialEq for BigFloat {
 	fn eq(&self, other: &Self) -> bool {
+		// Every value has a unique normalized representation, which all constructors produce
+		debug_assert!(
+			self.is_normalized() && other.is_normalized(),
+			"BigFloat is not normalized"
+		);
 		self.e == other.e && self.m == other.m
 	}
 }
@@ -89,43 +118,69 @@ impl PartialEq<Rational> for BigFloat {
 		if self.is_negative() != other.is_negative() {
 			return false;
 		}
-		if self.is_zero() {
-			return other.is_zero();
-		}
-		if other.is_zero() {
-			return self.is_zero();
+		if self.is_zero() || other.is_zero() {
+			return self.is_zero() && other.is_zero();
 		}
 
-		let mut other = other.clone();
-		other.reduce();
-		let Some(log_d) = other.denominator().ilog2_exact() else {
+		// n/d is in (2^(ilog2(n) - ilog2(d) - 1), 2^(ilog2(n) - ilog2(d) + 1)),
+		// which rules out most unequal values before reducing other
+		let log2_other = other.n.magnitude.ilog2() as i64 - other.d.ilog2() as i64;
+		let log2_self = self.ilog2();
+		if log2_self != log2_other && log2_self != log2_other - 1 {
 			return false;
-		};
+		}
 
 		if self.e.is_negative() {
-			if self.e != -(log_d as i64) {
+			let mut other = other.clone();
+			other.reduce();
+			let Some(log_d) = other.denominator().ilog2_exact() else {
 				return false;
-			}
-			self.m.magnitude == other.numerator().magnitude
+			};
+			self.e == -(log_d as i64) && self.m.magnitude == other.numerator().magnitude
+		} else if other.denominator().is_one() {
+			eq_shifted(&self.m.magnitude, &other.n.magnitude, self.e as u64)
 		} else {
-			if !other.denominator().is_one() {
-				return false;
-			}
-			if self.e as u64 + self.m.magnitude.ilog2() != other.n.magnitude.ilog2() {
-				return false;
-			}
-			let m = self.m.clone() << self.e;
-			m == other.n
+			let mut other = other.clone();
+			other.reduce();
+			other.denominator().is_one()
+				&& eq_shifted(&self.m.magnitude, &other.n.magnitude, self.e as u64)
 		}
 	}
 }
 
+/// Checks whether n == m << shift without shifting m, m must be odd
+fn eq_shifted(m: &BigUInt, n: &BigUInt, shift: u64) -> bool {
+	if n.trailing_zeros() != shift || n.ilog2() != m.ilog2() + shift {
+		return false;
+	}
+	m.inner()
+		.iter()
+		.enumerate()
+		.all(|(i, &digit)| n.bit_range(shift + i as u64 * u64::BITS as u64, u64::BITS) == digit)
+}
+
 impl PartialEq<BigFloat> for Rational {
 	fn eq(&self, other: &BigFloat) -> bool {
 		other == self
 	}
 }
 
+impl PartialOrd<Rational> for BigFloat {
+	fn partial_cmp(&self, other: &Rational) -> Option<Ordering> {
+		match (self.is_negative(), other.is_negative()) {
+			(false, true) => Some(Ordering::Greater),
+			(true, false) => Some(Ordering::Less),
+			_ => self.to_rational().partial_cmp(other),
+		}
+	}
+}
+
+impl PartialOrd<BigFloat> for Rational {
+	fn partial_cmp(&self, other: &BigFloat) -> Option<Ordering> {
+		other.partial_cmp(self).map(Ordering::reverse)
+	}
+}
+
 impl PartialOrd for BigFloat {
 	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
 		Some(self.cmp(other))
@@ -185,22 +240,76 @@ macro_rules! impl_partial_ord {
 
 impl_partial_ord! { u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize }
 
-fn cmp_abs_non_zero(a: &BigFloat, b: &BigFloat) -> Ordering {
-	let a_e_real = (a.e as i128) + (a.m.magnitude.ilog2() as i128);
-	let b_e_real = (b.e as i128) + (b.m.magnitude.ilog2() as i128);
-	Ord::cmp(&a_e_real, &b_e_real).then_with(|| match Ord::cmp(&a.e, &b.e) {
-		Ordering::Less => {
-			let b_m = b.m.magnitude.clone() << (b.e - a.e);
-			Ord::cmp(&a.m.magnitude, &b_m)
+// Floats are converted exactly, so 0.1f64 is only equal to its binary value.
+// NaN is unordered and unequal to everything, infinities are beyond every BigFloat.
+macro_rules! impl_float_cmp {
+	($($t:ty),*) => {$(
+		impl PartialEq<$t> for BigFloat {
+			fn eq(&self, other: &$t) -> bool {
+				self.partial_cmp(other) == Some(Ordering::Equal)
+			}
+		}
+
+		impl PartialEq<BigFloat> for $t {
+			fn eq(&self, other: &BigFloat) -> bool {
+				other == self
+			}
+		}
+
+		impl PartialOrd<$t> for BigFloat {
+			fn partial_cmp(&self, other: &$t) -> Option<Ordering> {
+				match BigFloat::try_from(*other) {
+					Ok(other) => Some(self.cmp(&other)),
+					Err(TryFromFloatError::Infinite) if other.is_sign_positive() => Some(Ordering::Less),
+					Err(TryFromFloatError::Infinite) => Some(Ordering::Greater),
+					Err(TryFromFloatError::NaN) => None,
+				}
+			}
 		}
-		Ordering::Equal => Ord::cmp(&a.m.magnitude, &b.m.magnitude),
-		Ordering::Greater => {
-			let a_m = a.m.magnitude.clone() << (a.e - b.e);
-			Ord::cmp(&a_m, &b.m.magnitude)
+
+		impl PartialOrd<BigFloat> for $t {
+			fn partial_cmp(&self, other: &BigFloat) -> Option<Ordering> {
+				other.partial_cmp(self).map(Ordering::reverse)
+			}
+		}
+	)*};
+}
+
+impl_float_cmp! { f32, f64 }
+
+fn cmp_abs_non_zero(a: &BigFloat, b: &BigFloat) -> Ordering {
+	Ord::cmp(&a.magnitude_order(), &b.magnitude_order()).then_with(|| {
+		// Same leading bit position, compare the mantissas aligned at their leading bits
+		// 64 bits at a time, instead of shifting one of them
+		let mut a_end = a.m.magnitude.ilog2() as i64 + 1;
+		let mut b_end = b.m.magnitude.ilog2() as i64 + 1;
+		while a_end > 0 || b_end > 0 {
+			let ord = Ord::cmp(
+				&window_below(&a.m.magnitude, a_end),
+				&window_below(&b.m.magnitude, b_end),
+			);
+			if ord != Ordering::Equal {
+				return ord;
+			}
+			a_end -= u64::BITS as i64;
+			b_end -= u64::BITS as i64;
 		}
+		Ordering::Equal
 	})
 }
 
+/// The 64 bits of m just below bit end, padded with zeros below bit 0
+fn window_below(m: &BigUInt, end: i64) -> u64 {
+	let start = end - u64::BITS as i64;
+	if start >= 0 {
+		m.bit_range(start as u64, u64::BITS)
+	} else if end <= 0 {
+		0
+	} else {
+		m.bit_range(0, end as u32) << -start
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -214,6 +323,68 @@ mod tests {
 		assert_ne!(a, c);
 	}
 
+	#[test]
+	fn test_eq_normalized() {
+		let from = |m: i64, e: i64| BigFloat::from_mantissa_exponent_checked(BigInt::from(m), e);
+		assert_eq!(from(4, 0), from(1, 2));
+		assert_eq!(from(4, 0).unwrap(), BigFloat::from(4));
+		assert_eq!(from(-12, -3), from(-3, -1));
+		assert_eq!(from(0, 7), Some(BigFloat::ZERO));
+		assert_eq!(from(0, 7).unwrap().inner(), (&BigInt::ZERO, 0));
+		assert_eq!(from(24, 5).unwrap().inner(), (&BigInt::from(3), 8));
+
+		assert_eq!(from(2, i64::MAX), None);
+		assert_eq!(
+			from(1, i64::MAX),
+			Some(BigFloat::from_mantissa_exponent(BigInt::ONE, i64::MAX))
+		);
+		assert_eq!(from(0, i64::MAX), Some(BigFloat::ZERO));
+	}
+
+	#[test]
+	fn test_eq_non_normalized_inputs() {
+		use std::hash::{BuildHasher, RandomState};
+
+		let hasher = RandomState::new();
+		let four = [
+			BigFloat::from_mantissa_exponent(BigInt::from(4), 0),
+			BigFloat::from_mantissa_exponent(BigInt::from(1), 2),
+			BigFloat::from_mantissa_exponent(BigInt::from(16), -2),
+			BigFloat::from_mantissa_exponent(BigInt::ONE << 200u32, -198),
+			BigFloat::from(4),
+			BigFloat::from(BigUInt::from(4u32)),
+			BigFloat::try_from(4.0).unwrap(),
+			BigFloat::from_str_radix("4", 10).unwrap(),
+			BigFloat::from_str_radix("100", 2).unwrap(),
+			BigFloat::ONE.mul_pow2(2),
+			BigFloat::ONE << 2,
+			BigFloat::from(64) >> 4,
+			&BigFloat::from(3) + &BigFloat::ONE,
+			&BigFloat::from(6) - &BigFloat::from(2),
+			&BigFloat::from(2) * &BigFloat::from(2),
+			BigFloat::from(12).div(&BigFloat::from(3), 64),
+		];
+		for a in &four {
+			assert_eq!(a.inner(), (&BigInt::ONE, 2));
+			for b in &four {
+				assert_eq!(a, b);
+				assert_eq!(a.cmp(b), Ordering::Equal);
+				assert_eq!(hasher.hash_one(a), hasher.hash_one(b));
+			}
+		}
+
+		let zeros = [
+			BigFloat::from_mantissa_exponent(BigInt::ZERO, 5),
+			BigFloat::from_mantissa_exponent(-BigInt::ZERO, -5),
+			&BigFloat::from(3) - &BigFloat::from(3),
+			&BigFloat::from(-3) * &BigFloat::ZERO,
+		];
+		for z in &zeros {
+			assert_eq!(z, &BigFloat::ZERO);
+			assert_eq!(hasher.hash_one(z), hasher.hash_one(BigFloat::ZERO));
+		}
+	}
+
 	#[test]
 	fn test_eq_bigint() {
 		let a = BigFloat::from(123);
@@ -247,6 +418,33 @@ mod tests {
 		assert!(a > d);
 	}
 
+	#[test]
+	fn test_signed_zero() {
+		let pos = BigFloat::try_from(0.0).unwrap();
+		let neg = BigFloat::try_from(-0.0).unwrap();
+		assert_eq!(pos, BigFloat::ZERO);
+		assert_eq!(neg, BigFloat::ZERO);
+		assert_eq!(pos.cmp(&neg), Ordering::Equal);
+		assert!(!neg.is_negative());
+		assert!(!neg.is_negative_zero());
+
+		let mut zero = BigFloat::ZERO;
+		zero.neg_in_place();
+		assert_eq!(zero, BigFloat::ZERO);
+		assert!(!zero.is_negative());
+		assert!(!(-BigFloat::ZERO).is_negative_zero());
+
+		let cancelled = &BigFloat::from(-3) + &BigFloat::from(3);
+		assert!(!cancelled.is_negative());
+		assert!(
+			(&cancelled * &BigFloat::from(-1))
+				.to_f64()
+				.is_sign_positive()
+		);
+		assert!(neg.to_f64().is_sign_positive());
+		assert!(neg.to_f32().is_sign_positive());
+	}
+
 	#[test]
 	fn test_eq_rational() {
 		let a = BigFloat::ZERO;
@@ -274,4 +472,154 @@ mod tests {
 		assert_eq!(a, b);
 		assert_eq!(b, a);
 	}
+
+	#[test]
+	fn test_eq_rational_exponent_signs() {
+		let r = |n: i128, d: u128| Rational::new(BigInt::from(n), BigUInt::from(d));
+
+		// Positive exponent, integer rational
+		let a = BigFloat::from_mantissa_exponent(BigInt::from(3), 100);
+		let b = Rational::from(BigInt::from(3) << 100);
+		assert_eq!(a, b);
+		assert_eq!(b, a);
+		assert_ne!(a, Rational::from(BigInt::from(3) << 99));
+		assert_ne!(a, Rational::from(BigInt::from(5) << 100));
+		assert_ne!(a, Rational::from((BigInt::from(3) << 100) + 1));
+		assert_ne!(a, Rational::from(BigInt::from(-3) << 100));
+
+		// Positive exponent, unreduced rational
+		let a = BigFloat::from_mantissa_exponent(BigInt::from(-5), 3);
+		assert_eq!(a, r(-120, 3));
+		assert_ne!(a, r(-121, 3));
+		assert_ne!(a, r(-40, 3));
+
+		// Mantissa spanning several digits
+		let m = BigInt::from(0x1234_5678_9abc_def0_1357_9bdf_u128 | 1);
+		let a = BigFloat::from_mantissa_exponent(m.clone(), 70);
+		assert_eq!(a, Rational::from(m.clone() << 70));
+		assert_ne!(a, Rational::from((m.clone() << 70) + &(BigInt::ONE << 140)));
+
+		// Zero exponent
+		assert_eq!(BigFloat::from(7), r(7, 1));
+		assert_eq!(BigFloat::from(7), r(14, 2));
+		assert_ne!(BigFloat::from(7), r(15, 2));
+
+		// Negative exponent
+		let a = BigFloat::from_mantissa_exponent(BigInt::from(3), -4);
+		assert_eq!(a, r(3, 16));
+		assert_eq!(a, r(6, 32));
+		assert_ne!(a, r(3, 8));
+		assert_ne!(a, r(5, 16));
+		assert_ne!(a, r(1, 3));
+		assert_ne!(a, r(-3, 16));
+
+		assert_ne!(BigFloat::ZERO, r(1, 1000));
+		assert_ne!(BigFloat::ONE, Rational::ZERO);
+	}
+
+	#[test]
+	fn test_ord_key() {
+		let values: Vec<BigFloat> = [
+			0.0,
+			1.0,
+			-1.0,
+			0.75,
+			-0.75,
+			1.5,
+			1.25,
+			-1.25,
+			3.0,
+			2.0,
+			1e300,
+			-1e300,
+			1e-300,
+			7.0,
+			-7.5,
+			0.1,
+			0.2,
+			-0.2,
+			1.0000000000000002,
+		]
+		.iter()
+		.map(|&x| BigFloat::try_from(x).unwrap())
+		.chain([
+			BigFloat::from_mantissa_exponent(BigInt::from(u128::MAX), -128),
+			BigFloat::from_mantissa_exponent(BigInt::from(u128::MAX - 2), -128),
+			BigFloat::from_mantissa_exponent(BigInt::from(-((1i128 << 100) + 1)), -100),
+			BigFloat::from_mantissa_exponent(BigInt::ONE, 1000),
+		])
+		.collect();
+
+		let mut by_ord = values.clone();
+		by_ord.sort();
+		let mut by_key = values.clone();
+		by_key.sort_by(|a, b| a.ord_key().cmp(&b.ord_key()).then_with(|| a.cmp(b)));
+		assert_eq!(by_ord, by_key);
+
+		for a in &values {
+			for b in &values {
+				if a < b {
+					assert!(a.ord_key() <= b.ord_key(), "{a} < {b}");
+				}
+			}
+		}
+
+		assert_eq!(BigFloat::from(5).magnitude_order(), 2);
+		assert_eq!(BigFloat::try_from(0.25).unwrap().magnitude_order(), -2);
+		assert_eq!(BigFloat::ZERO.magnitude_order(), i128::MIN);
+	}
+
+	#[test]
+	fn test_cmp_aligned_mantissas() {
+		// Same leading bit, mantissas of different lengths
+		let a = BigFloat::from_mantissa_exponent(BigInt::from((1u128 << 127) | 1), -127);
+		let b = BigFloat::from_mantissa_exponent(BigInt::from(3), -1);
+		let c = BigFloat::ONE;
+		assert!(c < a && a < b);
+		assert!(b > a && a > c);
+		assert_eq!(a.cmp(&a.clone()), Ordering::Equal);
+
+		let big = BigInt::from(u128::MAX) << 70u32;
+		let d = BigFloat::from(big.clone() + &BigInt::ONE);
+		let e = BigFloat::from(big + &BigInt::from(3));
+		assert!(d < e);
+		assert!(-d > -e);
+	}
+
+	#[test]
+	fn test_cmp_float() {
+		assert!(BigFloat::from(1) == 1.0f64);
+		assert!(BigFloat::try_from(0.5).unwrap() == 0.5f64);
+		assert!(0.5f64 == BigFloat::try_from(0.5).unwrap());
+		assert!(BigFloat::from(-3) == -3.0f32);
+		assert!(BigFloat::ZERO == 0.0f64);
+		assert!(BigFloat::ZERO == -0.0f64);
+		assert!(BigFloat::from(1) != 1.5f64);
+
+		assert!(BigFloat::from(1) < 1.5f64);
+		assert!(BigFloat::from(2) > 1.5f64);
+		assert!(BigFloat::from(-2) < -1.5f32);
+		assert!(1.5f64 < BigFloat::from(2));
+		assert!(f64::MAX > BigFloat::from(u128::MAX));
+		assert!(BigFloat::from((1u64 << 53) + 1) > 9007199254740992.0f64);
+
+		// The conversion is exact, 0.1 is not a binary fraction
+		let tenth = BigFloat::ONE.div(&BigFloat::from(10), 200);
+		assert!(tenth != 0.1f64);
+		assert!(tenth < 0.1f64);
+		// 0.1f32 rounds up
+		assert!(tenth < 0.1f32);
+		assert!(BigFloat::try_from(0.1f64).unwrap() == 0.1f64);
+		assert!(BigFloat::try_from(f64::MIN_POSITIVE / 4.0).unwrap() == f64::MIN_POSITIVE / 4.0);
+
+		let x = BigFloat::from(7);
+		assert!(x < f64::INFINITY);
+		assert!(x > f64::NEG_INFINITY);
+		assert!(f32::INFINITY > x);
+		assert!(x != f64::INFINITY);
+		assert_eq!(x.partial_cmp(&f64::NAN), None);
+		assert_eq!(f64::NAN.partial_cmp(&x), None);
+		assert_eq!(x.partial_cmp(&f32::NAN), None);
+		assert_eq!(f32::NAN.partial_cmp(&x), None);
+	}
 }
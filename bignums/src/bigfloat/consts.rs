@@ -8,7 +8,7 @@ impl BigFloat {
 	pub(crate) fn ln2_underestimate(prec: i64) -> BigFloat {
 		let mut k = 1i64;
 		let mut res = BigFloat::ZERO;
-		let working_prec = prec + prec.ilog2() as i64 + 16;
+		let working_prec = prec + i64::max(prec, 1).ilog2() as i64 + 16;
 
 		// ln2 = sum(1/(n*2^n))
 		while k <= prec {
@@ -96,6 +96,9 @@ mod tests {
 		test_ln2_helper(64);
 		test_ln2_helper(128);
 		test_ln2_helper(256);
+		// Coarse precisions, as requested by exp for small arguments
+		test_ln2_helper(0);
+		test_ln2_helper(-10);
 	}
 
 	fn test_ln2_helper(prec: i64) {
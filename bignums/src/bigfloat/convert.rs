@@ -1,7 +1,9 @@
+use alloc::vec::Vec;
+
 use crate::bigfloat::BigFloat;
 use crate::bigint::BigInt;
 use crate::biguint::BigUInt;
-use crate::error::TryFromFloatError;
+use crate::error::{TryFromBigFloatError, TryFromFloatError};
 use crate::rational::Rational;
 use crate::{SetVal, TrySetVal, util};
 
@@ -46,7 +48,36 @@ macro_rules! impl_try_from {
 
 impl_try_from! { f32, f64 }
 
+impl TryFrom<&BigFloat> for BigInt {
+	type Error = TryFromBigFloatError;
+	/// Converts the value if it is an integer
+	fn try_from(value: &BigFloat) -> Result<Self, Self::Error> {
+		if !value.is_integer() {
+			return Err(TryFromBigFloatError::NotInteger);
+		}
+		Ok(value.m.clone() << value.e)
+	}
+}
+
+impl TryFrom<&BigFloat> for BigUInt {
+	type Error = TryFromBigFloatError;
+	/// Converts the value if it is a non-negative integer
+	fn try_from(value: &BigFloat) -> Result<Self, Self::Error> {
+		if value.is_negative() {
+			return Err(TryFromBigFloatError::Negative);
+		}
+		Ok(BigInt::try_from(value)?.into_inner())
+	}
+}
+
 impl BigFloat {
+	/// Converts a float and rounds it s.t. the absolute error is less than 2^-prec
+	pub fn from_f64_rounded(value: f64, prec: i64) -> Result<BigFloat, TryFromFloatError> {
+		let mut res = BigFloat::try_from(value)?;
+		res.round_to_precision(prec);
+		Ok(res)
+	}
+
 	pub fn to_f32(&self) -> f32 {
 		if self.is_zero() {
 			0.0f32
@@ -103,6 +134,31 @@ impl BigFloat {
 		}
 		Rational::new(n, d)
 	}
+
+	/// Returns the first `terms` partial quotients of the continued fraction of self.
+	/// Since self is dyadic, the expansion terminates and may have fewer than `terms` terms.
+	/// Only the terms that are determined by the precision self was computed with are meaningful.
+	pub fn to_continued_fraction(&self, terms: usize) -> Vec<BigInt> {
+		let mut n = self.m.clone();
+		let mut d = BigInt::ONE;
+		if self.e.is_negative() {
+			d <<= -self.e;
+		} else {
+			n <<= self.e;
+		}
+
+		let mut res = Vec::with_capacity(terms);
+		while res.len() < terms {
+			let (q, r) = n.div_rem_floor(&mut d);
+			res.push(q);
+			if r.is_zero() {
+				break;
+			}
+			n = d;
+			d = r;
+		}
+		res
+	}
 }
 
 #[cfg(test)]
@@ -204,4 +260,89 @@ mod tests {
 			Rational::new(BigInt::from(-3i64), BigUInt::from(4u64))
 		);
 	}
+
+	#[test]
+	fn test_to_continued_fraction() {
+		let pi = BigFloat::pi(200);
+		let expected: Vec<BigInt> = [3, 7, 15, 1, 292, 1, 1, 1, 2, 1, 3, 1, 14, 2, 1]
+			.into_iter()
+			.map(BigInt::from)
+			.collect();
+		assert_eq!(pi.to_continued_fraction(expected.len()), expected);
+
+		// 3.25 = 3 + 1/4
+		let a = BigFloat::try_from(3.25).unwrap();
+		assert_eq!(
+			a.to_continued_fraction(10),
+			[BigInt::from(3), BigInt::from(4)]
+		);
+
+		// -0.75 = -1 + 1/4
+		let a = BigFloat::try_from(-0.75).unwrap();
+		assert_eq!(
+			a.to_continued_fraction(10),
+			[BigInt::from(-1), BigInt::from(4)]
+		);
+
+		assert_eq!(
+			BigFloat::from(12).to_continued_fraction(3),
+			[BigInt::from(12)]
+		);
+		assert_eq!(BigFloat::ZERO.to_continued_fraction(3), [BigInt::ZERO]);
+		assert!(BigFloat::ONE.to_continued_fraction(0).is_empty());
+	}
+
+	#[test]
+	fn test_from_f64_rounded() {
+		for value in [
+			core::f64::consts::PI,
+			-core::f64::consts::E,
+			0.1,
+			1e100,
+			-1e-100,
+			0.0,
+		] {
+			let exact = BigFloat::try_from(value).unwrap();
+			for prec in [-10, 0, 1, 10, 52, 100] {
+				let rounded = BigFloat::from_f64_rounded(value, prec).unwrap();
+				let delta = (&rounded - &exact).abs();
+				assert!(delta < BigFloat::from_mantissa_exponent(BigInt::ONE, -prec));
+			}
+		}
+
+		assert_eq!(BigFloat::from_f64_rounded(0.75, 1).unwrap(), BigFloat::ONE);
+		assert_eq!(
+			BigFloat::from_f64_rounded(f64::NAN, 10),
+			Err(TryFromFloatError::NaN)
+		);
+		assert_eq!(
+			BigFloat::from_f64_rounded(f64::INFINITY, 10),
+			Err(TryFromFloatError::Infinite)
+		);
+		assert_eq!(
+			BigFloat::from_f64_rounded(f64::NEG_INFINITY, 10),
+			Err(TryFromFloatError::Infinite)
+		);
+	}
+
+	#[test]
+	fn test_try_into_int() {
+		let x = BigFloat::from(-96);
+		assert_eq!(BigInt::try_from(&x), Ok(BigInt::from(-96)));
+		assert_eq!(BigUInt::try_from(&x), Err(TryFromBigFloatError::Negative));
+		let x = BigFloat::from(96);
+		assert_eq!(BigInt::try_from(&x), Ok(BigInt::from(96)));
+		assert_eq!(BigUInt::try_from(&x), Ok(BigUInt::from(96u64)));
+		let x = BigFloat::from_mantissa_exponent(BigInt::from(3), 100);
+		assert_eq!(BigUInt::try_from(&x), Ok(BigUInt::from(3u64) << 100u32));
+		assert_eq!(BigInt::try_from(&BigFloat::ZERO), Ok(BigInt::ZERO));
+		assert_eq!(BigUInt::try_from(&BigFloat::ZERO), Ok(BigUInt::ZERO));
+
+		let x = BigFloat::try_from(2.5).unwrap();
+		assert_eq!(BigInt::try_from(&x), Err(TryFromBigFloatError::NotInteger));
+		assert_eq!(BigUInt::try_from(&x), Err(TryFromBigFloatError::NotInteger));
+		let x = BigFloat::try_from(-0.125).unwrap();
+		assert_eq!(BigInt::try_from(&x), Err(TryFromBigFloatError::NotInteger));
+		assert_eq!(BigUInt::try_from(&x), Err(TryFromBigFloatError::Negative));
+	}
 }
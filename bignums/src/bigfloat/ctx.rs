@@ -0,0 +1,168 @@
+use crate::bigfloat::{BigFloat, TranscendentalOptions};
+
+/// Reusable state for evaluating BigFloat functions many times, e.g. at the same precision:
+/// scratch buffers for the Newton and AGM iterations, and the last pi and ln(2) computed.
+/// Results are the same as those of the BigFloat methods, which use a throwaway context.
+#[derive(Debug, Default, Clone)]
+pub struct BigFloatCtx {
+	pub(crate) reciprocal: Scratch,
+	pub(crate) sqrt: Scratch,
+	pub(crate) agm: Scratch,
+	pi: Option<(i64, BigFloat)>,
+	ln2: Option<(i64, BigFloat)>,
+}
+
+/// Temporaries of one iteration, their values are meaningless between calls
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Scratch {
+	pub(crate) a: BigFloat,
+	pub(crate) b: BigFloat,
+	pub(crate) c: BigFloat,
+}
+
+impl BigFloatCtx {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Same as x.reciprocal(prec)
+	/// # Panics
+	/// If x is zero
+	pub fn reciprocal(&mut self, x: &BigFloat, prec: i64) -> BigFloat {
+		assert!(!x.is_zero(), "Cannot divide by zero");
+		x.reciprocal_with(prec, self)
+	}
+
+	/// Same as x.div(y, prec)
+	/// # Panics
+	/// If y is zero
+	pub fn div(&mut self, x: &BigFloat, y: &BigFloat, prec: i64) -> BigFloat {
+		assert!(!y.is_zero(), "Cannot divide by zero");
+		x.div_with(y, prec, self)
+	}
+
+	/// Same as x.sqrt(prec)
+	/// # Panics
+	/// If x is negative
+	pub fn sqrt(&mut self, x: &BigFloat, prec: i64) -> BigFloat {
+		assert!(!x.is_negative(), "Cannot take sqrt of negative number");
+		x.sqrt_with(prec, TranscendentalOptions::default(), self).0
+	}
+
+	/// Same as x.agm(y, prec)
+	pub fn agm(&mut self, x: &BigFloat, y: &BigFloat, prec: i64) -> BigFloat {
+		x.agm_with(y, prec, TranscendentalOptions::default(), self)
+	}
+
+	/// Same as x.ln(prec)
+	/// # Panics
+	/// If x is not positive
+	pub fn ln(&mut self, x: &BigFloat, prec: i64) -> BigFloat {
+		x.ln_with(prec, TranscendentalOptions::default(), self)
+	}
+
+	/// Same as BigFloat::pi(prec), computed again only if prec differs from the last call
+	pub fn pi(&mut self, prec: i64) -> BigFloat {
+		cached(&mut self.pi, prec, BigFloat::pi)
+	}
+
+	/// Same as BigFloat::ln2(prec), computed again only if prec differs from the last call
+	pub fn ln2(&mut self, prec: i64) -> BigFloat {
+		cached(&mut self.ln2, prec, BigFloat::ln2)
+	}
+}
+
+fn cached(cache: &mut Option<(i64, BigFloat)>, prec: i64, f: fn(i64) -> BigFloat) -> BigFloat {
+	match cache {
+		Some((cached_prec, value)) if *cached_prec == prec => value.clone(),
+		_ => cache.insert((prec, f(prec))).1.clone(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use core::str::FromStr;
+
+	use super::*;
+
+	fn values() -> [BigFloat; 7] {
+		[
+			"2",
+			"3",
+			"0.001",
+			"12345.678",
+			"0.000000000000000000000000000001",
+			"70000000000000000000000000000000000000000",
+			"0.999999",
+		]
+		.map(|s| BigFloat::from_str(s).unwrap())
+	}
+
+	#[test]
+	fn test_ctx_matches_methods() {
+		// One context for everything, so the buffers hold leftovers of other calls
+		let mut ctx = BigFloatCtx::new();
+		for prec in [-10, 0, 64, 300] {
+			for x in &values() {
+				assert_eq!(
+					ctx.reciprocal(x, prec),
+					x.reciprocal(prec),
+					"1/{x} at {prec}"
+				);
+				assert_eq!(ctx.sqrt(x, prec), x.sqrt(prec), "sqrt({x}) at {prec}");
+				assert_eq!(ctx.ln(x, prec), x.ln(prec), "ln({x}) at {prec}");
+				for y in &values() {
+					assert_eq!(ctx.div(x, y, prec), x.div(y, prec), "{x}/{y} at {prec}");
+					assert_eq!(
+						ctx.agm(x, y, prec),
+						x.agm(y, prec),
+						"agm({x}, {y}) at {prec}"
+					);
+				}
+			}
+			assert_eq!(ctx.pi(prec), BigFloat::pi(prec));
+			assert_eq!(ctx.ln2(prec), BigFloat::ln2(prec));
+		}
+
+		let x = BigFloat::from(-3);
+		assert_eq!(ctx.reciprocal(&x, 100), x.reciprocal(100));
+		assert_eq!(
+			ctx.div(&x, &BigFloat::from(7), 100),
+			x.div(&BigFloat::from(7), 100)
+		);
+		assert_eq!(ctx.sqrt(&BigFloat::ZERO, 100), BigFloat::ZERO);
+		assert_eq!(ctx.div(&BigFloat::ZERO, &x, 100), BigFloat::ZERO);
+	}
+
+	#[test]
+	fn test_ctx_cache() {
+		let mut ctx = BigFloatCtx::new();
+		assert_eq!(ctx.pi(100), BigFloat::pi(100));
+		assert_eq!(ctx.pi(100), BigFloat::pi(100));
+		// A different precision replaces the cached value
+		assert_eq!(ctx.pi(50), BigFloat::pi(50));
+		assert_eq!(ctx.pi, Some((50, BigFloat::pi(50))));
+		assert_eq!(ctx.pi(100), BigFloat::pi(100));
+		assert_eq!(ctx.ln2(80), BigFloat::ln2(80));
+		assert_eq!(ctx.ln2, Some((80, BigFloat::ln2(80))));
+
+		// Repeated evaluation
+		let x = BigFloat::from(10);
+		let expected = x.ln(500);
+		for _ in 0..3 {
+			assert_eq!(ctx.ln(&x, 500), expected);
+		}
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_ctx_div_by_zero() {
+		BigFloatCtx::new().div(&BigFloat::ONE, &BigFloat::ZERO, 10);
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_ctx_sqrt_negative() {
+		BigFloatCtx::new().sqrt(&BigFloat::NEG_ONE, 10);
+	}
+}
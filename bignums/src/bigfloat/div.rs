@@ -1,22 +1,114 @@
 use core::cmp::Ordering;
+use core::mem;
 use core::ops::Div;
 
-use crate::bigfloat::BigFloat;
+use crate::bigfloat::ctx::Scratch;
+use crate::bigfloat::{BigFloat, BigFloatCtx};
 use crate::bigint::BigInt;
+use crate::biguint::DivRem;
+use crate::error::DivByZeroError;
+use crate::rational::Rational;
 
 impl BigFloat {
 	/// Divides self by rhs. Absolute error < 2^-prec.
+	/// Exact if rhs is a power of two, or if the quotient terminates in binary
+	/// without being much longer than the rounded result.
+	/// # Panics
+	/// If rhs is zero
 	pub fn div(&self, rhs: &BigFloat, prec: i64) -> BigFloat {
-		let r = rhs.reciprocal(prec + self.ilog2() + 1);
+		self.try_div(rhs, prec).expect("Cannot divide by zero")
+	}
+
+	/// Same as div, but returns an error instead of panicking if rhs is zero
+	pub fn try_div(&self, rhs: &BigFloat, prec: i64) -> Result<BigFloat, DivByZeroError> {
+		if rhs.is_zero() {
+			return Err(DivByZeroError);
+		}
+		Ok(self.div_with(rhs, prec, &mut BigFloatCtx::new()))
+	}
+
+	/// div for a non-zero rhs, with the buffers of ctx
+	pub(crate) fn div_with(&self, rhs: &BigFloat, prec: i64, ctx: &mut BigFloatCtx) -> BigFloat {
+		debug_assert!(!rhs.is_zero());
+		if self.is_zero() {
+			return BigFloat::ZERO;
+		}
+
+		if let Some(k) = rhs.ilog2_exact() {
+			let res = self.mul_pow2(-k);
+			return if rhs.is_negative() { -res } else { res };
+		}
+
+		// The mantissas are odd, so the quotient terminates in binary iff rhs.m divides self.m.
+		// Only worth checking if the exact quotient isn't longer than the rounded result
+		let q_bits = self.m.magnitude.ilog2() as i64 - rhs.m.magnitude.ilog2() as i64;
+		let res_bits = prec.saturating_add(self.ilog2() - rhs.ilog2()).max(0);
+		if q_bits >= 0 && q_bits <= res_bits.saturating_add(64) {
+			let (q, r) = (&mut self.m.clone()).div_rem(&mut rhs.m.clone());
+			if r.is_zero() {
+				return BigFloat::from_mantissa_exponent(q, self.e.strict_sub(rhs.e));
+			}
+		}
+
+		let r = rhs.reciprocal_with(prec + self.ilog2() + 1, ctx);
 		self.mul_with_precision(&r, prec + 1)
 	}
 
+	/// Divides self by an integer with a single integer division. Absolute error < 2^-prec.
+	/// # Panics
+	/// If rhs is zero
+	pub fn div_by_int(&self, rhs: &BigInt, prec: i64) -> BigFloat {
+		assert!(!rhs.is_zero(), "Cannot divide by zero");
+		if self.is_zero() {
+			return BigFloat::ZERO;
+		}
+
+		// Truncating m * 2^(e + prec + 1) / rhs is off by less than 2^-(prec + 1)
+		let shift = self.e.strict_add(prec).strict_add(1);
+		let mut n = self.m.clone();
+		let mut d = rhs.clone();
+		if shift >= 0 {
+			n <<= shift as u64;
+		} else if shift.unsigned_abs() > self.m.magnitude.ilog2() {
+			// |m| < 2^-shift <= |d| * 2^-shift, so the quotient truncates to zero
+			return BigFloat::ZERO;
+		} else {
+			d <<= shift.unsigned_abs();
+		}
+		let q = &mut n / &mut d;
+		BigFloat::from_mantissa_exponent(q, -prec - 1)
+	}
+
+	/// Multiplies self by a rational. Absolute error < 2^-prec.
+	pub fn mul_rational(&self, rhs: &Rational, prec: i64) -> BigFloat {
+		(self * rhs.numerator()).div_by_int(&BigInt::from(rhs.denominator().clone()), prec)
+	}
+
+	/// Divides self by a rational. Absolute error < 2^-prec.
+	/// # Panics
+	/// If rhs is zero
+	pub fn div_rational(&self, rhs: &Rational, prec: i64) -> BigFloat {
+		(self * rhs.denominator()).div_by_int(rhs.numerator(), prec)
+	}
+
 	/// Divides 1 by self. Absolute error < 2^-prec.
+	/// # Panics
+	/// If self is zero
 	pub fn reciprocal(&self, prec: i64) -> BigFloat {
+		self.try_reciprocal(prec).expect("Cannot divide by zero")
+	}
+
+	/// Same as reciprocal, but returns an error instead of panicking if self is zero
+	pub fn try_reciprocal(&self, prec: i64) -> Result<BigFloat, DivByZeroError> {
 		if self.is_zero() {
-			panic!("Cannot divide by zero");
+			return Err(DivByZeroError);
 		}
+		Ok(self.reciprocal_with(prec, &mut BigFloatCtx::new()))
+	}
 
+	/// reciprocal for a non-zero self, with the buffers of ctx
+	pub(crate) fn reciprocal_with(&self, prec: i64, ctx: &mut BigFloatCtx) -> BigFloat {
+		debug_assert!(!self.is_zero());
 		if let Some(_) = self.ilog2_exact() {
 			return BigFloat::from_mantissa_exponent(
 				if self.is_negative() {
@@ -41,7 +133,9 @@ impl BigFloat {
 		// finitely many iterations, imprecise arithmetic and rounding the final result.
 		let actual_prec = prec + 2;
 
-		let log_epsilon = (&BigFloat::from(1) - &(self * &est)).ilog2() + 1;
+		let Some(log_epsilon) = self.reciprocal_log_error(&est) else {
+			return est;
+		};
 		debug_assert!(log_epsilon < 0, "bad estimate for reciprocal");
 		let target_log_epsilon = self.ilog2() - actual_prec;
 		if log_epsilon < target_log_epsilon {
@@ -54,19 +148,31 @@ impl BigFloat {
 		let log_s = self.ilog2();
 
 		let mut x = est;
-		// Hopefully enough... 🙏
+		// Rounding errors of n iterations, with some slack for the estimate of n
 		let working_prec = actual_prec + n + i64::max(0, x.ilog2()) + 16;
-
-		loop {
+		let max_iterations = Self::max_iterations(working_prec);
+		let Scratch {
+			a: prod,
+			b: delta,
+			c: diff,
+		} = &mut ctx.reciprocal;
+
+		for iteration in 0.. {
 			// x_n+1 = x_n * (2 - s * x_n)
-			let prod = self.mul_with_precision(&x, working_prec);
-
-			let delta = BigFloat::from(1).sub_with_precision(&prod, working_prec);
-
-			let diff = BigFloat::from(2).sub_with_precision(&prod, working_prec);
-			x = x.mul_with_precision(&diff, working_prec);
-
-			if delta.is_zero() || delta.ilog2() <= -actual_prec + log_s - 1 {
+			prod.mul_to(self, &x);
+			prod.round_to_precision(working_prec);
+
+			// prod is already rounded, so these are exact and cheap
+			prod.neg_in_place();
+			delta.add_to(&BigFloat::ONE, prod);
+			diff.add_to(delta, &BigFloat::ONE);
+			prod.mul_to(&x, diff);
+			prod.round_to_precision(working_prec);
+			mem::swap(&mut x, prod);
+
+			let converged = delta.is_zero() || delta.ilog2() <= -actual_prec + log_s - 1;
+			if converged || iteration == max_iterations {
+				debug_assert!(converged, "reciprocal did not converge");
 				break;
 			}
 		}
@@ -75,6 +181,13 @@ impl BigFloat {
 		x
 	}
 
+	/// Returns floor(log2(|1 - self * est|)) + 1, or None if est is the exact reciprocal.
+	/// Both the product and the difference are exact, even though they cancel.
+	fn reciprocal_log_error(&self, est: &BigFloat) -> Option<i64> {
+		let delta = &BigFloat::ONE - &(self * est);
+		Some(delta.checked_ilog2()? + 1)
+	}
+
 	pub fn div_int(&self, rhs: &BigFloat) -> BigInt {
 		if rhs.is_zero() {
 			panic!("Cannot divide by zero");
@@ -103,6 +216,23 @@ impl BigFloat {
 		(q, self - &n)
 	}
 
+	/// Remainder of truncated division, which has the sign of self and is less than rhs in magnitude.
+	/// The result is exact.
+	pub fn rem(&self, rhs: &BigFloat) -> BigFloat {
+		self.div_rem(rhs).1
+	}
+
+	/// Remainder of euclidean division, which is non-negative and less than |rhs|.
+	/// The result is exact.
+	pub fn rem_euclid(&self, rhs: &BigFloat) -> BigFloat {
+		let r = self.rem(rhs);
+		if r.is_negative() {
+			&r + &rhs.clone().abs()
+		} else {
+			r
+		}
+	}
+
 	pub fn div_floor(&self, rhs: &BigFloat) -> BigInt {
 		if rhs.is_zero() {
 			panic!("Cannot divide by zero");
@@ -148,13 +278,32 @@ impl BigFloat {
 	}
 }
 
+/// Divides with 64 more significant bits than self has, so the relative error is < 2^-64.
+/// Use div_by_int for a given precision.
+/// # Panics
+/// If rhs is zero
+impl Div<&BigInt> for &BigFloat {
+	type Output = BigFloat;
+
+	fn div(self, rhs: &BigInt) -> Self::Output {
+		assert!(!rhs.is_zero(), "Cannot divide by zero");
+		if self.is_zero() {
+			return BigFloat::ZERO;
+		}
+		// The quotient is at least 2^(ilog2(self) - ilog2(rhs) - 1)
+		let q_log2 = self.ilog2() - rhs.magnitude.ilog2() as i64 - 1;
+		let bits = self.m.magnitude.ilog2() as i64 + 1;
+		let prec = bits + 64 - q_log2;
+		self.div_by_int(rhs, prec)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use core::str::FromStr;
 
 	use super::*;
 	use crate::biguint::BigUInt;
-	use crate::rational::Rational;
 
 	#[test]
 	fn test_reciprocal() {
@@ -245,6 +394,170 @@ mod tests {
 		assert!(delta < epsilon, "|{q} - {q_rat}| = {delta} > {epsilon}")
 	}
 
+	#[test]
+	fn test_div_pow2_is_exact() {
+		let x = BigFloat::from_str("123456789.123456789").unwrap();
+		assert_eq!(
+			x.div(&BigFloat::from(4), 0),
+			BigFloat::from_mantissa_exponent(x.m.clone(), x.e - 2)
+		);
+		// Precision doesn't matter, nothing is rounded away
+		for prec in [-100, 0, 10, 1000] {
+			assert_eq!(
+				x.div(&BigFloat::from(4), prec).to_rational(),
+				&x.to_rational() / 4
+			);
+		}
+
+		let x = BigFloat::from(-3);
+		assert_eq!(
+			x.div(&BigFloat::from(-8), 1),
+			BigFloat::from_mantissa_exponent(BigInt::from(3), -3)
+		);
+		let half = BigFloat::from_mantissa_exponent(BigInt::ONE, -1);
+		assert_eq!(x.div(&half, 1), BigFloat::from(-6));
+		assert_eq!(x.div(&-half, 1), BigFloat::from(6));
+		assert_eq!(
+			x.div(&BigFloat::from_mantissa_exponent(BigInt::ONE, 1000), 1),
+			BigFloat::from_mantissa_exponent(BigInt::from(-3), -1000)
+		);
+		assert!(BigFloat::ZERO.div(&BigFloat::from(2), 10).is_zero());
+	}
+
+	#[test]
+	fn test_div_terminating_is_exact() {
+		let f = |x: f64| BigFloat::try_from(x).unwrap();
+		assert_eq!(
+			BigFloat::from(6).div(&BigFloat::from(2), 0),
+			BigFloat::from(3)
+		);
+		assert_eq!(BigFloat::ONE.div(&BigFloat::from(4), 0), f(0.25));
+		assert_eq!(
+			BigFloat::from(6).div(&BigFloat::from(3), 0),
+			BigFloat::from(2)
+		);
+		assert_eq!(
+			BigFloat::from(-45).div(&f(0.375), -10),
+			BigFloat::from(-120)
+		);
+		assert_eq!(f(2.625).div(&f(-1.75), 0), f(-1.5));
+		assert_eq!(
+			BigFloat::from(21).div(&BigFloat::from(7 << 20), 0),
+			f(3.0 / 1048576.0)
+		);
+
+		// Exact quotients with many bits
+		let a = BigFloat::from(BigInt::from(3).pow(200));
+		let b = BigFloat::from(BigInt::from(3).pow(120)) >> 7u32;
+		let q = BigFloat::from(BigInt::from(3).pow(80)) << 7u32;
+		assert_eq!(a.div(&b, 0), q);
+		assert_eq!(a.div(&b, 1000), q);
+
+		// Non-terminating quotients are still rounded
+		let third = BigFloat::ONE.div(&BigFloat::from(3), 10);
+		assert!(third.exponent() >= -11);
+		let q = BigFloat::from(10).div(&BigFloat::from(6), 100);
+		assert!(
+			(&(&q * &BigFloat::from(6)) - &BigFloat::from(10)).abs() < (BigFloat::ONE >> 97u32)
+		);
+	}
+
+	#[test]
+	fn test_div_by_int() {
+		let values = [
+			BigFloat::from(1),
+			BigFloat::from(-7),
+			BigFloat::from_str("123.456").unwrap(),
+			BigFloat::from_str("-0.000000000001").unwrap(),
+			BigFloat::from_mantissa_exponent(BigInt::from(u128::MAX), 300),
+			BigFloat::from_mantissa_exponent(BigInt::from(-3), -500),
+		];
+		let divisors = [1i128, -1, 2, 3, -10, 12345678901234567890, -(1 << 100)];
+		for x in &values {
+			for &d in &divisors {
+				let d = BigInt::from(d);
+				for prec in [-20, 0, 64, 300] {
+					let q = x.div_by_int(&d, prec);
+					let expected = &x.to_rational() / &Rational::from(d.clone());
+					let delta = (&q.to_rational() - &expected).abs();
+					let epsilon = BigFloat::ONE.mul_pow2(-prec).to_rational();
+					assert!(delta < epsilon, "{x} / {d} at {prec}");
+				}
+			}
+		}
+
+		// Multiplying by an integer is exact, dividing gives back the original up to 2^-prec
+		let x = BigFloat::from_str("3.14159").unwrap();
+		let n = BigInt::from(10).pow(30);
+		let product = &x * &n;
+		assert_eq!(
+			product.to_rational(),
+			&x.to_rational() * &Rational::from(n.clone())
+		);
+		let back = product.div_by_int(&n, 100);
+		assert!((&back - &x).abs() < (BigFloat::ONE >> 100));
+
+		assert_eq!(
+			BigFloat::from(12).div_by_int(&BigInt::from(-4), 0),
+			BigFloat::from(-3)
+		);
+		assert_eq!(
+			BigFloat::ZERO.div_by_int(&BigInt::from(7), 10),
+			BigFloat::ZERO
+		);
+		assert!(std::panic::catch_unwind(|| BigFloat::ONE.div_by_int(&BigInt::ZERO, 10)).is_err());
+
+		// Far below the precision, without shifting the divisor by the exponent
+		let tiny = BigFloat::from_mantissa_exponent(BigInt::from(5), -(1 << 60));
+		assert_eq!(tiny.div_by_int(&BigInt::from(3), 100), BigFloat::ZERO);
+	}
+
+	#[test]
+	fn test_div_bigint_op() {
+		let x = BigFloat::from_str("-2.5").unwrap();
+		assert_eq!(&x / &BigInt::from(5), BigFloat::from_str("-0.5").unwrap());
+
+		// 64 more significant bits than x has
+		let values = [
+			BigFloat::ONE,
+			x,
+			BigFloat::from_mantissa_exponent(BigInt::from(u128::MAX), -300),
+		];
+		for x in &values {
+			for d in [3i128, -7, 12345678901234567890] {
+				let d = BigInt::from(d);
+				let q = x / &d;
+				let expected = &x.to_rational() / &Rational::from(d.clone());
+				let delta = (&q.to_rational() - &expected).abs();
+				let bits = x.m.magnitude.ilog2() as i64 + 1;
+				let epsilon = &expected.abs() * &BigFloat::ONE.mul_pow2(-bits - 64).to_rational();
+				assert!(delta < epsilon, "{x} / {d}");
+			}
+		}
+		assert!(std::panic::catch_unwind(|| &BigFloat::ONE / &BigInt::ZERO).is_err());
+	}
+
+	#[test]
+	fn test_mul_div_rational() {
+		let x = BigFloat::from_str("-2.5").unwrap();
+		let q = Rational::new(BigInt::from(2), BigUInt::from(3u32));
+		let prec = 128;
+		let epsilon = BigFloat::ONE >> prec;
+
+		let expected = Rational::new(BigInt::from(-5), BigUInt::from(3u32));
+		assert!(
+			(&x.mul_rational(&q, prec).to_rational() - &expected).abs() < epsilon.to_rational()
+		);
+		let expected = Rational::new(BigInt::from(-15), BigUInt::from(4u32));
+		assert_eq!(x.div_rational(&q, prec), expected);
+
+		let third = BigFloat::ONE.div_rational(&Rational::from(3), prec);
+		assert!(
+			(&third.mul_rational(&Rational::from(3), prec) - &BigFloat::ONE).abs() < &epsilon * 2
+		);
+		assert!(std::panic::catch_unwind(|| x.div_rational(&Rational::ZERO, prec)).is_err());
+	}
+
 	#[test]
 	fn test_div_int() {
 		// Test positive numbers
@@ -283,4 +596,101 @@ mod tests {
 		};
 		assert_eq!(q, expected);
 	}
+
+	#[test]
+	fn test_rem() {
+		let f = |x: f64| BigFloat::try_from(x).unwrap();
+		assert_eq!(f(5.5).rem(&f(2.0)), f(1.5));
+		assert_eq!(f(-5.5).rem(&f(2.0)), f(-1.5));
+		assert_eq!(f(5.5).rem(&f(-2.0)), f(1.5));
+		assert_eq!(f(-5.5).rem(&f(-2.0)), f(-1.5));
+		assert_eq!(f(6.0).rem(&f(1.5)), BigFloat::ZERO);
+		assert_eq!(f(0.75).rem(&f(2.0)), f(0.75));
+		assert_eq!(f(1.0).rem(&f(0.375)), f(0.25));
+		assert_eq!(BigFloat::ZERO.rem(&f(3.0)), BigFloat::ZERO);
+
+		// Far apart exponents
+		let big = BigFloat::from_mantissa_exponent(BigInt::from(3), 200);
+		let small = BigFloat::from_mantissa_exponent(BigInt::from(5), -100);
+		let r = big.rem(&small);
+		assert!(!r.is_negative() && r < small);
+		assert_eq!((&big - &r).div_rem(&small).1, BigFloat::ZERO);
+	}
+
+	#[test]
+	fn test_rem_euclid() {
+		let f = |x: f64| BigFloat::try_from(x).unwrap();
+		assert_eq!(f(5.5).rem_euclid(&f(2.0)), f(1.5));
+		assert_eq!(f(-5.5).rem_euclid(&f(2.0)), f(0.5));
+		assert_eq!(f(-5.5).rem_euclid(&f(-2.0)), f(0.5));
+		assert_eq!(f(5.5).rem_euclid(&f(-2.0)), f(1.5));
+		assert_eq!(f(-6.0).rem_euclid(&f(1.5)), BigFloat::ZERO);
+		assert_eq!(f(-0.25).rem_euclid(&f(3.0)), f(2.75));
+	}
+
+	#[test]
+	fn test_try_div() {
+		let three = BigFloat::from(3);
+		assert_eq!(three.try_div(&BigFloat::ZERO, 64), Err(DivByZeroError));
+		assert_eq!(
+			BigFloat::ZERO.try_div(&BigFloat::ZERO, 64),
+			Err(DivByZeroError)
+		);
+		assert_eq!(BigFloat::ZERO.try_div(&three, 64), Ok(BigFloat::ZERO));
+		assert_eq!(
+			three.try_div(&BigFloat::from(-4), 64),
+			Ok(BigFloat::try_from(-0.75).unwrap())
+		);
+		assert_eq!(
+			BigFloat::ONE.try_div(&three, 128),
+			Ok(BigFloat::ONE.div(&three, 128))
+		);
+
+		assert_eq!(BigFloat::ZERO.try_reciprocal(64), Err(DivByZeroError));
+		assert_eq!(
+			BigFloat::from(-8).try_reciprocal(64),
+			Ok(BigFloat::try_from(-0.125).unwrap())
+		);
+		assert_eq!(three.try_reciprocal(128), Ok(three.reciprocal(128)));
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_rem_by_zero() {
+		let _ = BigFloat::ONE.rem(&BigFloat::ZERO);
+	}
+
+	#[test]
+	fn test_reciprocal_log_error() {
+		// 1 - 3 * 341/1024 = 2^-10
+		let est = BigFloat::from_mantissa_exponent(BigInt::from(341), -10);
+		assert_eq!(BigFloat::from(3).reciprocal_log_error(&est), Some(-9));
+
+		// 1 - (2^200 + 1) * 2^-200 = -2^-200, lost entirely if the difference was rounded
+		let x = BigFloat::from((BigUInt::ONE << 200u32) + 1u32);
+		let est = BigFloat::from_mantissa_exponent(BigInt::ONE, -200);
+		assert_eq!(x.reciprocal_log_error(&est), Some(-199));
+
+		// 1 - 5 * (2^-100 * (2^100 / 5 rounded down)) = 2^-100
+		let x = BigFloat::from(5);
+		let m = &mut (BigInt::ONE << 100u32) / &mut BigInt::from(5);
+		let est = BigFloat::from_mantissa_exponent(m, -100);
+		assert_eq!(x.reciprocal_log_error(&est), Some(-99));
+		assert_eq!(
+			x.reciprocal_log_error(&BigFloat::try_from(0.2).unwrap()),
+			Some(-53)
+		);
+
+		let est = BigFloat::from_mantissa_exponent(BigInt::ONE, -3);
+		assert_eq!(BigFloat::from(8).reciprocal_log_error(&est), None);
+	}
+
+	#[test]
+	fn test_sub_is_exact() {
+		// The error estimates rely on differences of close values not being rounded
+		let a = BigFloat::from(BigUInt::ONE << 500u32);
+		let b = &a - &BigFloat::from_mantissa_exponent(BigInt::ONE, -500);
+		assert_eq!(&a - &b, BigFloat::from_mantissa_exponent(BigInt::ONE, -500));
+		assert_eq!((&b - &a).ilog2(), -500);
+	}
 }
@@ -1,4 +1,4 @@
-use crate::bigfloat::BigFloat;
+use crate::bigfloat::{BigFloat, BigFloatCtx, TranscendentalOptions};
 
 impl BigFloat {
 	/// Returns log2(|self|) if self is a power of 2, otherwise None.
@@ -18,7 +18,47 @@ impl BigFloat {
 		self.m.magnitude.ilog2() as i64 + self.e
 	}
 
+	/// Returns floor(log2(|self|)), or None for 0.
+	pub fn checked_ilog2(&self) -> Option<i64> {
+		Some(self.m.magnitude.checked_ilog2()? as i64 + self.e)
+	}
+
+	/// Returns ceil(log2(|self|)).
+	/// Panics for 0.
+	pub fn ceil_ilog2(&self) -> i64 {
+		self.checked_ceil_ilog2().expect("ceil_ilog2 of zero")
+	}
+
+	/// Returns ceil(log2(|self|)), or None for 0.
+	pub fn checked_ceil_ilog2(&self) -> Option<i64> {
+		let floor = self.checked_ilog2()?;
+		// The mantissa is odd, so self is a power of 2 iff it is 1
+		Some(if self.m.magnitude.is_one() {
+			floor
+		} else {
+			floor + 1
+		})
+	}
+
 	pub fn ln(&self, prec: i64) -> BigFloat {
+		self.ln_opts(prec, TranscendentalOptions::default())
+	}
+
+	/// Same as ln, with the given working precision settings.
+	/// The iteration cap applies to the AGM.
+	/// # Panics
+	/// If self is not positive
+	pub fn ln_opts(&self, prec: i64, opts: TranscendentalOptions) -> BigFloat {
+		self.ln_with(prec, opts, &mut BigFloatCtx::new())
+	}
+
+	/// ln_opts with the buffers and cached constants of ctx
+	pub(crate) fn ln_with(
+		&self,
+		prec: i64,
+		opts: TranscendentalOptions,
+		ctx: &mut BigFloatCtx,
+	) -> BigFloat {
 		if self.is_negative() {
 			panic!("ln(negative)");
 		}
@@ -31,17 +71,17 @@ impl BigFloat {
 
 		// ln(x) = π / (2 * AGM(1, 4/x)) - shift * ln(2)
 		let actual_prec = i64::max(prec + 2, -7);
-		let working_prec = actual_prec * 2 + 16;
+		let working_prec = actual_prec * 2 + 16 + opts.extra_guard_bits;
 
 		let mut x = self.clone();
 		let shift = 5 + actual_prec / 2 + (actual_prec + 8).ilog2() as i64 - x.ilog2();
 		x <<= shift;
 
-		let pi = BigFloat::pi(working_prec);
-		let four_over_x = x.reciprocal(working_prec) << 2u32;
-		let agm = BigFloat::agm(&BigFloat::ONE, &four_over_x, working_prec);
-		let ln_x = pi.div(&agm, working_prec) >> 1u32;
-		let ln2 = BigFloat::ln2(working_prec + shift.abs().max(1).ilog2() as i64);
+		let pi = ctx.pi(working_prec);
+		let four_over_x = x.reciprocal_with(working_prec, ctx) << 2u32;
+		let agm = BigFloat::ONE.agm_with(&four_over_x, working_prec, opts, ctx);
+		let ln_x = pi.div_with(&agm, working_prec, ctx) >> 1u32;
+		let ln2 = ctx.ln2(working_prec + shift.abs().max(1).ilog2() as i64);
 		let ln2_times_shift = ln2.mul_with_precision(&BigFloat::from(shift), working_prec);
 		let mut res = ln_x.sub_with_precision(&ln2_times_shift, working_prec);
 
@@ -53,8 +93,47 @@ impl BigFloat {
 #[cfg(test)]
 mod tests {
 	use core::ops::Sub;
+	use core::str::FromStr;
 
 	use super::*;
+	use crate::util::check_transcendental_opts;
+
+	#[test]
+	fn test_ilog2() {
+		let cases = [
+			("1", 0, 0),
+			("2", 1, 1),
+			("3", 1, 2),
+			("-3", 1, 2),
+			("4", 2, 2),
+			("5", 2, 3),
+			("0.5", -1, -1),
+			("-0.5", -1, -1),
+			("0.75", -1, 0),
+			("0.3", -2, -1),
+			("1024", 10, 10),
+			("1025", 10, 11),
+			("-1023", 9, 10),
+		];
+		for (src, floor, ceil) in cases {
+			let x = BigFloat::from_str(src).unwrap();
+			assert_eq!(x.ilog2(), floor, "{src}");
+			assert_eq!(x.checked_ilog2(), Some(floor), "{src}");
+			assert_eq!(x.ceil_ilog2(), ceil, "{src}");
+			assert_eq!(x.checked_ceil_ilog2(), Some(ceil), "{src}");
+		}
+
+		let pow2 = BigFloat::ONE.mul_pow2(-1000);
+		assert_eq!(pow2.checked_ceil_ilog2(), Some(-1000));
+		let above = &pow2 + &BigFloat::ONE.mul_pow2(-2000);
+		assert_eq!(above.checked_ilog2(), Some(-1000));
+		assert_eq!(above.checked_ceil_ilog2(), Some(-999));
+
+		assert_eq!(BigFloat::ZERO.checked_ilog2(), None);
+		assert_eq!(BigFloat::ZERO.checked_ceil_ilog2(), None);
+		assert_eq!(BigFloat::ZERO.ilog2_exact(), None);
+		assert!(std::panic::catch_unwind(|| BigFloat::ZERO.ceil_ilog2()).is_err());
+	}
 
 	#[test]
 	fn test_ln() {
@@ -107,4 +186,14 @@ mod tests {
 		print!("expected: {expected}\nactual: {actual}\ndelta: {delta}\nepsilon: {epsilon}\n\n");
 		assert!(delta < epsilon);
 	}
+
+	#[test]
+	fn test_ln_opts() {
+		check_transcendental_opts(
+			"ln",
+			&["2", "0.001", "12345.678"],
+			BigFloat::ln,
+			BigFloat::ln_opts,
+		);
+	}
 }
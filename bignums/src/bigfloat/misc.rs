@@ -1,7 +1,27 @@
-use crate::bigfloat::BigFloat;
+use core::mem;
+
+use crate::bigfloat::ctx::Scratch;
+use crate::bigfloat::{BigFloat, BigFloatCtx, TranscendentalOptions};
 
 impl BigFloat {
 	pub fn agm(&self, other: &BigFloat, prec: i64) -> BigFloat {
+		self.agm_with(
+			other,
+			prec,
+			TranscendentalOptions::default(),
+			&mut BigFloatCtx::new(),
+		)
+	}
+
+	/// agm, with the iteration cap of opts and the buffers of ctx.
+	/// The guard bits are left to the caller.
+	pub(crate) fn agm_with(
+		&self,
+		other: &BigFloat,
+		prec: i64,
+		opts: TranscendentalOptions,
+		ctx: &mut BigFloatCtx,
+	) -> BigFloat {
 		if self.is_negative() || other.is_negative() {
 			panic!("agm() only works for positive numbers");
 		}
@@ -11,27 +31,108 @@ impl BigFloat {
 		}
 
 		let actual_prec = i64::max(prec + 2, 1);
+		// Large inputs scale up the rounding errors, and the square root of a small product
+		// magnifies its absolute error, so both ends need extra bits
+		let log_max = self.ilog2().max(other.ilog2());
+		let log_min = self.ilog2().min(other.ilog2());
 		let working_prec =
-			actual_prec + actual_prec.ilog2() as i64 + self.ilog2().max(other.ilog2());
+			actual_prec + actual_prec.ilog2() as i64 + log_max.max(0) + (-log_min).max(0) + 4;
 
+		let sqrt_opts = TranscendentalOptions::default();
 		let mut a = self.add_with_precision(other, working_prec) >> 1u32;
 		let mut b = self
 			.mul_with_precision(other, working_prec)
-			.sqrt(working_prec);
-
-		loop {
-			let tmp = a.add_with_precision(&b, working_prec) >> 1;
-			b = a.mul_with_precision(&b, working_prec).sqrt(working_prec);
-			a = tmp;
-			let delta = a.sub_with_precision(&b, working_prec);
-			if delta.is_zero() || delta.ilog2() + 1 < -actual_prec {
+			.sqrt_with(working_prec, sqrt_opts, ctx)
+			.0;
+
+		// Taken out of ctx, which the square roots need as well
+		let mut scratch = mem::take(&mut ctx.agm);
+		let Scratch {
+			a: sum,
+			b: prod,
+			c: delta,
+		} = &mut scratch;
+
+		let max_iterations = opts.iteration_limit(working_prec);
+		for iteration in 1.. {
+			sum.add_to(&a, &b);
+			sum.round_to_precision(working_prec);
+			*sum >>= 1;
+			prod.mul_to(&a, &b);
+			prod.round_to_precision(working_prec);
+			b = prod.sqrt_with(working_prec, sqrt_opts, ctx).0;
+			mem::swap(&mut a, sum);
+
+			// a and b are already rounded, so the difference is exact
+			b.neg_in_place();
+			delta.add_to(&a, &b);
+			b.neg_in_place();
+			let converged = delta.is_zero() || delta.ilog2() + 1 < -actual_prec;
+			if converged || iteration >= max_iterations {
+				debug_assert!(
+					converged || opts.max_iterations.is_some(),
+					"agm did not converge"
+				);
 				break;
 			}
 		}
 
+		ctx.agm = scratch;
 		b.round_to_precision(actual_prec);
 		b
 	}
+
+	/// Complete elliptic integral of the first kind K(k) = pi / (2 * agm(1, sqrt(1 - k^2))),
+	/// for the modulus k (not the parameter m = k^2). Absolute error < 2^-prec.
+	/// # Panics
+	/// If |k| >= 1
+	pub fn ellipk(k: &BigFloat, prec: i64) -> BigFloat {
+		// Exact
+		let k_prime_sq = &BigFloat::ONE - &(k * k);
+		if k_prime_sq <= BigFloat::ZERO {
+			panic!("ellipk() only works for |k| < 1");
+		}
+
+		// With k'^2 ~ 2^-m, an error in k' is magnified about 2^(m / 2) times by the agm,
+		// and K ~ ln(4 / k') grows like m, which the division by the agm magnifies again
+		let m = (-k_prime_sq.ilog2()).max(0);
+		let working_prec = prec + m / 2 + 2 * (m + 2).ilog2() as i64 + 16;
+
+		let k_prime = k_prime_sq.sqrt(working_prec);
+		let agm = BigFloat::ONE.agm(&k_prime, working_prec);
+		let mut res = BigFloat::pi(working_prec).div(&(agm << 1u32), working_prec);
+		res.round_to_precision(prec);
+		res
+	}
+
+	/// Linear interpolation a + (b - a) * t, rounded once to absolute error < 2^-prec.
+	/// Gives a for t = 0 and b for t = 1, up to that rounding.
+	pub fn lerp(a: &BigFloat, b: &BigFloat, t: &BigFloat, prec: i64) -> BigFloat {
+		// Exact until the final rounding
+		let mut res = a + &(&(b - a) * t);
+		res.round_to_precision(prec);
+		res
+	}
+
+	/// Hermite interpolation 3t^2 - 2t^3 of t = (x - edge0) / (edge1 - edge0) clamped to [0, 1].
+	/// Absolute error < 2^-prec.
+	/// # Panics
+	/// If edge0 == edge1
+	pub fn smoothstep(edge0: &BigFloat, edge1: &BigFloat, x: &BigFloat, prec: i64) -> BigFloat {
+		// The derivative is at most 3/2, so an error of 2^-(prec + 3) in t
+		// and the final rounding together stay below 2^-prec
+		let t = (x - edge0).div(&(edge1 - edge0), prec + 3);
+		if t <= BigFloat::ZERO {
+			return BigFloat::ZERO;
+		}
+		if t >= BigFloat::ONE {
+			return BigFloat::ONE;
+		}
+		let three_minus_2t = &BigFloat::from(3) - &(t.clone() << 1u32);
+		let mut res = &(&t * &t) * &three_minus_2t;
+		res.round_to_precision(prec);
+		res
+	}
 }
 
 #[cfg(test)]
@@ -74,6 +175,101 @@ mod tests {
 		test_agm_helper(x, y, expected, 1000);
 	}
 
+	#[test]
+	fn test_agm_small_inputs() {
+		// agm(c * x, c * y) = c * agm(x, y)
+		let prec = 300;
+		let expected = BigFloat::ONE.agm(&BigFloat::from(2), prec + 200) >> 200u32;
+		let x = BigFloat::ONE >> 200u32;
+		let y = BigFloat::ONE >> 199u32;
+		let actual = x.agm(&y, prec);
+		assert!((&actual - &expected).abs() < (BigFloat::ONE >> prec));
+	}
+
+	#[test]
+	fn test_lerp() {
+		let f = |x: f64| BigFloat::try_from(x).unwrap();
+		let (a, b) = (f(-1.5), f(6.25));
+		assert_eq!(BigFloat::lerp(&a, &b, &BigFloat::ZERO, 64), a);
+		assert_eq!(BigFloat::lerp(&a, &b, &BigFloat::ONE, 64), b);
+		assert_eq!(BigFloat::lerp(&a, &b, &f(0.5), 64), f(2.375));
+		assert_eq!(BigFloat::lerp(&a, &b, &f(2.0), 64), f(14.0));
+		assert_eq!(BigFloat::lerp(&a, &b, &f(-1.0), 64), f(-9.25));
+		assert_eq!(BigFloat::lerp(&b, &a, &f(0.5), 64), f(2.375));
+
+		// Rounded once, at the end
+		let third = BigFloat::ONE.div(&BigFloat::from(3), 200);
+		let res = BigFloat::lerp(&BigFloat::ZERO, &BigFloat::from(3), &third, 100);
+		assert!((&res - &BigFloat::ONE).abs() < (BigFloat::ONE >> 100u32));
+		assert!(res.exponent() >= -100);
+	}
+
+	#[test]
+	fn test_smoothstep() {
+		let f = |x: f64| BigFloat::try_from(x).unwrap();
+		let (e0, e1) = (f(2.0), f(4.0));
+		let smoothstep = |x: f64| BigFloat::smoothstep(&e0, &e1, &f(x), 64);
+		assert_eq!(smoothstep(2.0), BigFloat::ZERO);
+		assert_eq!(smoothstep(-100.0), BigFloat::ZERO);
+		assert_eq!(smoothstep(4.0), BigFloat::ONE);
+		assert_eq!(smoothstep(1e10), BigFloat::ONE);
+		assert_eq!(smoothstep(3.0), f(0.5));
+		// t = 1/4: 3/16 - 2/64
+		assert_eq!(smoothstep(2.5), f(0.15625));
+		assert_eq!(smoothstep(3.5), f(0.84375));
+
+		// Reversed edges mirror the curve
+		assert_eq!(BigFloat::smoothstep(&e1, &e0, &f(2.5), 64), f(0.84375));
+
+		// t = 1/3 gives 7/27
+		let prec = 200;
+		let res = BigFloat::smoothstep(&BigFloat::ZERO, &BigFloat::from(3), &BigFloat::ONE, prec);
+		let expected = BigFloat::from(7).div(&BigFloat::from(27), prec + 10);
+		assert!((&res - &expected).abs() < (BigFloat::ONE >> prec));
+	}
+
+	#[test]
+	fn test_ellipk() {
+		let prec = 300;
+		let epsilon = BigFloat::ONE >> prec;
+
+		let half_pi = BigFloat::pi(prec + 10) >> 1u32;
+		let res = BigFloat::ellipk(&BigFloat::ZERO, prec);
+		assert!((&res - &half_pi).abs() < epsilon);
+
+		let cases = [
+			(
+				BigFloat::ONE >> 1u32,
+				"1.68575035481259604287120365779907698950080089414108904411994829789343370288234\
+				676040645097393661257033478937836378483024",
+			),
+			(
+				BigFloat::from_str_with_precision("0.9", prec + 64).unwrap(),
+				"2.28054913842277020461375194455553043874323796627879333692834106379643607375689\
+				842918693572447354929700428320856402360159",
+			),
+			// Close to the logarithmic singularity at 1
+			(
+				&BigFloat::ONE - &(BigFloat::ONE >> 30u32),
+				"11.4369284843320018031297335262822749257458344526038995750225664280648805952815\
+				382483983580594092611768284669355564335527",
+			),
+		];
+		for (k, expected) in cases {
+			let expected = BigFloat::from_str_with_precision(expected, prec + 64).unwrap();
+			let res = BigFloat::ellipk(&k, prec);
+			assert!((&res - &expected).abs() < epsilon, "{k}");
+			// Even in k
+			assert_eq!(BigFloat::ellipk(&-k, prec), res);
+		}
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_ellipk_out_of_domain() {
+		BigFloat::ellipk(&BigFloat::from(-1), 64);
+	}
+
 	fn test_agm_helper(x: &str, y: &str, expected: &str, prec: i64) {
 		let x = BigFloat::from_str_with_precision(x, prec + 64).unwrap();
 		let y = BigFloat::from_str_with_precision(y, prec + 64).unwrap();
@@ -6,10 +6,44 @@ use crate::biguint::BigUInt;
 
 impl BigFloat {
 	pub fn mul_with_precision(&self, rhs: &BigFloat, prec: i64) -> BigFloat {
-		let mut res = self * rhs;
+		if self.is_zero() || rhs.is_zero() {
+			return BigFloat::ZERO;
+		}
+
+		// Multiplying by ±2^k only moves the exponent and maybe flips the sign
+		let (mut res, pow2) = if let Some(k) = rhs.ilog2_exact() {
+			(self.mul_pow2(k), rhs)
+		} else if let Some(k) = self.ilog2_exact() {
+			(rhs.mul_pow2(k), self)
+		} else {
+			let mut res = self * rhs;
+			res.round_to_precision(prec);
+			return res;
+		};
+		if pow2.is_negative() {
+			res.neg_in_place();
+		}
 		res.round_to_precision(prec);
 		res
 	}
+
+	/// Sets self to lhs * rhs, which is exact, reusing the buffer of self
+	pub fn mul_to(&mut self, lhs: &BigFloat, rhs: &BigFloat) {
+		self.m.mul_to(&lhs.m, &rhs.m);
+		self.e = lhs.e.strict_add(rhs.e);
+		self.normalize();
+	}
+
+	/// Returns self * 2^k, which is exact
+	pub fn mul_pow2(&self, k: i64) -> BigFloat {
+		if self.is_zero() {
+			return BigFloat::ZERO;
+		}
+		BigFloat {
+			m: self.m.clone(),
+			e: self.e.strict_add(k),
+		}
+	}
 }
 
 impl Mul<&BigFloat> for &BigFloat {
@@ -125,4 +159,42 @@ mod tests {
 		assert_eq!(&a * 3.0f32, BigFloat::from(15));
 		assert_eq!(3.0f64 * &a, BigFloat::from(15));
 	}
+
+	#[test]
+	fn test_mul_pow2() {
+		let a = BigFloat::try_from(-2.75).unwrap();
+		assert_eq!(a.mul_pow2(3), BigFloat::from(-22));
+		assert_eq!(a.mul_pow2(-2), BigFloat::try_from(-0.6875).unwrap());
+		assert_eq!(a.mul_pow2(0), a);
+		assert_eq!(BigFloat::ZERO.mul_pow2(100), BigFloat::ZERO);
+		assert_eq!(BigFloat::ZERO.mul_pow2(100).exponent(), 0);
+	}
+
+	#[test]
+	fn test_mul_with_precision_fast_paths() {
+		let values = [
+			BigFloat::ZERO,
+			BigFloat::ONE,
+			BigFloat::NEG_ONE,
+			BigFloat::from(1024),
+			BigFloat::from(-3),
+			BigFloat::from_mantissa_exponent(BigInt::from(-1), -70),
+			BigFloat::from_mantissa_exponent(BigInt::from(0x1234_5678_9abc_def1i64), -40),
+			BigFloat::try_from(-0.1).unwrap(),
+			BigFloat::pi(200),
+		];
+		for a in &values {
+			for b in &values {
+				for prec in [-10, 0, 3, 64, 300] {
+					let mut expected = a * b;
+					expected.round_to_precision(prec);
+					assert_eq!(
+						a.mul_with_precision(b, prec),
+						expected,
+						"{a:?} * {b:?}, {prec}"
+					);
+				}
+			}
+		}
+	}
 }
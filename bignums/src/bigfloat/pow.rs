@@ -1,20 +1,159 @@
-use crate::bigfloat::BigFloat;
+use crate::bigfloat::{BigFloat, TranscendentalOptions};
+use crate::error::BigFloatError;
 
 impl BigFloat {
+	/// Default bound on log2 of the results of exp and powi, i.e. about 20 million decimal digits.
+	/// Larger results would take a lot of time and memory to compute, so they are reported
+	/// as an overflow instead.
+	pub const DEFAULT_MAX_LOG2: i64 = 1 << 26;
+
+	/// # Panics
+	/// If the result is at least 2^DEFAULT_MAX_LOG2
 	pub fn exp(&self, prec: i64) -> BigFloat {
+		self.try_exp(prec, Self::DEFAULT_MAX_LOG2)
+			.expect("exp overflowed")
+	}
+
+	/// Returns exp(self), or an error if the result is at least 2^max_log2.
+	/// Results less than 2^-prec are rounded to zero.
+	pub fn try_exp(&self, prec: i64, max_log2: i64) -> Result<BigFloat, BigFloatError> {
+		let mut res = self.expm1_impl(prec, max_log2, TranscendentalOptions::default())?;
+		res += 1;
+		Ok(res)
+	}
+
+	/// Same as exp, with the given working precision settings
+	/// # Panics
+	/// If the result is at least 2^DEFAULT_MAX_LOG2
+	pub fn exp_opts(&self, prec: i64, opts: TranscendentalOptions) -> BigFloat {
+		let mut res = self
+			.expm1_impl(prec, Self::DEFAULT_MAX_LOG2, opts)
+			.expect("exp overflowed");
+		res += 1;
+		res
+	}
+
+	/// Returns exp(self) - 1.
+	/// Unlike exp(self) - 1, no precision is lost to cancellation when self is close to zero:
+	/// for |self| < 1 the error is also less than 2^-prec times the result.
+	/// # Panics
+	/// If the result is at least 2^DEFAULT_MAX_LOG2
+	pub fn expm1(&self, prec: i64) -> BigFloat {
+		self.try_expm1(prec, Self::DEFAULT_MAX_LOG2)
+			.expect("expm1 overflowed")
+	}
+
+	/// Returns exp(self) - 1, or an error if the result is at least 2^max_log2.
+	pub fn try_expm1(&self, prec: i64, max_log2: i64) -> Result<BigFloat, BigFloatError> {
+		let opts = TranscendentalOptions::default();
+		if self.is_zero() || self.ilog2() >= 0 {
+			return self.expm1_impl(prec, max_log2, opts);
+		}
+		if self.ilog2() < -prec - 1 {
+			// The x^2/2 term is below 2^-prec relative to x
+			return Ok(self.clone());
+		}
+		// |exp(x) - 1| > |x| / 2 for |x| < 1
+		self.expm1_impl(prec - self.ilog2() + 1, max_log2, opts)
+	}
+
+	fn expm1_impl(
+		&self,
+		prec: i64,
+		max_log2: i64,
+		opts: TranscendentalOptions,
+	) -> Result<BigFloat, BigFloatError> {
 		if self.is_zero() {
-			return BigFloat::ONE;
+			return Ok(BigFloat::ZERO);
+		}
+
+		// log2(exp(self)) = self * log2(e), an f64 estimate is good enough for range checks
+		let log2_res = self.to_f64() * core::f64::consts::LOG2_E;
+		if log2_res >= max_log2 as f64 {
+			return Err(BigFloatError::Overflow);
+		}
+		if log2_res < -(prec as f64) - 2.0 {
+			// exp(self) < 2^-(prec + 2), so it is lost to rounding
+			return Ok(BigFloat::NEG_ONE);
+		}
+
+		// Reduce the argument to r = self / 2^k with |r| < 1/2,
+		// then undo the reduction with expm1(2y) = expm1(y) * (expm1(y) + 2)
+		let actual_prec = i64::max(prec + 2, 1);
+		let k = i64::max(self.ilog2() + 2, 0);
+		// Every doubling step at most doubles the error, times e^y for positive arguments
+		let growth = if self.is_negative() {
+			0
+		} else {
+			// f64::ceil is not available in no_std
+			(self.to_f64() * core::f64::consts::LOG2_E) as i64 + 1
+		};
+		let working_prec =
+			actual_prec + k + growth + (actual_prec + k).ilog2() as i64 + 8 + opts.extra_guard_bits;
+
+		// Taylor series: r + r^2/2! + r^3/3! + ...
+		let r = self.clone() >> k;
+		let mut res = r.clone();
+		let mut term = r.clone();
+		let mut n = 1u64;
+		let max_terms = opts.max_iterations.map_or(u64::MAX, u64::from);
+		while n < max_terms {
+			n += 1;
+			term = term
+				.mul_with_precision(&r, working_prec)
+				.div(&BigFloat::from(n), working_prec);
+			if term.is_zero() || term.ilog2() < -working_prec {
+				break;
+			}
+			res = res.add_with_precision(&term, working_prec);
+		}
+
+		for _ in 0..k {
+			let res_plus_two = &res + 2;
+			res = res.mul_with_precision(&res_plus_two, working_prec);
 		}
 
-		todo!()
+		res.round_to_precision(actual_prec);
+		Ok(res)
 	}
 
+	/// # Panics
+	/// If the result is at least 2^DEFAULT_MAX_LOG2
 	pub fn powi_with_precision(&self, pow: i64, prec: i64) -> BigFloat {
+		self.try_powi_with_precision(pow, prec, Self::DEFAULT_MAX_LOG2)
+			.expect("powi overflowed")
+	}
+
+	/// Returns self^pow, or an error if the result is at least 2^max_log2.
+	/// Results less than 2^-prec may be rounded to zero.
+	pub fn try_powi_with_precision(
+		&self,
+		pow: i64,
+		prec: i64,
+		max_log2: i64,
+	) -> Result<BigFloat, BigFloatError> {
 		if self.is_zero() {
-			return BigFloat::ZERO;
+			return Ok(BigFloat::ZERO);
 		}
 		if self.is_one() {
-			return BigFloat::ONE;
+			return Ok(BigFloat::ONE);
+		}
+
+		// |self|^|pow| is in [2^lo, 2^hi)
+		let log2 = self.ilog2() as i128;
+		let abs_pow_wide = pow.unsigned_abs() as i128;
+		let (lo, hi) = (abs_pow_wide * log2, abs_pow_wide * (log2 + 1));
+		// Bounds on log2 of the result
+		let (lo, hi) = if pow.is_negative() {
+			(-hi, -lo)
+		} else {
+			(lo, hi)
+		};
+		if lo >= max_log2 as i128 {
+			return Err(BigFloatError::Overflow);
+		}
+		if hi < -(prec as i128) - 1 {
+			return Ok(BigFloat::ZERO);
 		}
 
 		let working_prec = prec + 16;
@@ -30,7 +169,34 @@ impl BigFloat {
 				res = res.mul_with_precision(&power_of_self, working_prec);
 			}
 			abs_pow >>= 1;
+			if abs_pow == 0 {
+				break;
+			}
 			power_of_self = power_of_self.mul_with_precision(&power_of_self, working_prec);
+
+			// The bounds above are loose for |self| close to 1, so keep checking.
+			// The remaining powers multiply to power_of_self^abs_pow at least if it is > 1,
+			// and at most if it is < 1, which gives tighter bounds on the result.
+			if !power_of_self.is_zero() {
+				let l = power_of_self.ilog2() as i128;
+				let rem = abs_pow as i128;
+				let (lo, hi) = if l >= 0 {
+					(l * rem, i128::MAX)
+				} else {
+					(-i128::MAX, (l + 1) * rem)
+				};
+				let (lo, hi) = if is_pow_negative {
+					(-hi, -lo)
+				} else {
+					(lo, hi)
+				};
+				if lo >= max_log2 as i128 {
+					return Err(BigFloatError::Overflow);
+				}
+				if hi < -(prec as i128) - 1 {
+					return Ok(BigFloat::ZERO);
+				}
+			}
 		}
 
 		if is_pow_negative {
@@ -38,6 +204,169 @@ impl BigFloat {
 		}
 
 		res.round_to_precision(prec);
-		res
+		Ok(res)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::util::check_transcendental_opts;
+
+	#[test]
+	fn test_expm1() {
+		// The x^2/2 term is 40 digits below x and must survive
+		let x = "0.0000000000000000000000000000000000000001";
+		let expected = "0.00000000000000000000000000000000000000010000000000000000000000000000\
+		000000000000500000000000000000000000000000000000000016666666666666666666666666666666666666\
+		667083333333333333333333333333333333333333341666666666666666";
+		test_expm1_helper(x, expected, 500);
+
+		let x = "0.001";
+		let expected = "0.00100050016670834166805575399305831156307620058070146022851467446035\
+		974825144829841271822600415326094306821887209509934206367869611962384097233090553176831192\
+		355619849802365290937767773028386848628740987031722433682729";
+		test_expm1_helper(x, expected, 500);
+
+		let x = "1";
+		let expected = "1.71828182845904523536028747135266249775724709369995957496696762772407\
+		663035354759457138217852516642742746639193200305992181741359662904357290033429526059563073\
+		813232862794349076323382988075319525101901157383418793070215";
+		test_expm1_helper(x, expected, 500);
+
+		let x = "-1";
+		let expected = "-0.6321205588285576784044762298385391325541888689682321654921631983025\
+		385042551001966428527256540803562533726747231560047917530242072098709913733464105059012169\
+		078056326226618849513610088748543836550122800213155240420602";
+		test_expm1_helper(x, expected, 500);
+
+		let x = "10";
+		let expected = "22025.4657948067165169579006452842443663535126185567810742354263552252\
+		028185707925751991209681645258954515555010924578366524232916065228951662224801377289728734\
+		855778378472751954806100958814170558886579273172361684011926";
+		test_expm1_helper(x, expected, 500);
+
+		let x = "-20";
+		let expected = "-0.9999999979388463775614421720340596198441790236241927244008963070277\
+		553383708359762154406467200890720944186329636121092078488392656646616333358082861711523062\
+		633865825991676214245511453354792572211938035316187587032480";
+		test_expm1_helper(x, expected, 500);
+
+		test_expm1_helper("0", "0", 500);
+	}
+
+	#[test]
+	fn test_expm1_relative() {
+		// exp(x) - 1 cancels all the bits of x at this precision
+		let x = BigFloat::ONE >> 200u32;
+		assert!((&x.exp(100) - &BigFloat::ONE).is_zero());
+
+		for x in [x.clone(), -&x] {
+			let expected = &x + &((&x * &x) >> 1u32);
+			let err = (&x.expm1(100) - &expected).abs();
+			assert!(err < (x.clone().abs() >> 100u32), "{x}");
+		}
+
+		// Far below the precision, x itself is accurate enough
+		let tiny = BigFloat::ONE >> 1000u32;
+		assert_eq!(tiny.expm1(100), tiny);
+	}
+
+	#[test]
+	fn test_exp() {
+		let e = BigFloat::ONE.exp(200);
+		let expected = "2.71828182845904523536028747135266249775724709369995957496696762772407\
+		663035354759457138217852516642";
+		let expected = BigFloat::from_str_with_precision(expected, 264).unwrap();
+		assert!((&e - &expected).abs() < (BigFloat::ONE >> 200));
+		assert_eq!(BigFloat::ZERO.exp(200), BigFloat::ONE);
+	}
+
+	#[test]
+	fn test_exp_overflow() {
+		let max = BigFloat::DEFAULT_MAX_LOG2;
+		let big = BigFloat::from(1_000_000_000);
+		assert_eq!(big.try_exp(64, max), Err(BigFloatError::Overflow));
+		assert_eq!(big.try_expm1(64, max), Err(BigFloatError::Overflow));
+		assert_eq!(
+			BigFloat::from(100).try_exp(64, 100),
+			Err(BigFloatError::Overflow)
+		);
+
+		// Underflow
+		let small = BigFloat::from(-1_000_000_000);
+		assert_eq!(small.try_exp(64, max), Ok(BigFloat::ZERO));
+		assert_eq!(small.try_expm1(64, max), Ok(BigFloat::NEG_ONE));
+		assert_eq!(BigFloat::from(-100).exp(64), BigFloat::ZERO);
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_exp_overflow_panics() {
+		let _ = BigFloat::from(1_000_000_000).exp(64);
+	}
+
+	#[test]
+	fn test_powi_overflow() {
+		let max = BigFloat::DEFAULT_MAX_LOG2;
+		let ten = BigFloat::from(10);
+		assert_eq!(
+			ten.try_powi_with_precision(10_000_000_000, 64, max),
+			Err(BigFloatError::Overflow)
+		);
+		assert_eq!(
+			ten.try_powi_with_precision(-10_000_000_000, 64, max),
+			Ok(BigFloat::ZERO)
+		);
+		assert_eq!(
+			ten.try_powi_with_precision(3, 64, max),
+			Ok(BigFloat::from(1000))
+		);
+		assert_eq!(
+			ten.try_powi_with_precision(40, 64, 100),
+			Err(BigFloatError::Overflow)
+		);
+
+		// Close to 1, where the initial bounds don't help
+		let x = BigFloat::try_from(1.5).unwrap();
+		assert_eq!(
+			x.try_powi_with_precision(10_000_000_000, 64, max),
+			Err(BigFloatError::Overflow)
+		);
+		assert_eq!(
+			x.try_powi_with_precision(-10_000_000_000, 64, max),
+			Ok(BigFloat::ZERO)
+		);
+
+		let half = BigFloat::try_from(0.5).unwrap();
+		assert_eq!(
+			half.try_powi_with_precision(-10_000_000_000, 64, max),
+			Err(BigFloatError::Overflow)
+		);
+		assert_eq!(
+			half.try_powi_with_precision(10_000_000_000, 64, max),
+			Ok(BigFloat::ZERO)
+		);
+	}
+
+	fn test_expm1_helper(x: &str, expected: &str, prec: i64) {
+		let x = BigFloat::from_str_with_precision(x, prec + 64).unwrap();
+		let expected = BigFloat::from_str_with_precision(expected, prec + 64).unwrap();
+		let actual = x.expm1(prec);
+		let delta = (&actual - &expected).abs();
+		let epsilon = BigFloat::ONE >> prec;
+
+		print!("expected: {expected}\nactual: {actual}\ndelta: {delta}\nepsilon: {epsilon}\n\n");
+		assert!(delta < epsilon);
+	}
+
+	#[test]
+	fn test_exp_opts() {
+		check_transcendental_opts(
+			"exp",
+			&["1", "-3.5", "0.001"],
+			BigFloat::exp,
+			BigFloat::exp_opts,
+		);
 	}
 }
@@ -1,3 +1,4 @@
+use crate::SetVal;
 use crate::bigfloat::BigFloat;
 use crate::bigint::BigInt;
 
@@ -23,12 +24,18 @@ impl BigFloat {
 		if round_up {
 			self.m.magnitude += 1;
 		}
-		self.m.normalize();
 
 		self.e = new_lsb_weight;
 		self.normalize();
 	}
 
+	/// Sets self to src rounded s.t. the absolute error is less than 2^-prec.
+	/// Same as cloning src and calling round_to_precision, but reuses the buffer of self.
+	pub fn set_val_rounded(&mut self, src: &BigFloat, prec: i64) {
+		self.set_val(src);
+		self.round_to_precision(prec);
+	}
+
 	/// Rounds the number down s.t. the absolute error is less than 2^-prec.
 	pub fn floor_to_precision(&mut self, prec: i64) {
 		if self.is_zero() {
@@ -128,6 +135,45 @@ impl BigFloat {
 		let fract = (self - &whole).abs();
 		(whole.m << whole.e, fract)
 	}
+
+	/// Returns true if the number is less than 2^-prec away from the nearest integer.
+	pub fn is_close_to_integer(&self, prec: i64) -> bool {
+		let mut nearest = self.clone();
+		nearest.round();
+		let delta = self - &nearest;
+		delta.is_zero() || delta.ilog2() < -prec
+	}
+
+	/// Rounds the number to the nearest integer if it is less than 2^-prec away from it,
+	/// otherwise returns it unchanged.
+	/// Useful for hiding the error of iterative methods, e.g. sqrt(4) = 1.99...9
+	pub fn snap_to_integer(&self, prec: i64) -> BigFloat {
+		let mut res = self.clone();
+		if self.is_close_to_integer(prec) {
+			res.round();
+		}
+		res
+	}
+
+	/// Returns the distance between adjacent numbers representable at the given precision,
+	/// i.e. 2^-prec.
+	pub fn ulp(&self, prec: i64) -> BigFloat {
+		BigFloat::from_mantissa_exponent(BigInt::ONE, -prec)
+	}
+
+	/// Returns the smallest number representable at the given precision that is greater than self.
+	pub fn next_up(&self, prec: i64) -> BigFloat {
+		let mut res = self.clone();
+		res.floor_to_precision(prec);
+		&res + &self.ulp(prec)
+	}
+
+	/// Returns the largest number representable at the given precision that is less than self.
+	pub fn next_down(&self, prec: i64) -> BigFloat {
+		let mut res = self.clone();
+		res.ceil_to_precision(prec);
+		&res - &self.ulp(prec)
+	}
 }
 
 #[cfg(test)]
@@ -153,6 +199,37 @@ mod tests {
 		assert_eq!(f, BigFloat::try_from(-2.5).unwrap());
 	}
 
+	#[test]
+	fn test_set_val_rounded() {
+		let values = [
+			BigFloat::ZERO,
+			BigFloat::try_from(1.5).unwrap(),
+			BigFloat::try_from(-2.25).unwrap(),
+			BigFloat::try_from(1e-30).unwrap(),
+			BigFloat::ONE.div(&BigFloat::from(3), 300),
+			-BigFloat::from(7).sqrt(500),
+			BigFloat::from_mantissa_exponent(BigInt::from(u128::MAX), 100),
+		];
+		let mut res = BigFloat::from_mantissa_exponent(BigInt::from(-5), 1000);
+		for src in &values {
+			for prec in [-200, -64, -1, 0, 1, 2, 10, 64, 65, 200, 1000] {
+				let mut expected = src.clone();
+				expected.round_to_precision(prec);
+				res.set_val_rounded(src, prec);
+				assert_eq!(res, expected, "{src} {prec}");
+			}
+		}
+
+		// The mantissa buffer of self is reused
+		let src = BigFloat::from(13).sqrt(2000);
+		let mut res = src.clone();
+		let ptr = res.mantissa().magnitude.inner().as_ptr();
+		for prec in [1500, 100, 1999, 1] {
+			res.set_val_rounded(&src, prec);
+			assert_eq!(res.mantissa().magnitude.inner().as_ptr(), ptr);
+		}
+	}
+
 	#[test]
 	fn test_floor_to_precision() {
 		let mut f = BigFloat::try_from(1.75).unwrap();
@@ -218,6 +295,62 @@ mod tests {
 		assert_eq!(f, BigFloat::from(-1));
 	}
 
+	#[test]
+	fn test_snap_to_integer() {
+		let below = &BigFloat::from(2) - &(BigFloat::ONE >> 100u32);
+		assert!(below.is_close_to_integer(64));
+		assert_eq!(below.snap_to_integer(64), BigFloat::from(2));
+
+		let above = &BigFloat::from(-2) - &(BigFloat::ONE >> 100u32);
+		assert!(above.is_close_to_integer(64));
+		assert_eq!(above.snap_to_integer(64), BigFloat::from(-2));
+
+		let far = &BigFloat::from(2) - &(BigFloat::ONE >> 10u32);
+		assert!(!far.is_close_to_integer(64));
+		assert_eq!(far.snap_to_integer(64), far);
+
+		let half = BigFloat::try_from(0.5).unwrap();
+		assert!(!half.is_close_to_integer(1));
+		assert_eq!(half.snap_to_integer(1), half);
+
+		assert_eq!(BigFloat::ZERO.snap_to_integer(64), BigFloat::ZERO);
+		assert_eq!(BigFloat::from(7).snap_to_integer(64), BigFloat::from(7));
+	}
+
+	#[test]
+	fn test_ulp() {
+		let x = BigFloat::try_from(2.5).unwrap();
+		assert_eq!(x.ulp(0), BigFloat::ONE);
+		assert_eq!(x.ulp(3), BigFloat::try_from(0.125).unwrap());
+		assert_eq!(x.ulp(-2), BigFloat::from(4));
+
+		for prec in [-2, 0, 1, 10, 100] {
+			for x in [BigFloat::ZERO, BigFloat::from(12), BigFloat::from(-20)] {
+				assert_eq!(&x.next_up(prec) - &x, x.ulp(prec));
+				assert_eq!(&x - &x.next_down(prec), x.ulp(prec));
+			}
+		}
+	}
+
+	#[test]
+	fn test_next_up_down() {
+		let x = BigFloat::try_from(2.3).unwrap();
+		assert_eq!(x.next_up(0), BigFloat::from(3));
+		assert_eq!(x.next_down(0), BigFloat::from(2));
+		assert_eq!(x.next_up(2), BigFloat::try_from(2.5).unwrap());
+		assert_eq!(x.next_down(2), BigFloat::try_from(2.25).unwrap());
+
+		let x = BigFloat::try_from(-2.3).unwrap();
+		assert_eq!(x.next_up(0), BigFloat::from(-2));
+		assert_eq!(x.next_down(0), BigFloat::from(-3));
+
+		let x = BigFloat::try_from(2.5).unwrap();
+		assert_eq!(x.next_up(1), BigFloat::from(3));
+		assert_eq!(x.next_down(1), BigFloat::from(2));
+		assert_eq!(x.next_up(0), BigFloat::from(3));
+		assert_eq!(x.next_down(0), BigFloat::from(2));
+	}
+
 	#[test]
 	fn test_zero_rounding() {
 		let mut f = BigFloat::from(0);
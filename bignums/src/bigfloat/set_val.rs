@@ -52,6 +52,11 @@ impl TrySetVal<f32> for BigFloat {
 
 	fn try_set_val(&mut self, src: f32) -> Result<(), Self::Error> {
 		let (is_negative, m, e) = util::f32_to_parts(src)?;
+		// Both 0.0 and -0.0 map to the only zero
+		if m == 0 {
+			self.set_zero();
+			return Ok(());
+		}
 		self.m.set_val(m);
 		self.m.set_sign(is_negative);
 		self.e = e as i64 - m.ilog2() as i64;
@@ -65,6 +70,11 @@ impl TrySetVal<f64> for BigFloat {
 
 	fn try_set_val(&mut self, src: f64) -> Result<(), Self::Error> {
 		let (is_negative, m, e) = util::f64_to_parts(src)?;
+		// Both 0.0 and -0.0 map to the only zero
+		if m == 0 {
+			self.set_zero();
+			return Ok(());
+		}
 		self.m.set_val(m);
 		self.m.set_sign(is_negative);
 		self.e = e - m.ilog2() as i64;
@@ -1,29 +1,142 @@
-use crate::bigfloat::BigFloat;
+use core::mem;
+
+use crate::bigfloat::{BigFloat, BigFloatCtx, TranscendentalOptions};
 use crate::bigint::BigInt;
+use crate::error::DomainError;
 
 impl BigFloat {
+	/// # Panics
+	/// If self is negative
 	pub fn sqrt(&self, prec: i64) -> BigFloat {
+		self.try_sqrt(prec)
+			.expect("Cannot take sqrt of negative number")
+	}
+
+	/// Same as sqrt, but returns an error instead of panicking if self is negative
+	pub fn try_sqrt(&self, prec: i64) -> Result<BigFloat, DomainError> {
 		if self.is_negative() {
-			panic!("Cannot take sqrt of negative number");
+			return Err(DomainError);
 		}
+		Ok(self.sqrt_impl(prec, TranscendentalOptions::default()).0)
+	}
+
+	/// Same as sqrt, with the given working precision settings
+	/// # Panics
+	/// If self is negative
+	pub fn sqrt_opts(&self, prec: i64, opts: TranscendentalOptions) -> BigFloat {
+		assert!(!self.is_negative(), "Cannot take sqrt of negative number");
+		self.sqrt_impl(prec, opts).0
+	}
+
+	/// Returns the square root and the number of Newton iterations it took
+	fn sqrt_impl(&self, prec: i64, opts: TranscendentalOptions) -> (BigFloat, u32) {
+		self.sqrt_with(prec, opts, &mut BigFloatCtx::new())
+	}
+
+	/// sqrt_impl with the buffers of ctx
+	pub(crate) fn sqrt_with(
+		&self,
+		prec: i64,
+		opts: TranscendentalOptions,
+		ctx: &mut BigFloatCtx,
+	) -> (BigFloat, u32) {
+		debug_assert!(!self.is_negative());
 		if self.is_zero() {
-			return BigFloat::ZERO;
+			return (BigFloat::ZERO, 0);
 		}
 		if self.is_one() {
-			return BigFloat::ONE;
+			return (BigFloat::ONE, 0);
 		}
 
 		let actual_prec = prec + 2;
-		let working_prec = actual_prec + 16;
+		let working_prec = actual_prec + 16 + opts.extra_guard_bits;
 
-		let mut x = Self::est_sqrt(self.clone());
+		let mut x = self.est_sqrt();
+		let max_iterations = opts.iteration_limit(working_prec);
 
-		loop {
-			let q = self.div(&x, working_prec);
-			let delta = x.sub_with_precision(&q, working_prec);
-			x = x.add_with_precision(&q, working_prec) >> 1;
+		// Taken out of ctx, which the divisions need as well
+		let mut scratch = mem::take(&mut ctx.sqrt);
+		let (delta, sum) = (&mut scratch.a, &mut scratch.b);
 
-			if delta.is_zero() || delta.ilog2() + 1 <= -actual_prec {
+		let mut iterations = 0;
+		for iteration in 0.. {
+			iterations = iteration + 1;
+			let mut q = self.div_with(&x, working_prec, ctx);
+			q.neg_in_place();
+			delta.add_to(&x, &q);
+			delta.round_to_precision(working_prec);
+			q.neg_in_place();
+			sum.add_to(&x, &q);
+			sum.round_to_precision(working_prec);
+			*sum >>= 1;
+			mem::swap(&mut x, sum);
+
+			let converged = delta.is_zero() || delta.ilog2() + 1 <= -actual_prec;
+			if converged || iterations >= max_iterations {
+				debug_assert!(
+					converged || opts.max_iterations.is_some(),
+					"sqrt did not converge"
+				);
+				break;
+			}
+		}
+
+		ctx.sqrt = scratch;
+		x.round_to_precision(actual_prec);
+		(x, iterations)
+	}
+
+	pub fn cbrt(&self, prec: i64) -> BigFloat {
+		self.nth_root(3, prec)
+	}
+
+	/// Real n-th root of self
+	/// # Panics
+	/// If n is zero, or n is even and self is negative
+	pub fn nth_root(&self, n: u32, prec: i64) -> BigFloat {
+		assert!(n != 0, "Cannot take 0th root");
+		if self.is_negative() {
+			assert!(n % 2 == 1, "Cannot take even root of negative number");
+			return -self.clone().abs().nth_root(n, prec);
+		}
+		if n == 1 {
+			let mut res = self.clone();
+			res.round_to_precision(prec);
+			return res;
+		}
+		if n == 2 {
+			return self.sqrt(prec);
+		}
+		if self.is_zero() || self.is_one() {
+			return self.clone();
+		}
+
+		let n_wide = n as i64;
+		// self < 2^t, so self^(1/n) < 2^ceil(t/n)
+		let t = self.ilog2() + 1;
+		let root_log2 = t.div_euclid(n_wide) + (t.rem_euclid(n_wide) != 0) as i64;
+
+		let actual_prec = prec + 2;
+		// x^(n-1) must be accurate relative to its size when the root is small
+		let working_prec = actual_prec + 16 + n_wide * i64::max(-root_log2, 0) + n.ilog2() as i64;
+
+		// Newton's method converges monotonically from above
+		let mut x = BigFloat::from_mantissa_exponent(BigInt::ONE, root_log2);
+		let n_float = BigFloat::from(n);
+		// x / root <= 4 initially, far above the root x shrinks by a factor of about 1 - 1/n
+		let max_iterations = Self::max_iterations(working_prec).saturating_add(n.saturating_mul(4));
+		for iteration in 0.. {
+			let pow = x.powi_with_precision(n_wide - 1, working_prec);
+			let q = self.div(&pow, working_prec);
+			// x - (x - self / x^(n-1)) / n
+			let delta = x
+				.sub_with_precision(&q, working_prec)
+				.div(&n_float, working_prec);
+			x = x.sub_with_precision(&delta, working_prec);
+
+			let converged = delta.is_zero() || delta.ilog2() < -actual_prec;
+			if converged || iteration == max_iterations {
+				debug_assert!(converged, "nth_root did not converge");
 				break;
 			}
 		}
@@ -32,24 +145,34 @@ impl BigFloat {
 		x
 	}
 
-	fn est_sqrt(x: BigFloat) -> BigFloat {
-		let mut shift = x.m.magnitude.ilog2() as i64;
-		if (x.e + shift) % 2 != 0 {
+	/// Estimates the square root from the top bits of the mantissa, relative error < 2^-61.
+	/// The exponent is handled separately, so this works for any exponent.
+	fn est_sqrt(&self) -> BigFloat {
+		// self ~ top * 2^(e + shift) with 2^124 <= top < 2^126 and e + shift even
+		let len = self.m.magnitude.ilog2() as i64 + 1;
+		let mut shift = len - 126;
+		if (self.e + shift).rem_euclid(2) != 0 {
 			shift += 1;
 		}
-		let n = (x.e + shift) / 2;
 
-		let a = BigFloat::from_mantissa_exponent(BigInt::ONE, n - 1);
-		let b = BigFloat::from_mantissa_exponent(x.m, n - shift - 1);
-		&a + &b
+		let top = if shift >= 0 {
+			self.m.magnitude.clone() >> shift
+		} else {
+			self.m.magnitude.clone() << -shift
+		};
+		let top = u128::try_from(&top).expect("top bits fit in u128");
+
+		BigFloat::from_mantissa_exponent(BigInt::from(top.isqrt()), (self.e + shift) / 2)
 	}
 }
 
 #[cfg(test)]
 mod tests {
 	use core::ops::Sub;
+	use core::str::FromStr;
 
 	use super::*;
+	use crate::util::check_transcendental_opts;
 
 	#[test]
 	fn test_sqrt() {
@@ -102,4 +225,138 @@ mod tests {
 		print!("expected: {expected}\nactual: {actual}\ndelta: {delta}\nepsilon: {epsilon}\n\n");
 		assert!(delta < epsilon);
 	}
+
+	#[test]
+	fn test_nth_root() {
+		let prec = 500;
+		let epsilon = BigFloat::ONE >> prec;
+		let close = |a: &BigFloat, b: &BigFloat| (a - b).abs() < epsilon;
+
+		assert!(close(&BigFloat::from(27).cbrt(prec), &BigFloat::from(3)));
+		assert!(close(&BigFloat::from(-8).cbrt(prec), &BigFloat::from(-2)));
+		assert!(close(
+			&BigFloat::from(16).nth_root(4, prec),
+			&BigFloat::from(2)
+		));
+		assert!(close(
+			&BigFloat::from(2).nth_root(2, prec),
+			&BigFloat::sqrt2(prec)
+		));
+		assert!(close(
+			&BigFloat::from(7).nth_root(1, prec),
+			&BigFloat::from(7)
+		));
+		assert_eq!(BigFloat::ZERO.nth_root(5, prec), BigFloat::ZERO);
+
+		// Check x^n against self for roots that aren't exact
+		for (x, n) in [(2, 3), (1000, 7), (3, 10)] {
+			let root = BigFloat::from(x).nth_root(n, prec + 64);
+			let back = root.powi_with_precision(n as i64, prec + 64);
+			assert!(close(&back, &BigFloat::from(x)), "{x}^(1/{n})");
+		}
+
+		// Small numbers and small roots
+		let x = BigFloat::ONE >> 300u32;
+		assert!(close(&x.nth_root(3, prec), &(BigFloat::ONE >> 100u32)));
+		let x = BigFloat::ONE >> 3000u32;
+		assert!(close(&x.nth_root(5, prec), &(BigFloat::ONE >> 600u32)));
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_nth_root_even_of_negative() {
+		let _ = BigFloat::from(-16).nth_root(4, 64);
+	}
+
+	#[test]
+	fn test_try_sqrt() {
+		assert_eq!(BigFloat::from(-4).try_sqrt(64), Err(DomainError));
+		assert_eq!(
+			BigFloat::try_from(-1e-300).unwrap().try_sqrt(64),
+			Err(DomainError)
+		);
+		assert_eq!(BigFloat::ZERO.try_sqrt(64), Ok(BigFloat::ZERO));
+		assert_eq!(BigFloat::from(49).try_sqrt(64), Ok(BigFloat::from(7)));
+		let x = BigFloat::from(2);
+		assert_eq!(x.try_sqrt(200), Ok(x.sqrt(200)));
+		assert!(std::panic::catch_unwind(|| BigFloat::NEG_ONE.sqrt(64)).is_err());
+	}
+
+	#[test]
+	fn test_sqrt_extreme_exponents() {
+		let y = BigFloat::from_str("1234567.891011121314151617181920").unwrap();
+		for k in [-1_000_000i64, -1001, -64, -1, 0, 1, 63, 1000, 1_000_000] {
+			// sqrt(y * 2^2k) = sqrt(y) * 2^k
+			let x = y.mul_pow2(2 * k);
+			let odd = y.mul_pow2(2 * k + 1);
+			for prec in [16, 64, 256, 2048] {
+				let prec = prec - k;
+				let (s, iterations) = x.sqrt_impl(prec, TranscendentalOptions::default());
+				let expected = y.sqrt(prec + k).mul_pow2(k);
+				assert!(
+					(&s - &expected).abs() < BigFloat::from_mantissa_exponent(BigInt::ONE, -prec),
+					"k = {k}, prec = {prec}"
+				);
+				// The seed is good to 61 bits, and each iteration doubles that
+				let bound = ((prec + k + 64) / 61).max(1).ilog2() + 2;
+				assert!(
+					iterations <= bound,
+					"{iterations} iterations, k = {k}, prec = {prec}"
+				);
+
+				let (s, iterations) = odd.sqrt_impl(prec, TranscendentalOptions::default());
+				let error = &s.mul_with_precision(&s, prec - k + 64) - &odd;
+				assert!(error.abs() < BigFloat::from_mantissa_exponent(BigInt::ONE, k + 24 - prec));
+				assert!(
+					iterations <= bound,
+					"{iterations} iterations, k = {k}, prec = {prec}"
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn test_est_sqrt() {
+		for x in [2u128, 3, 4, 1 << 125, u128::MAX, 12345678901234567890] {
+			for e in [-3i64, 0, 7, 1 << 40] {
+				let x = BigFloat::from_mantissa_exponent(BigInt::from(x), e);
+				let est = x.est_sqrt();
+				let exact = x.sqrt(200 - x.ilog2() / 2);
+				let error = (&est - &exact).abs();
+				assert!(error.is_zero() || error.ilog2() < exact.ilog2() - 60);
+			}
+		}
+	}
+
+	#[test]
+	fn test_iterations_terminate_at_high_precision() {
+		// Each loop debug-asserts that it converged before hitting its iteration cap
+		let prec = 1 << 12;
+		let three = BigFloat::from(3);
+		let bound = BigFloat::ONE >> (prec - 4);
+
+		let r = three.reciprocal(prec);
+		assert!((&r.mul_with_precision(&three, prec + 4) - &BigFloat::ONE).abs() < bound);
+
+		let s = three.sqrt(prec);
+		assert!((&s.mul_with_precision(&s, prec + 4) - &three).abs() < bound);
+
+		// Starts far above the root, so most iterations are spent converging linearly
+		let x = BigFloat::from(1_000_003).nth_root(200, 512);
+		let x_pow = x.powi_with_precision(200, 512);
+		assert!((&x_pow - &BigFloat::from(1_000_003)).abs() < (BigFloat::ONE >> 400u32));
+
+		let l = three.ln(prec / 4);
+		assert!(l > BigFloat::try_from(1.0986).unwrap() && l < BigFloat::try_from(1.0987).unwrap());
+	}
+
+	#[test]
+	fn test_sqrt_opts() {
+		check_transcendental_opts(
+			"sqrt",
+			&["2", "0.001", "12345.678"],
+			BigFloat::sqrt,
+			BigFloat::sqrt_opts,
+		);
+	}
 }
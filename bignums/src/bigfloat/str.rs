@@ -1,4 +1,7 @@
+use alloc::format;
 use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
 use core::fmt::{Display, Formatter};
 use core::ops::Mul;
 use core::str::FromStr;
@@ -63,8 +66,8 @@ impl BigFloat {
 
 		let is_negative = whole.get(0).copied() == Some(b'-');
 
-		let whole_i = BigInt::from_ascii_radix(whole, radix).map_err(|e| e.to_float_error())?;
-		let fract_i = BigUInt::from_ascii_radix(fract, radix).map_err(|e| e.to_float_error())?;
+		let whole_i = BigInt::parse_untrimmed(whole, radix)?;
+		let fract_i = BigUInt::parse_untrimmed(fract, radix)?;
 
 		let whole_f = BigFloat::from(whole_i);
 		if fract_i.is_zero() {
@@ -83,6 +86,69 @@ impl BigFloat {
 		Ok(res)
 	}
 
+	pub fn from_str_with_digit_precision(
+		src: &str,
+		extra_bits: i64,
+	) -> Result<Self, ParseFloatError> {
+		Self::from_ascii_radix_with_digit_precision(src.as_bytes(), 10, extra_bits)
+	}
+
+	/// Like from_ascii_radix_with_precision, but the precision is relative to the last parsed digit:
+	/// the absolute error is less than 2^-extra_bits times its weight radix^-k,
+	/// where k is the number of fractional digits. So no digit of the input is lost for
+	/// extra_bits >= 1, however large the integer part is.
+	pub fn from_ascii_radix_with_digit_precision(
+		src: &[u8],
+		radix: u32,
+		extra_bits: i64,
+	) -> Result<Self, ParseFloatError> {
+		let (_, fract) = src.split_once(|c| *c == b'.').unwrap_or((src, b""));
+		// 2^-prec <= radix^-k * 2^-extra_bits, rounding log2(radix) up
+		let bits_per_digit = radix.next_power_of_two().ilog2() as i64;
+		let prec = (fract.len() as i64).saturating_mul(bits_per_digit) + extra_bits;
+		Self::from_ascii_radix_with_precision(src, radix, prec)
+	}
+
+	/// Parses a decimal number with just enough precision that
+	/// `to_string_radix_rounded(10, false, k)` gives back the input,
+	/// where k is the number of fractional digits of the input, minus trailing zeros.
+	pub fn from_decimal_round_trip(src: &str) -> Result<Self, ParseFloatError> {
+		let (_, fract) = src.split_once('.').unwrap_or((src, ""));
+		// 2^-prec <= 10^-k / 2, using log2(10) < 3.322
+		let k = fract.trim_end_matches('0').len() as i64;
+		let prec = k * 3322 / 1000 + 2;
+		Self::from_str_with_precision(src, prec)
+	}
+
+	/// Builds mantissa_digits * radix^exp, rounded s.t. the absolute error is less than 2^-prec.
+	/// The mantissa may have a sign and a radix point, e.g. from_scientific("-1.5", 3, 10, prec) is -1500.
+	pub fn from_scientific(
+		mantissa_digits: &str,
+		exp: i64,
+		radix: u32,
+		prec: i64,
+	) -> Result<Self, ParseFloatError> {
+		let src = mantissa_digits.as_bytes();
+		let (whole, fract) = src.split_once(|c| *c == b'.').unwrap_or((src, b""));
+
+		// mantissa = digits / radix^fract.len()
+		let mut digits = Vec::with_capacity(whole.len() + fract.len());
+		digits.extend_from_slice(whole);
+		digits.extend_from_slice(fract);
+		let m = BigInt::parse_untrimmed(&digits, radix)?;
+
+		let exp = exp.saturating_sub(fract.len() as i64);
+		let scale = BigUInt::from(radix).pow(exp.unsigned_abs());
+
+		let mut res = if exp >= 0 {
+			BigFloat::from(&m * &scale)
+		} else {
+			BigFloat::from(m).div(&BigFloat::from(scale), prec + 16)
+		};
+		res.round_to_precision(prec);
+		Ok(res)
+	}
+
 	pub fn to_string_radix(&self, radix: u32, uppercase: bool) -> String {
 		assert!((2..=36).contains(&radix), "radix must be between 2 and 36");
 
@@ -110,10 +176,73 @@ impl BigFloat {
 
 		s
 	}
+
+	/// Formats the number rounded to the nearest multiple of radix^-digits, ties away from zero.
+	/// Trailing zeros of the fractional part are omitted.
+	pub fn to_string_radix_rounded(&self, radix: u32, uppercase: bool, digits: u64) -> String {
+		assert!((2..=36).contains(&radix), "radix must be between 2 and 36");
+
+		let scale = BigFloat::from(BigUInt::from(radix).pow(digits));
+		let mut scaled = &self.clone().abs() * &scale;
+		scaled.round();
+		let scaled = scaled.trunc_to_int();
+
+		let mut s = String::new();
+		if self.is_negative() && !scaled.is_zero() {
+			s.push('-');
+		}
+
+		let scaled = scaled.to_string_radix(radix, uppercase);
+		let digits = digits as usize;
+		let scaled = format!("{scaled:0>width$}", width = digits + 1);
+		let (whole, fract) = scaled.split_at(scaled.len() - digits);
+		s.push_str(whole);
+		let fract = fract.trim_end_matches('0');
+		if !fract.is_empty() {
+			s.push('.');
+			s.push_str(fract);
+		}
+
+		s
+	}
+
+	/// Formats the number in decimal with exactly decimal_places fractional digits,
+	/// rounded to nearest with ties to even and padded with zeros, e.g. 0.125 to 2 places is "0.12".
+	/// Zero places omit the decimal point.
+	pub fn to_fixed(&self, decimal_places: usize) -> String {
+		let scale = BigUInt::from(10u32).pow(decimal_places as u64);
+		// Exact, so the tie check below sees the true fractional part
+		let scaled = &self.clone().abs() * &scale;
+		let mut q = scaled.trunc_to_int();
+		let half = BigFloat::ONE >> 1u32;
+		match scaled.fract().cmp(&half) {
+			Ordering::Greater => q += 1u32,
+			Ordering::Equal if q.inner().bit(0) => q += 1u32,
+			_ => {}
+		}
+
+		let mut s = String::new();
+		if self.is_negative() && !q.is_zero() {
+			s.push('-');
+		}
+
+		let digits = q.to_string_radix(10, false);
+		let digits = format!("{digits:0>width$}", width = decimal_places + 1);
+		let (whole, fract) = digits.split_at(digits.len() - decimal_places);
+		s.push_str(whole);
+		if decimal_places > 0 {
+			s.push('.');
+			s.push_str(fract);
+		}
+
+		s
+	}
 }
 
 #[cfg(test)]
 mod tests {
+	use alloc::string::ToString;
+
 	use super::*;
 
 	#[test]
@@ -224,4 +353,228 @@ mod tests {
 			"-0.8"
 		);
 	}
+
+	#[test]
+	fn test_from_scientific() {
+		let prec = 64;
+		assert_eq!(
+			BigFloat::from_scientific("15", 2, 10, prec).unwrap(),
+			BigFloat::from(1500)
+		);
+		assert_eq!(
+			BigFloat::from_scientific("-1.5", 3, 10, prec).unwrap(),
+			BigFloat::from(-1500)
+		);
+		assert_eq!(
+			BigFloat::from_scientific("ff", 1, 16, prec).unwrap(),
+			BigFloat::from(0xff0)
+		);
+		assert_eq!(
+			BigFloat::from_scientific("1.25", 0, 10, prec).unwrap(),
+			BigFloat::try_from(1.25).unwrap()
+		);
+		assert_eq!(
+			BigFloat::from_scientific("5", -1, 10, prec).unwrap(),
+			BigFloat::try_from(0.5).unwrap()
+		);
+		assert_eq!(
+			BigFloat::from_scientific("0", 10, 10, prec).unwrap(),
+			BigFloat::ZERO
+		);
+
+		// Inexact in binary
+		let third = BigFloat::from_scientific("3.3333333333333333333333333", -1, 10, prec).unwrap();
+		let expected = BigFloat::ONE.div(&BigFloat::from(3), prec + 16);
+		assert!((&third - &expected).abs() < (BigFloat::ONE >> (prec - 2)));
+
+		assert_eq!(
+			BigFloat::from_scientific("", 2, 10, prec),
+			Err(ParseFloatError::Empty)
+		);
+		assert_eq!(
+			BigFloat::from_scientific("1.2.3", 2, 10, prec),
+			Err(ParseFloatError::InvalidDigit)
+		);
+		assert_eq!(
+			BigFloat::from_scientific("12", 2, 2, prec),
+			Err(ParseFloatError::InvalidDigit)
+		);
+	}
+
+	#[test]
+	fn test_negative_zero() {
+		let mut set = BigFloat::ZERO;
+		set.set_sign(true);
+		let mut rounded = BigFloat::try_from(-0.25).unwrap();
+		rounded.round_to_precision(0);
+		let mut ceiled = BigFloat::try_from(-0.25).unwrap();
+		ceiled.ceil_to_precision(0);
+		let zeros = [
+			set,
+			rounded,
+			ceiled,
+			-BigFloat::ZERO,
+			BigFloat::try_from(-0.0).unwrap(),
+			BigFloat::from_mantissa_exponent(
+				BigInt::from_sign_and_magnitude(true, BigUInt::ZERO),
+				5,
+			),
+			BigFloat::from_str("-0").unwrap(),
+			BigFloat::from_str("-0.000").unwrap(),
+			&BigFloat::from(-7) * &BigFloat::ZERO,
+			&BigFloat::from(-7) - &BigFloat::from(-7),
+		];
+		for zero in zeros {
+			assert_eq!(zero, BigFloat::ZERO);
+			assert!(!zero.is_negative());
+			assert_eq!(zero.to_string(), "0");
+		}
+	}
+
+	#[test]
+	fn test_to_string_radix_rounded() {
+		let x = BigFloat::try_from(2.71875).unwrap();
+		assert_eq!(x.to_string_radix_rounded(10, false, 0), "3");
+		assert_eq!(x.to_string_radix_rounded(10, false, 2), "2.72");
+		assert_eq!(x.to_string_radix_rounded(10, false, 4), "2.7188");
+		assert_eq!(x.to_string_radix_rounded(10, false, 10), "2.71875");
+		assert_eq!((-x).to_string_radix_rounded(10, false, 3), "-2.719");
+
+		let x = BigFloat::try_from(0.03125).unwrap();
+		assert_eq!(x.to_string_radix_rounded(10, false, 3), "0.031");
+		assert_eq!((-x).to_string_radix_rounded(10, false, 1), "0");
+		assert_eq!(
+			BigFloat::try_from(-10.5)
+				.unwrap()
+				.to_string_radix_rounded(10, false, 0),
+			"-11"
+		);
+		assert_eq!(
+			BigFloat::try_from(255.75)
+				.unwrap()
+				.to_string_radix_rounded(16, true, 1),
+			"FF.C"
+		);
+		assert_eq!(BigFloat::ZERO.to_string_radix_rounded(10, false, 5), "0");
+	}
+
+	#[test]
+	fn test_to_fixed() {
+		let f = |x: f64, places| BigFloat::try_from(x).unwrap().to_fixed(places);
+		let third = BigFloat::ONE.div(&BigFloat::from(3), 100);
+		assert_eq!(third.to_fixed(4), "0.3333");
+		assert_eq!((-third).to_fixed(4), "-0.3333");
+		let two_thirds = BigFloat::from(2).div(&BigFloat::from(3), 100);
+		assert_eq!(two_thirds.to_fixed(3), "0.667");
+		assert_eq!(two_thirds.to_fixed(0), "1");
+
+		// Padding
+		assert_eq!(f(1.5, 3), "1.500");
+		assert_eq!(f(42.0, 2), "42.00");
+		assert_eq!(f(0.0, 2), "0.00");
+		assert_eq!(f(0.0, 0), "0");
+		assert_eq!(f(0.03125, 3), "0.031");
+		assert_eq!(f(0.03125, 8), "0.03125000");
+		assert_eq!(f(-7.0, 1), "-7.0");
+
+		// Ties to even
+		assert_eq!(f(0.5, 0), "0");
+		assert_eq!(f(1.5, 0), "2");
+		assert_eq!(f(2.5, 0), "2");
+		assert_eq!(f(-2.5, 0), "-2");
+		assert_eq!(f(-3.5, 0), "-4");
+		assert_eq!(f(0.125, 2), "0.12");
+		assert_eq!(f(0.375, 2), "0.38");
+		assert_eq!(f(0.03125, 4), "0.0312");
+		// Slightly above a tie, since 2.675 isn't exactly representable
+		assert_eq!(f(2.675, 2), "2.67");
+		assert_eq!(f(2.6750000000000003, 2), "2.68");
+
+		// Rounds to zero without a sign
+		assert_eq!(f(-0.004, 2), "0.00");
+		assert_eq!(f(-0.5, 0), "0");
+
+		let big = &BigFloat::from(BigInt::from(10).pow(30)) + &BigFloat::try_from(0.25).unwrap();
+		assert_eq!(big.to_fixed(1), format!("1{}.2", "0".repeat(30)));
+	}
+
+	#[test]
+	fn test_from_str_with_digit_precision() {
+		// An absolute precision that is fine for the integer part loses the tail
+		let src = "1000000.000000000001";
+		let coarse = BigFloat::from_str_with_precision(src, 20).unwrap();
+		assert_eq!(coarse, BigFloat::from(1000000));
+
+		let x = BigFloat::from_str_with_digit_precision(src, 4).unwrap();
+		let tail = &x - &BigFloat::from(1000000);
+		assert!(!tail.is_zero());
+		assert_eq!(
+			tail.to_string_radix_rounded(10, false, 12),
+			"0.000000000001"
+		);
+		assert_eq!(x.to_string_radix_rounded(10, false, 12), src);
+
+		// Same result regardless of the size of the integer part
+		let big = format!("{}.000000000001", "9".repeat(40));
+		let x = BigFloat::from_str_with_digit_precision(&big, 4).unwrap();
+		assert_eq!(x.to_string_radix_rounded(10, false, 12), big);
+		let x = BigFloat::from_str_with_digit_precision("-0.000000000000000000000000000007", 1)
+			.unwrap();
+		assert_eq!(
+			x.to_string_radix_rounded(10, false, 30),
+			"-0.000000000000000000000000000007"
+		);
+
+		// Exact inputs stay exact, whatever the precision
+		let x = BigFloat::from_ascii_radix_with_digit_precision(b"ff.c", 16, 0).unwrap();
+		assert_eq!(x, BigFloat::try_from(255.75).unwrap());
+		let x = BigFloat::from_str_with_digit_precision("-42", 0).unwrap();
+		assert_eq!(x, BigFloat::from(-42));
+
+		assert_eq!(
+			BigFloat::from_str_with_digit_precision("1.2.3", 4),
+			Err(ParseFloatError::InvalidDigit)
+		);
+	}
+
+	#[test]
+	fn test_from_decimal_round_trip() {
+		for src in [
+			"0",
+			"1",
+			"-17",
+			"0.1",
+			"-0.1",
+			"0.3",
+			"3.14159",
+			"2.718281828459045235360287471352662497757",
+			"-1234567.000001",
+			"0.000000000000000000000000000001",
+			"99.99999999999999999",
+			"123456789012345678901234567890.5",
+			"2.50",
+			"-3.000",
+		] {
+			let x = BigFloat::from_decimal_round_trip(src).unwrap();
+			let expected = match src.split_once('.') {
+				Some(_) => src.trim_end_matches('0').trim_end_matches('.'),
+				None => src,
+			};
+			let digits = expected
+				.split_once('.')
+				.map_or(0, |(_, fract)| fract.len() as u64);
+			assert_eq!(x.to_string_radix_rounded(10, false, digits), expected);
+		}
+
+		// Only as precise as needed, trailing zeros add nothing
+		let x = BigFloat::from_decimal_round_trip("0.1").unwrap();
+		assert!(-x.exponent() <= 6);
+		let y = BigFloat::from_decimal_round_trip("0.1000").unwrap();
+		assert_eq!(x, y);
+
+		assert_eq!(
+			BigFloat::from_decimal_round_trip("1.2.3"),
+			Err(ParseFloatError::InvalidDigit)
+		);
+	}
 }
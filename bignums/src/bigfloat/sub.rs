@@ -13,6 +13,14 @@ impl Neg for BigFloat {
 	}
 }
 
+impl Neg for &BigFloat {
+	type Output = BigFloat;
+
+	fn neg(self) -> Self::Output {
+		-self.clone()
+	}
+}
+
 impl BigFloat {
 	pub fn neg_in_place(&mut self) {
 		self.m.neg_in_place();
@@ -30,7 +38,7 @@ impl Sub<&BigFloat> for &BigFloat {
 
 	fn sub(self, rhs: &BigFloat) -> Self::Output {
 		if self.is_zero() {
-			return -rhs.clone();
+			return -rhs;
 		}
 		if rhs.is_zero() {
 			return self.clone();
@@ -139,6 +147,18 @@ mod tests {
 		assert_eq!(&zero - &a, BigFloat::from(-5));
 	}
 
+	#[test]
+	fn test_neg_ref() {
+		let f = |x: f64| BigFloat::try_from(x).unwrap();
+		for x in [f(0.0), f(2.5), f(-0.375), f(1e300)] {
+			let copy = x.clone();
+			assert_eq!(-&x, -x.clone());
+			assert_eq!(x, copy);
+		}
+		assert_eq!(-&f(2.5), f(-2.5));
+		assert!(!(-&BigFloat::ZERO).is_negative());
+	}
+
 	#[test]
 	fn test_sub_different_exponents() {
 		let mut a = BigFloat::from(5);
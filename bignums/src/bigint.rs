@@ -4,12 +4,16 @@ mod cmp;
 mod convert;
 mod div;
 mod mul;
+mod num_theory;
+mod range;
 mod set_val;
 mod str;
 mod sub;
 
 use core::ops::Index;
 
+pub use range::*;
+
 use crate::biguint::BigUInt;
 
 /// Dynamic, arbitrary-sized signed integer type
@@ -1,4 +1,5 @@
 use core::cmp::Ordering;
+use core::iter::Sum;
 use core::ops::{Add, AddAssign};
 
 use crate::bigint::BigInt;
@@ -148,6 +149,24 @@ macro_rules! impl_add {
 
 impl_add! { u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize }
 
+impl Sum for BigInt {
+	fn sum<I: Iterator<Item = BigInt>>(iter: I) -> Self {
+		iter.fold(BigInt::ZERO, |mut acc, x| {
+			acc += &x;
+			acc
+		})
+	}
+}
+
+impl<'a> Sum<&'a BigInt> for BigInt {
+	fn sum<I: Iterator<Item = &'a BigInt>>(iter: I) -> Self {
+		iter.fold(BigInt::ZERO, |mut acc, x| {
+			acc += x;
+			acc
+		})
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -187,4 +206,13 @@ mod tests {
 		assert_eq!(100i32 + BigInt::from(30), BigInt::from(130));
 		assert_eq!(100u32 + BigInt::from(30), BigInt::from(130));
 	}
+
+	#[test]
+	fn test_sum() {
+		let values = [5, -12, 8, i64::MAX as i128, -3].map(BigInt::from);
+		let expected = BigInt::from(i64::MAX as i128 - 2);
+		assert_eq!(values.iter().sum::<BigInt>(), expected);
+		assert_eq!(values.into_iter().sum::<BigInt>(), expected);
+		assert_eq!(core::iter::empty::<&BigInt>().sum::<BigInt>(), BigInt::ZERO);
+	}
 }
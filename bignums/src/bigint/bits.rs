@@ -1,8 +1,125 @@
-// TODO: impl stuff
+use core::ops::{
+	BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Shl, ShlAssign, Shr,
+	ShrAssign,
+};
 
-use core::ops::{Shl, ShlAssign, Shr, ShrAssign};
+use smallvec::SmallVec;
 
 use crate::bigint::BigInt;
+use crate::biguint::BigUInt;
+
+// Bitwise operations behave as if the numbers were stored in infinite two's complement,
+// same as for primitive signed integers.
+impl BigInt {
+	/// Flips all bits in two's complement, i.e. self = -self - 1
+	pub fn not_in_place(&mut self) {
+		self.neg_in_place();
+		*self -= 1;
+	}
+
+	/// Returns self << rhs with the sign preserved, or None if the magnitude would have
+	/// isize::MAX bits or more, see BigUInt::checked_shl
+	pub fn checked_shl(self, rhs: u64) -> Option<BigInt> {
+		let is_negative = self.is_negative;
+		let magnitude = self.magnitude.checked_shl(rhs)?;
+		Some(BigInt::from_sign_and_magnitude(is_negative, magnitude))
+	}
+
+	/// Two's complement digits of self, sign-extended to len digits
+	fn to_twos_complement(&self, len: usize) -> SmallVec<[u64; 2]> {
+		let mut digits: SmallVec<[u64; 2]> = self.magnitude.inner().clone();
+		digits.resize(len, 0u64);
+		if self.is_negative() {
+			negate_twos_complement(&mut digits);
+		}
+		digits
+	}
+
+	fn from_twos_complement(mut digits: SmallVec<[u64; 2]>) -> BigInt {
+		let is_negative = digits.last().is_some_and(|&hi| hi >> (u64::BITS - 1) == 1);
+		if is_negative {
+			negate_twos_complement(&mut digits);
+		}
+		BigInt::from_sign_and_magnitude(is_negative, BigUInt::from_smallvec_le(digits))
+	}
+
+	fn bitwise_op(&mut self, rhs: &BigInt, op: impl Fn(u64, u64) -> u64) {
+		// One extra digit so that the sign bit is never lost
+		let len = self.len().max(rhs.len()) + 1;
+		let mut lhs = self.to_twos_complement(len);
+		let rhs = rhs.to_twos_complement(len);
+		for (x, &y) in lhs.iter_mut().zip(rhs.iter()) {
+			*x = op(*x, y);
+		}
+		*self = BigInt::from_twos_complement(lhs);
+	}
+}
+
+fn negate_twos_complement(digits: &mut [u64]) {
+	let mut carry = true;
+	for x in digits.iter_mut() {
+		(*x, carry) = (!*x).overflowing_add(carry as u64);
+	}
+}
+
+impl Not for BigInt {
+	type Output = BigInt;
+
+	fn not(mut self) -> Self::Output {
+		self.not_in_place();
+		self
+	}
+}
+
+impl Not for &BigInt {
+	type Output = BigInt;
+
+	fn not(self) -> Self::Output {
+		!self.clone()
+	}
+}
+
+macro_rules! impl_bitwise_op {
+	($($trait:ident, $fn:ident, $trait_assign:ident, $fn_assign:ident, $op:tt);*$(;)?) => {$(
+		impl $trait_assign<&BigInt> for BigInt {
+			fn $fn_assign(&mut self, rhs: &BigInt) {
+				self.bitwise_op(rhs, |x, y| x $op y);
+			}
+		}
+
+		impl $trait<&BigInt> for BigInt {
+			type Output = BigInt;
+
+			fn $fn(mut self, rhs: &BigInt) -> Self::Output {
+				self.$fn_assign(rhs);
+				self
+			}
+		}
+
+		impl $trait<BigInt> for &BigInt {
+			type Output = BigInt;
+
+			fn $fn(self, mut rhs: BigInt) -> Self::Output {
+				rhs.$fn_assign(self);
+				rhs
+			}
+		}
+
+		impl $trait<&BigInt> for &BigInt {
+			type Output = BigInt;
+
+			fn $fn(self, rhs: &BigInt) -> Self::Output {
+				self.clone().$fn(rhs)
+			}
+		}
+	)*}
+}
+
+impl_bitwise_op! {
+	BitAnd, bitand, BitAndAssign, bitand_assign, &;
+	BitOr, bitor, BitOrAssign, bitor_assign, |;
+	BitXor, bitxor, BitXorAssign, bitxor_assign, ^;
+}
 
 macro_rules! impl_shl_shr {
 	($($t:ty),*) => {$(
@@ -91,6 +208,24 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn test_checked_shl() {
+		for val in [5i64, -5, -1, i64::MIN] {
+			let n = BigInt::from(val);
+			assert_eq!(
+				n.clone().checked_shl(3),
+				Some(BigInt::from(val as i128 * 8))
+			);
+			assert_eq!(n.clone().checked_shl(100), Some(n.clone() << 100u32));
+			assert_eq!(n.clone().checked_shl(0), Some(n.clone()));
+			assert_eq!(n.checked_shl(u64::MAX), None);
+		}
+		let res = BigInt::from(-3).checked_shl(1000).unwrap();
+		assert!(res.is_negative());
+		assert_eq!(res.inner().trailing_zeros(), 1000);
+		assert_eq!(BigInt::ZERO.checked_shl(u64::MAX), Some(BigInt::ZERO));
+	}
+
 	#[test]
 	fn test_shr() {
 		let cases = vec![
@@ -106,6 +241,32 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn test_bitwise_ops() {
+		let values: [i128; 10] = [
+			0,
+			1,
+			-1,
+			6,
+			-6,
+			3,
+			i64::MIN as i128,
+			u64::MAX as i128,
+			-(u64::MAX as i128) - 1,
+			0x1234_5678_9abc_def0_1234_5678_9abc_def0,
+		];
+
+		for &x in &values {
+			assert_eq!(!BigInt::from(x), BigInt::from(!x));
+			for &y in &values {
+				let (a, b) = (BigInt::from(x), BigInt::from(y));
+				assert_eq!(&a & &b, BigInt::from(x & y), "{x} & {y}");
+				assert_eq!(&a | &b, BigInt::from(x | y), "{x} | {y}");
+				assert_eq!(&a ^ &b, BigInt::from(x ^ y), "{x} ^ {y}");
+			}
+		}
+	}
+
 	#[test]
 	fn test_shr_negative() {
 		let cases = vec![
@@ -3,26 +3,41 @@ use core::cmp::Ordering;
 use crate::bigint::BigInt;
 use crate::biguint::BigUInt;
 
-macro_rules! impl_partial_eq {
+macro_rules! impl_partial_eq_u {
 	($($t:ty),*) => {$(
 		impl PartialEq<$t> for BigInt {
 			fn eq(&self, other: &$t) -> bool {
-				#[allow(clippy::cmp_owned)]
-				let res = *self == BigInt::from(*other);
-				res
+				!self.is_negative() && self.magnitude == *other
 			}
 		}
+
+		impl PartialEq<BigInt> for $t {
+			fn eq(&self, other: &BigInt) -> bool {
+				other == self
+			}
+		}
+	)*};
+}
+
+impl_partial_eq_u! { u8, u16, u32, u64, u128, usize }
+
+macro_rules! impl_partial_eq_i {
+	($($t:ty),*) => {$(
+		impl PartialEq<$t> for BigInt {
+			fn eq(&self, other: &$t) -> bool {
+				self.is_negative() == other.is_negative() && self.magnitude == other.unsigned_abs()
+			}
+		}
+
 		impl PartialEq<BigInt> for $t {
 			fn eq(&self, other: &BigInt) -> bool {
-				#[allow(clippy::cmp_owned)]
-				let res = *other == BigInt::from(*self);
-				res
+				other == self
 			}
 		}
 	)*};
 }
 
-impl_partial_eq! { u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize }
+impl_partial_eq_i! { i8, i16, i32, i64, i128, isize }
 
 impl PartialEq<BigUInt> for BigInt {
 	fn eq(&self, other: &BigUInt) -> bool {
@@ -78,21 +93,87 @@ impl PartialOrd<BigInt> for BigUInt {
 	}
 }
 
-macro_rules! impl_partial_ord {
+macro_rules! impl_partial_ord_u {
+	($($t:ty),*) => {$(
+		impl PartialOrd<$t> for BigInt {
+			fn partial_cmp(&self, other: &$t) -> Option<Ordering> {
+				if self.is_negative() {
+					Some(Ordering::Less)
+				} else {
+					self.magnitude.partial_cmp(other)
+				}
+			}
+		}
+
+		impl PartialOrd<BigInt> for $t {
+			fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+				other.partial_cmp(self).map(Ordering::reverse)
+			}
+		}
+	)*};
+}
+
+impl_partial_ord_u! { u8, u16, u32, u64, u128, usize }
+
+macro_rules! impl_partial_ord_i {
 	($($t:ty),*) => {$(
 		impl PartialOrd<$t> for BigInt {
 			fn partial_cmp(&self, other: &$t) -> Option<Ordering> {
-				#[allow(clippy::cmp_owned)]
-				return self.partial_cmp(&BigInt::from(*other));
+				match (self.is_negative(), other.is_negative()) {
+					(false, true) => Some(Ordering::Greater),
+					(true, false) => Some(Ordering::Less),
+					(false, false) => self.magnitude.partial_cmp(&other.unsigned_abs()),
+					(true, true) => self
+						.magnitude
+						.partial_cmp(&other.unsigned_abs())
+						.map(Ordering::reverse),
+				}
 			}
 		}
+
 		impl PartialOrd<BigInt> for $t {
 			fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
-				#[allow(clippy::cmp_owned)]
-				return BigInt::from(*self).partial_cmp(other);
+				other.partial_cmp(self).map(Ordering::reverse)
 			}
 		}
 	)*};
 }
 
-impl_partial_ord! { u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize }
+impl_partial_ord_i! { i8, i16, i32, i64, i128, isize }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_eq_primitive() {
+		assert_eq!(BigInt::from(0), 0u8);
+		assert_eq!(BigInt::from(-1), -1i8);
+		assert_eq!(BigInt::from(i128::MIN), i128::MIN);
+		assert_eq!(BigInt::from(i128::MAX), i128::MAX);
+		assert_eq!(BigInt::from(u128::MAX), u128::MAX);
+		assert_eq!(i64::MIN, BigInt::from(i64::MIN));
+		assert_ne!(BigInt::from(-1), u64::MAX);
+		assert_ne!(BigInt::from(-5), 5i32);
+		assert_ne!(BigInt::from(u128::MAX), -1i128);
+	}
+
+	#[test]
+	fn test_ord_primitive() {
+		let min = BigInt::from(i128::MIN);
+		let max = BigInt::from(u128::MAX);
+		assert!(BigInt::from(-1) < 0u8);
+		assert!(BigInt::from(-1) < 0i8);
+		assert!(BigInt::from(-2i64) < -1i32);
+		assert!(BigInt::from(-1) > i128::MIN);
+		assert!(min < -1i128);
+		assert!(max > i128::MAX);
+		assert!(max >= u128::MAX);
+		assert!(min <= i128::MIN);
+		assert!(0u8 > BigInt::from(-1));
+		assert!(i128::MIN < BigInt::from(i64::MIN));
+		assert!(u64::MAX < BigInt::from(u128::MAX));
+		assert!(BigInt::from(3) > 2usize);
+		assert!(BigInt::from(-3) < -2isize);
+	}
+}
@@ -1,9 +1,11 @@
+use alloc::vec::Vec;
 use core::convert::{TryFrom, TryInto};
 
-use crate::SetVal;
 use crate::bigint::BigInt;
 use crate::biguint::BigUInt;
-use crate::error::TryIntoIntError;
+use crate::error::{TryFromFloatError, TryIntoIntError};
+use crate::rational::Rational;
+use crate::{SetVal, util};
 
 impl From<BigUInt> for BigInt {
 	fn from(val: BigUInt) -> Self {
@@ -28,6 +30,64 @@ macro_rules! impl_from {
 
 impl_from! { u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize }
 
+impl TryFrom<f64> for BigInt {
+	type Error = TryFromFloatError;
+
+	/// Converts a float to an integer, truncating toward zero
+	fn try_from(val: f64) -> Result<Self, Self::Error> {
+		BigInt::from_f64_impl(val, false)
+	}
+}
+
+impl BigInt {
+	/// Returns whether self is negative, and the big-endian bytes of the magnitude
+	/// without leading zeros. Zero is (false, []).
+	pub fn to_bytes_with_sign(&self) -> (bool, Vec<u8>) {
+		(self.is_negative(), self.magnitude.to_bytes_be())
+	}
+
+	/// Returns the digits of the magnitude in little-endian, without leading zeros,
+	/// see BigUInt::as_limbs
+	pub fn magnitude_limbs(&self) -> &[u64] {
+		self.magnitude.as_limbs()
+	}
+
+	/// Inverse of to_bytes_with_sign. A zero magnitude gives 0 regardless of the sign.
+	pub fn from_bytes_with_sign(is_negative: bool, bytes: &[u8]) -> BigInt {
+		BigInt::from_sign_and_magnitude(is_negative, BigUInt::from_bytes_be(bytes))
+	}
+
+	/// Returns self as a fraction with denominator one
+	pub fn to_rational(&self) -> Rational {
+		Rational::from(self.clone())
+	}
+
+	/// Converts a float to the nearest integer, rounding half away from zero like f64::round
+	pub fn from_f64_round(val: f64) -> Result<Self, TryFromFloatError> {
+		BigInt::from_f64_impl(val, true)
+	}
+
+	fn from_f64_impl(val: f64, round: bool) -> Result<Self, TryFromFloatError> {
+		let (is_negative, m, e) = util::f64_to_parts(val)?;
+		// val = m * 2^(e - 52)
+		let shift = e - 52;
+		let magnitude = if shift >= 0 {
+			BigUInt::from(m) << shift
+		} else if shift <= -64 {
+			// m < 2^53, so |val| < 2^-11
+			BigUInt::ZERO
+		} else {
+			let shift = -shift as u32;
+			let mut int = m >> shift;
+			if round && (m >> (shift - 1)) & 1 == 1 {
+				int += 1;
+			}
+			BigUInt::from(int)
+		};
+		Ok(BigInt::from_sign_and_magnitude(is_negative, magnitude))
+	}
+}
+
 macro_rules! impl_try_into_u {
 	($($t:ty),*) => {$(
 		impl TryFrom<&BigInt> for $t {
@@ -82,3 +142,126 @@ macro_rules! from_sign_and_magnitude {
 }
 
 from_sign_and_magnitude! { u8 => i8, u16 => i16, u32 => i32, u64 => i64, u128 => i128, usize => isize }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_bytes_with_sign() {
+		for x in [
+			0i128,
+			1,
+			-1,
+			255,
+			-256,
+			i64::MIN as i128,
+			i128::MAX,
+			i128::MIN,
+		] {
+			let a = BigInt::from(x);
+			let (is_negative, bytes) = a.to_bytes_with_sign();
+			assert_eq!(is_negative, x < 0);
+			assert_eq!(
+				bytes,
+				x.unsigned_abs().to_be_bytes()[(x.unsigned_abs().leading_zeros() / 8) as usize..]
+			);
+			assert_eq!(BigInt::from_bytes_with_sign(is_negative, &bytes), a);
+		}
+
+		let big = -(BigInt::from(3) << 1000u32);
+		let (is_negative, bytes) = big.to_bytes_with_sign();
+		assert!(is_negative);
+		assert_eq!(bytes.len(), 126);
+		assert_eq!(BigInt::from_bytes_with_sign(true, &bytes), big);
+
+		// Zero is never negative, and leading zero bytes are ignored
+		assert_eq!(BigInt::ZERO.to_bytes_with_sign(), (false, Vec::new()));
+		for zero in [
+			BigInt::from_bytes_with_sign(true, &[]),
+			BigInt::from_bytes_with_sign(true, &[0, 0]),
+			BigInt::from_bytes_with_sign(false, &[]),
+		] {
+			assert_eq!(zero, BigInt::ZERO);
+			assert!(!zero.is_negative());
+			assert_eq!(zero.to_bytes_with_sign(), (false, Vec::new()));
+		}
+		assert_eq!(
+			BigInt::from_bytes_with_sign(true, &[0, 0, 5]),
+			BigInt::from(-5)
+		);
+	}
+
+	#[test]
+	fn test_magnitude_limbs() {
+		assert!(BigInt::ZERO.magnitude_limbs().is_empty());
+		assert_eq!(BigInt::from(-1).magnitude_limbs(), &[1]);
+		assert_eq!(
+			BigInt::from(u128::MAX).magnitude_limbs(),
+			&[u64::MAX, u64::MAX]
+		);
+		assert_eq!(BigInt::from(i128::MIN).magnitude_limbs(), &[0, 1 << 63]);
+		// The sign doesn't matter
+		let a = BigInt::from(3) << 100u32;
+		assert_eq!(a.magnitude_limbs(), (-a.clone()).magnitude_limbs());
+		assert_eq!(a.magnitude_limbs(), a.inner().as_limbs());
+		assert_eq!((a.clone() - &a).magnitude_limbs(), &[] as &[u64]);
+	}
+
+	#[test]
+	fn test_try_from_f64() {
+		let cases: [(f64, i128); 10] = [
+			(0.0, 0),
+			(-0.0, 0),
+			(3.9, 3),
+			(-3.9, -3),
+			(0.5, 0),
+			(-0.99, 0),
+			(1e-310, 0),
+			(4503599627370497.0, 4503599627370497),
+			(-9007199254740992.0, -9007199254740992),
+			(1e30, 1_000_000_000_000_000_019_884_624_838_656),
+		];
+		for (val, expected) in cases {
+			assert_eq!(BigInt::try_from(val), Ok(BigInt::from(expected)), "{val}");
+		}
+
+		let big = BigInt::try_from(2f64.powi(200)).unwrap();
+		assert_eq!(big, BigInt::ONE << 200);
+
+		assert_eq!(BigInt::try_from(f64::NAN), Err(TryFromFloatError::NaN));
+		assert_eq!(
+			BigInt::try_from(f64::NEG_INFINITY),
+			Err(TryFromFloatError::Infinite)
+		);
+	}
+
+	#[test]
+	fn test_from_f64_round() {
+		let cases: [f64; 12] = [
+			0.0,
+			3.9,
+			-3.9,
+			3.1,
+			-3.1,
+			0.5,
+			-0.5,
+			2.5,
+			-2.5,
+			0.49999999999999994,
+			1e-310,
+			1e20,
+		];
+		for val in cases {
+			assert_eq!(
+				BigInt::from_f64_round(val),
+				Ok(BigInt::from(val.round() as i128)),
+				"{val}"
+			);
+		}
+		assert_eq!(
+			BigInt::from_f64_round(f64::INFINITY),
+			Err(TryFromFloatError::Infinite)
+		);
+	}
+}
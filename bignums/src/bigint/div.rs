@@ -10,7 +10,8 @@ impl BigInt {
 			return (q, r);
 		}
 
-		if q.is_negative() {
+		// The quotient may have been truncated to zero, so look at the signs of r and d
+		if r.is_negative() != d.is_negative() {
 			q -= 1;
 			r += &*d;
 		}
@@ -264,6 +265,24 @@ mod tests {
 		assert_eq!(r, BigInt::from(-10));
 	}
 
+	#[test]
+	fn test_bigint_div_rem_floor() {
+		for (a, b, q, r) in [
+			(100, 30, 3, 10),
+			(-100, 30, -4, 20),
+			(100, -30, -4, -20),
+			(-100, -30, 3, -10),
+			(-3, 4, -1, 1),
+			(3, -4, -1, -1),
+			(-3, -4, 0, -3),
+			(-90, 30, -3, 0),
+		] {
+			let mut a = BigInt::from(a);
+			let mut b = BigInt::from(b);
+			assert_eq!(a.div_rem_floor(&mut b), (BigInt::from(q), BigInt::from(r)));
+		}
+	}
+
 	#[test]
 	#[should_panic]
 	fn test_bigint_div_by_zero() {
@@ -1,3 +1,4 @@
+use core::iter::Product;
 use core::ops::{Mul, MulAssign};
 
 use crate::bigint::BigInt;
@@ -55,6 +56,38 @@ impl Mul<&BigInt> for &BigUInt {
 	}
 }
 
+impl BigInt {
+	/// Returns self^power, where 0^0 = 1
+	pub fn pow(&self, power: u64) -> BigInt {
+		let is_negative = self.is_negative() && power % 2 == 1;
+		BigInt::from_sign_and_magnitude(is_negative, self.magnitude.pow(power))
+	}
+
+	/// Returns self^power, or None if its magnitude could be 2^max_log2 or more,
+	/// see BigUInt::checked_pow
+	pub fn checked_pow(&self, power: u64, max_log2: u64) -> Option<BigInt> {
+		let is_negative = self.is_negative() && power % 2 == 1;
+		let magnitude = self.magnitude.checked_pow(power, max_log2)?;
+		Some(BigInt::from_sign_and_magnitude(is_negative, magnitude))
+	}
+
+	/// floor(sqrt(self)), see BigUInt::isqrt
+	/// # Panics
+	/// If self is negative
+	pub fn isqrt(&self) -> BigInt {
+		assert!(
+			!self.is_negative(),
+			"attempt to take isqrt of a negative number"
+		);
+		BigInt::from(self.magnitude.isqrt())
+	}
+
+	/// Cube root rounded toward zero, so cbrt(-x) = -cbrt(x)
+	pub fn cbrt(&self) -> BigInt {
+		BigInt::from_sign_and_magnitude(self.is_negative(), self.magnitude.nth_root(3))
+	}
+}
+
 impl MulAssign<&BigInt> for BigInt {
 	fn mul_assign(&mut self, rhs: &BigInt) {
 		let mut res = BigInt::ZERO;
@@ -107,9 +140,30 @@ macro_rules! impl_mul {
 
 impl_mul! { u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize }
 
+impl Product for BigInt {
+	fn product<I: Iterator<Item = BigInt>>(iter: I) -> Self {
+		iter.fold(BigInt::ONE, |mut acc, x| {
+			acc *= &x;
+			acc
+		})
+	}
+}
+
+impl<'a> Product<&'a BigInt> for BigInt {
+	fn product<I: Iterator<Item = &'a BigInt>>(iter: I) -> Self {
+		iter.fold(BigInt::ONE, |mut acc, x| {
+			acc *= x;
+			acc
+		})
+	}
+}
+
 #[cfg(test)]
 mod tests {
+	use alloc::vec::Vec;
+
 	use super::*;
+	use crate::util::{from_foreign_bigint, to_foreign_bigint, to_foreign_biguint, xorshift64};
 
 	#[test]
 	fn test_bigint_mul() {
@@ -153,4 +207,152 @@ mod tests {
 		b *= 200i32;
 		assert_eq!(b, BigInt::from(20000));
 	}
+
+	#[test]
+	fn test_product() {
+		let values = [-5, 12, i64::MAX as i128, -3].map(BigInt::from);
+		let expected = BigInt::from(-5 * 12 * i64::MAX as i128 * -3);
+		assert_eq!(values.iter().product::<BigInt>(), expected);
+		assert_eq!(values.into_iter().product::<BigInt>(), expected);
+		assert_eq!(
+			core::iter::empty::<&BigInt>().product::<BigInt>(),
+			BigInt::ONE
+		);
+	}
+
+	#[test]
+	fn test_pow() {
+		for base in [-7i128, -2, -1, 0, 1, 3, 10] {
+			for power in 0..20u32 {
+				let expected = BigInt::from(base.pow(power));
+				assert_eq!(BigInt::from(base).pow(power as u64), expected);
+				assert_eq!(
+					BigInt::from(base).checked_pow(power as u64, 128),
+					Some(expected)
+				);
+			}
+		}
+		assert_eq!(
+			BigInt::from(-1).checked_pow(u64::MAX, 1),
+			Some(BigInt::NEG_ONE)
+		);
+		assert_eq!(
+			BigInt::from(-1).checked_pow(u64::MAX - 1, 1),
+			Some(BigInt::ONE)
+		);
+		assert_eq!(BigInt::from(-3).checked_pow(u64::MAX, u64::MAX), None);
+		assert_eq!(
+			BigInt::from(-3).checked_pow(33, 66),
+			Some(BigInt::from(-3i128.pow(33)))
+		);
+		assert_eq!(BigInt::from(-3).checked_pow(33, 65), None);
+	}
+
+	#[test]
+	fn test_isqrt_cbrt() {
+		for n in 0..500i64 {
+			let x = BigInt::from(n);
+			assert_eq!(x.isqrt(), BigInt::from(n.isqrt()), "{n}");
+			let r = x.cbrt();
+			assert!(r.pow(3) <= x && (r.clone() + 1u32).pow(3) > x, "{n}");
+			assert_eq!((-&x).cbrt(), -r, "{n}");
+		}
+
+		let ten_30 = BigInt::from(10).pow(30);
+		assert_eq!(ten_30.pow(2).isqrt(), ten_30);
+		assert_eq!((ten_30.pow(2) - 1u32).isqrt(), ten_30.clone() - 1u32);
+		assert_eq!(ten_30.pow(3).cbrt(), ten_30);
+		assert_eq!((-ten_30.pow(3)).cbrt(), -&ten_30);
+		// Rounded toward zero
+		assert_eq!((ten_30.pow(3) + 1u32).cbrt(), ten_30);
+		assert_eq!((-(ten_30.pow(3) + 1u32)).cbrt(), -&ten_30);
+		assert_eq!((-(ten_30.pow(3) - 1u32)).cbrt(), -(ten_30 - 1u32));
+		assert_eq!(BigInt::from(-27).cbrt(), BigInt::from(-3));
+		assert_eq!(BigInt::from(-26).cbrt(), BigInt::from(-2));
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_isqrt_negative() {
+		BigInt::from(-4).isqrt();
+	}
+
+	/// Deterministic pseudo-random signed values of the given lengths in limbs
+	fn random_bigints(lens: &[usize]) -> Vec<BigInt> {
+		let mut next = xorshift64(0x9E3779B97F4A7C15);
+
+		lens.iter()
+			.map(|&len| {
+				let magnitude = BigUInt::from_vec_le((0..len).map(|_| next()).collect());
+				BigInt::from_sign_and_magnitude(next() & 1 == 1, magnitude)
+			})
+			.collect()
+	}
+
+	#[test]
+	fn test_bigint_mul_large_foreign() {
+		let mut values = random_bigints(&[1, 2, 7, 32, 33, 64, 100, 257]);
+		values.push(BigInt::ZERO);
+		values.push(BigInt::NEG_ONE);
+		values.push(-BigInt::from_sign_and_magnitude(
+			false,
+			BigUInt::ONE << 4096u32,
+		));
+
+		for a in &values {
+			for b in &values {
+				let expected = to_foreign_bigint(a.clone()) * to_foreign_bigint(b.clone());
+				let res = a * b;
+				assert_eq!(to_foreign_bigint(res.clone()), expected, "{a} * {b}");
+				assert_eq!(
+					res.is_negative(),
+					!res.is_zero() && a.is_negative() != b.is_negative()
+				);
+
+				let mut assigned = a.clone();
+				assigned *= b;
+				assert_eq!(assigned, res);
+
+				let mut into = BigInt::from(-5);
+				into.mul_to(a, b);
+				assert_eq!(into, res);
+			}
+		}
+	}
+
+	#[test]
+	fn test_bigint_mul_zero_operand() {
+		let values = random_bigints(&[1, 40, 300]);
+		for a in &values {
+			// The result reuses a non-zero, negative target, which has to be cleared
+			let mut res = BigInt::from(-12345);
+			res.mul_to(a, &BigInt::ZERO);
+			assert!(res.is_zero() && !res.is_negative());
+			res = BigInt::from(-12345);
+			res.mul_to(&BigInt::ZERO, a);
+			assert!(res.is_zero() && !res.is_negative());
+			res = BigInt::from(-12345);
+			res.mul_to_u(a, &BigUInt::ZERO);
+			assert!(res.is_zero() && !res.is_negative());
+			assert_eq!((&BigUInt::ZERO * a).to_string(), "0");
+		}
+	}
+
+	#[test]
+	fn test_bigint_mul_biguint_large_foreign() {
+		let values = random_bigints(&[1, 3, 32, 65, 128]);
+		for a in &values {
+			for b in &values {
+				let b = b.inner();
+				let expected = to_foreign_bigint(a.clone())
+					* num_bigint::BigInt::from(to_foreign_biguint(b.clone()));
+				assert_eq!(from_foreign_bigint(expected.clone()), a * b);
+				assert_eq!(from_foreign_bigint(expected.clone()), b * a);
+
+				let mut assigned = a.clone();
+				assigned *= b;
+				assert_eq!(to_foreign_bigint(assigned), expected);
+			}
+		}
+	}
 }
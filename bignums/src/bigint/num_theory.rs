@@ -0,0 +1,236 @@
+use core::mem;
+
+use crate::bigint::BigInt;
+use crate::biguint::BigUInt;
+
+impl BigInt {
+	/// Returns the Jacobi symbol (a/n), which is one of -1, 0 or 1.
+	/// For prime n it's the Legendre symbol, i.e. whether a is a quadratic residue mod n.
+	/// # Panics
+	/// If n is even or zero.
+	pub fn jacobi(a: &BigInt, n: &BigUInt) -> i8 {
+		assert!(n.bit(0), "Jacobi symbol requires an odd positive n");
+
+		let is_negative = a.is_negative();
+		let mut n = n.clone();
+		let mut a = &mut a.magnitude.clone() % &mut n;
+		let mut res = 1i8;
+
+		// (-1/n) = -1 iff n = 3 mod 4
+		if is_negative && n.bit_range(0, 2) == 3 {
+			res = -res;
+		}
+
+		while !a.is_zero() {
+			// (2/n) = -1 iff n = 3 or 5 mod 8
+			let twos = a.trailing_zeros();
+			a >>= twos;
+			if twos % 2 == 1 && matches!(n.bit_range(0, 3), 3 | 5) {
+				res = -res;
+			}
+
+			// Quadratic reciprocity, (a/n) = -(n/a) iff a = n = 3 mod 4
+			if a.bit_range(0, 2) == 3 && n.bit_range(0, 2) == 3 {
+				res = -res;
+			}
+			mem::swap(&mut a, &mut n);
+			a = &mut a % &mut n;
+		}
+
+		if n.is_one() { res } else { 0 }
+	}
+
+	/// Returns self * (self - 1) * ... * (self - k + 1), which is 1 for k = 0
+	pub fn falling_factorial(&self, k: u64) -> BigInt {
+		let mut res = BigInt::ONE;
+		let mut factor = self.clone();
+		for _ in 0..k {
+			res *= &factor;
+			factor -= 1u32;
+		}
+		res
+	}
+
+	/// Returns self * (self + 1) * ... * (self + k - 1), which is 1 for k = 0
+	pub fn rising_factorial(&self, k: u64) -> BigInt {
+		let mut res = BigInt::ONE;
+		let mut factor = self.clone();
+		for _ in 0..k {
+			res *= &factor;
+			factor += 1u32;
+		}
+		res
+	}
+
+	/// Binomial coefficient self choose k, defined as self * (self - 1) * ... * (self - k + 1) / k!
+	/// also for negative self, where binomial(-n, k) = (-1)^k * binomial(n + k - 1, k).
+	pub fn binomial(&self, k: u64) -> BigInt {
+		// After step i, res = self * ... * (self - i + 1) / i!, which is an integer
+		let mut res = BigInt::ONE;
+		let mut factor = self.clone();
+		for i in 1..=k {
+			res *= &factor;
+			let rem = res.magnitude.div_rem_u64_assign(i);
+			debug_assert_eq!(rem, 0, "binomial step is not exact");
+			res.normalize();
+			if res.is_zero() {
+				break;
+			}
+			factor -= 1u32;
+		}
+		res
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn jacobi(a: i64, n: u64) -> i8 {
+		BigInt::jacobi(&BigInt::from(a), &BigUInt::from(n))
+	}
+
+	#[test]
+	fn test_jacobi_table() {
+		let n15 = [0, 1, 1, 0, 1, 0, 0, -1, 1, 0, 0, -1, 0, -1, -1];
+		let n21 = [
+			0, 1, -1, 0, 1, 1, 0, 0, -1, 0, -1, -1, 0, -1, 0, 0, 1, 1, 0, -1, 1,
+		];
+		for (a, &expected) in n15.iter().enumerate() {
+			assert_eq!(jacobi(a as i64, 15), expected, "({a}/15)");
+			assert_eq!(jacobi(a as i64 + 15 * 7, 15), expected, "({a}/15)");
+		}
+		for (a, &expected) in n21.iter().enumerate() {
+			assert_eq!(jacobi(a as i64, 21), expected, "({a}/21)");
+			assert_eq!(jacobi(a as i64 - 21, 21), expected, "({a}/21)");
+		}
+
+		assert_eq!(jacobi(0, 1), 1);
+		assert_eq!(jacobi(5, 1), 1);
+		assert_eq!(jacobi(-1, 5), 1);
+		assert_eq!(jacobi(-1, 7), -1);
+		assert_eq!(jacobi(-3, 45), 0);
+		assert_eq!(jacobi(-2, 45), -1);
+		assert_eq!(jacobi(1001, 9907), -1);
+		assert_eq!(jacobi(19, 45), 1);
+		assert_eq!(jacobi(8, 21), -1);
+		assert_eq!(jacobi(5, 21), 1);
+	}
+
+	#[test]
+	fn test_legendre_matches_euler_criterion() {
+		// For prime p, (a/p) = a^((p-1)/2) mod p
+		for p in [3u64, 5, 7, 11, 13, 101, 65537] {
+			for a in 0..200u64 {
+				let mut pow = 1u128;
+				let mut base = (a % p) as u128;
+				let mut e = (p - 1) / 2;
+				while e > 0 {
+					if e & 1 == 1 {
+						pow = pow * base % p as u128;
+					}
+					base = base * base % p as u128;
+					e >>= 1;
+				}
+				let expected = match pow as u64 {
+					0 => 0,
+					1 => 1,
+					_ => -1,
+				};
+				assert_eq!(jacobi(a as i64, p), expected, "({a}/{p})");
+			}
+		}
+	}
+
+	#[test]
+	fn test_jacobi_large() {
+		// (a/mn) = (a/m)(a/n)
+		let m = BigUInt::from(u64::MAX - 58); // largest 64 bit prime
+		let n = (BigUInt::ONE << 127u32) - &BigUInt::ONE;
+		let mn = &m * &n;
+		for a in [2i64, 3, -5, 12345, i64::MIN + 1] {
+			let a = &BigInt::from(a) * 1_000_000_007i64;
+			assert_eq!(
+				BigInt::jacobi(&a, &mn),
+				BigInt::jacobi(&a, &m) * BigInt::jacobi(&a, &n)
+			);
+		}
+		assert_eq!(BigInt::jacobi(&BigInt::from(m.clone()), &mn), 0);
+		assert_eq!(BigInt::jacobi(&-BigInt::from(n.clone()), &mn), 0);
+	}
+
+	#[test]
+	fn test_binomial() {
+		let binomial = |n: i64, k: u64| BigInt::from(n).binomial(k);
+		assert_eq!(binomial(-1, 3), BigInt::from(-1));
+		assert_eq!(binomial(-1, 4), BigInt::from(1));
+		assert_eq!(binomial(-3, 2), BigInt::from(6));
+		assert_eq!(binomial(-2, 5), BigInt::from(-6));
+		assert_eq!(binomial(5, 2), BigInt::from(10));
+		assert_eq!(binomial(5, 7), BigInt::ZERO);
+		assert_eq!(binomial(0, 0), BigInt::ONE);
+		assert_eq!(binomial(-7, 0), BigInt::ONE);
+
+		// Matches the unsigned version, and the reflection formula for negative n
+		for n in 0..40i64 {
+			for k in 0..45u64 {
+				let expected = BigUInt::from(n as u64).binomial(k);
+				assert_eq!(
+					binomial(n, k),
+					BigInt::from(expected.clone()),
+					"{n} choose {k}"
+				);
+
+				let reflected = BigInt::from(n + k as i64).binomial(k);
+				let sign = if k % 2 == 0 { 1 } else { -1 };
+				assert_eq!(
+					binomial(-n - 1, k),
+					&reflected * sign,
+					"-{} choose {k}",
+					n + 1
+				);
+			}
+		}
+
+		let big = BigInt::from(u128::MAX);
+		assert_eq!(big.binomial(1), big);
+		assert_eq!(&big.binomial(2) * 2, &big * &(big.clone() - 1u32));
+	}
+
+	#[test]
+	fn test_falling_rising_factorial() {
+		let falling = |n: i64, k: u64| BigInt::from(n).falling_factorial(k);
+		let rising = |n: i64, k: u64| BigInt::from(n).rising_factorial(k);
+		assert_eq!(falling(5, 3), BigInt::from(60));
+		assert_eq!(rising(5, 3), BigInt::from(210));
+		assert_eq!(falling(3, 5), BigInt::ZERO);
+		assert_eq!(rising(-3, 3), BigInt::from(-6));
+		assert_eq!(rising(-3, 5), BigInt::ZERO);
+		assert_eq!(falling(-2, 3), BigInt::from(-24));
+		assert_eq!(falling(9, 0), BigInt::ONE);
+		assert_eq!(rising(9, 0), BigInt::ONE);
+
+		for n in -10..10i64 {
+			for k in 0..8u64 {
+				// rising(n, k) = (-1)^k * falling(-n, k)
+				let sign = if k % 2 == 0 { 1 } else { -1 };
+				assert_eq!(rising(n, k), &falling(-n, k) * sign);
+				// falling(n, k) = binomial(n, k) * k!
+				let k_factorial = BigInt::from(BigUInt::from(k).factorial());
+				assert_eq!(falling(n, k), &BigInt::from(n).binomial(k) * &k_factorial);
+			}
+		}
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_jacobi_even() {
+		jacobi(3, 10);
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_jacobi_zero() {
+		jacobi(3, 0);
+	}
+}
@@ -0,0 +1,213 @@
+use core::iter::FusedIterator;
+
+use crate::bigint::BigInt;
+use crate::error::ZeroStepError;
+
+/// Iterator over the half-open range [start, end) of BigInts
+/// Created by BigInt::range and BigIntRange::with_step
+#[derive(Debug, Clone)]
+pub struct BigIntRange {
+	next: BigInt,
+	end: BigInt,
+	step: BigInt,
+}
+
+impl BigInt {
+	/// Returns an iterator over start, start + 1, ..., end - 1
+	/// The range is empty if start >= end
+	pub fn range(start: BigInt, end: BigInt) -> BigIntRange {
+		BigIntRange {
+			next: start,
+			end,
+			step: BigInt::ONE,
+		}
+	}
+
+	/// Returns floor((self + other) / 2)
+	pub fn midpoint(&self, other: &BigInt) -> BigInt {
+		(self.clone() + other) >> 1u32
+	}
+
+	/// Binary search for the point in lo..hi where pred flips from false to true.
+	/// pred must be false below some x and true from x on, which is returned.
+	/// pred is only called on lo..hi, so hi is returned if pred is false on all of them.
+	/// # Panics
+	/// If lo > hi
+	pub fn bisect(mut lo: BigInt, mut hi: BigInt, mut pred: impl FnMut(&BigInt) -> bool) -> BigInt {
+		assert!(lo <= hi, "bisect requires lo <= hi");
+		while lo < hi {
+			let mid = lo.midpoint(&hi);
+			if pred(&mid) {
+				hi = mid;
+			} else {
+				lo = mid + 1u32;
+			}
+		}
+		lo
+	}
+}
+
+impl BigIntRange {
+	/// Changes the step of the range
+	/// A negative step yields a descending range, which is empty if start <= end
+	pub fn with_step(self, step: BigInt) -> Result<BigIntRange, ZeroStepError> {
+		if step.is_zero() {
+			return Err(ZeroStepError);
+		}
+		Ok(BigIntRange { step, ..self })
+	}
+
+	pub fn step(&self) -> &BigInt {
+		&self.step
+	}
+
+	fn is_finished(&self) -> bool {
+		if self.step.is_negative() {
+			self.next <= self.end
+		} else {
+			self.next >= self.end
+		}
+	}
+}
+
+impl Iterator for BigIntRange {
+	type Item = BigInt;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.is_finished() {
+			return None;
+		}
+		let res = self.next.clone();
+		self.next += &self.step;
+		Some(res)
+	}
+}
+
+impl FusedIterator for BigIntRange {}
+
+#[cfg(test)]
+mod tests {
+	use alloc::vec::Vec;
+
+	use super::*;
+	use crate::util::xorshift64;
+
+	/// floor(sqrt(n)) as the last x with x^2 <= n
+	fn isqrt(n: &BigInt) -> BigInt {
+		BigInt::bisect(BigInt::ZERO, n.clone() + 1u32, |x| x * x > *n) - 1u32
+	}
+
+	#[test]
+	fn test_bisect_isqrt() {
+		for n in (0..2000u128).chain([u64::MAX as u128, u128::MAX - 1, u128::MAX]) {
+			assert_eq!(isqrt(&BigInt::from(n)), BigInt::from(n.isqrt()), "{n}");
+		}
+
+		let mut next = xorshift64(0x9E3779B97F4A7C15);
+		for _ in 0..200 {
+			let n = ((next() as u128) << 64) | next() as u128;
+			assert_eq!(isqrt(&BigInt::from(n)), BigInt::from(n.isqrt()), "{n}");
+		}
+
+		let ten_50 = BigInt::from(10).pow(50);
+		let ten_100 = &ten_50 * &ten_50;
+		assert_eq!(isqrt(&ten_100), ten_50);
+		assert_eq!(isqrt(&(ten_100.clone() - 1u32)), ten_50.clone() - 1u32);
+		assert_eq!(isqrt(&(ten_100.clone() + &(&ten_50 * 2))), ten_50);
+
+		let x = BigInt::from(3).pow(1000) + 12345u32;
+		let r = isqrt(&x);
+		assert!(&r * &r <= x);
+		let r1 = r + 1u32;
+		assert!(&r1 * &r1 > x);
+	}
+
+	#[test]
+	fn test_bisect() {
+		let bisect = |lo: i64, hi: i64, x: i64| {
+			BigInt::bisect(BigInt::from(lo), BigInt::from(hi), |y| *y >= x)
+		};
+		assert_eq!(bisect(-100, 100, -37), BigInt::from(-37));
+		assert_eq!(bisect(-100, 100, -100), BigInt::from(-100));
+		assert_eq!(bisect(-100, 100, 99), BigInt::from(99));
+		// pred is false everywhere, or true everywhere
+		assert_eq!(bisect(-100, 100, 1000), BigInt::from(100));
+		assert_eq!(bisect(-100, 100, -1000), BigInt::from(-100));
+		assert_eq!(bisect(5, 5, 0), BigInt::from(5));
+
+		// pred is never called outside lo..hi
+		let mut calls = 0;
+		let res = BigInt::bisect(BigInt::from(-8), BigInt::from(8), |y| {
+			calls += 1;
+			assert!(*y >= -8i64 && *y < 8i64);
+			false
+		});
+		assert_eq!(res, BigInt::from(8));
+		assert_eq!(calls, 4);
+
+		// Cube root via bisect
+		let n = BigInt::from(7).pow(300);
+		let root = BigInt::bisect(BigInt::ZERO, n.clone(), |y| &(y * y) * y >= n);
+		assert_eq!(root, BigInt::from(7).pow(100));
+	}
+
+	#[test]
+	fn test_midpoint() {
+		for a in -6..6i64 {
+			for b in -6..6i64 {
+				let expected = BigInt::from((a + b).div_euclid(2));
+				assert_eq!(BigInt::from(a).midpoint(&BigInt::from(b)), expected);
+			}
+		}
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_bisect_reversed() {
+		BigInt::bisect(BigInt::ONE, BigInt::ZERO, |_| true);
+	}
+
+	#[test]
+	fn test_range() {
+		let v: Vec<BigInt> = BigInt::range(BigInt::from(-2), BigInt::from(3)).collect();
+		let expected: Vec<BigInt> = (-2..3).map(BigInt::from).collect();
+		assert_eq!(v, expected);
+
+		assert_eq!(BigInt::range(BigInt::from(3), BigInt::from(3)).count(), 0);
+		assert_eq!(BigInt::range(BigInt::from(5), BigInt::from(3)).count(), 0);
+
+		let n = 1000u32;
+		let sum =
+			BigInt::range(BigInt::ONE, BigInt::from(n + 1)).fold(BigInt::ZERO, |acc, x| acc + &x);
+		assert_eq!(sum, BigInt::from(n * (n + 1) / 2));
+	}
+
+	#[test]
+	fn test_range_with_step() {
+		let v: Vec<BigInt> = BigInt::range(BigInt::from(1), BigInt::from(10))
+			.with_step(BigInt::from(3))
+			.unwrap()
+			.collect();
+		let expected: Vec<BigInt> = [1, 4, 7].into_iter().map(BigInt::from).collect();
+		assert_eq!(v, expected);
+
+		let v: Vec<BigInt> = BigInt::range(BigInt::from(10), BigInt::from(1))
+			.with_step(BigInt::from(-4))
+			.unwrap()
+			.collect();
+		let expected: Vec<BigInt> = [10, 6, 2].into_iter().map(BigInt::from).collect();
+		assert_eq!(v, expected);
+
+		let empty = BigInt::range(BigInt::from(1), BigInt::from(10))
+			.with_step(BigInt::NEG_ONE)
+			.unwrap();
+		assert_eq!(empty.count(), 0);
+
+		assert_eq!(
+			BigInt::range(BigInt::ZERO, BigInt::ONE)
+				.with_step(BigInt::ZERO)
+				.unwrap_err(),
+			ZeroStepError
+		);
+	}
+}
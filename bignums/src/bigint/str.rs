@@ -1,4 +1,5 @@
 use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use core::fmt::{Binary, Debug, Display, Formatter, LowerHex, Octal, UpperHex};
 use core::str::FromStr;
 
@@ -79,7 +80,14 @@ impl BigInt {
 		Self::from_ascii_radix(src, 10)
 	}
 
-	pub fn from_ascii_radix(mut src: &[u8], radix: u32) -> Result<Self, ParseIntError> {
+	/// Parses an optional sign followed by digits. Leading and trailing ASCII whitespace
+	/// is ignored, whitespace between the sign and the digits or between digits is not.
+	pub fn from_ascii_radix(src: &[u8], radix: u32) -> Result<Self, ParseIntError> {
+		Self::parse_untrimmed(src.trim_ascii(), radix)
+	}
+
+	/// Same as from_ascii_radix, without trimming whitespace, for parsing parts of a larger number
+	pub(crate) fn parse_untrimmed(mut src: &[u8], radix: u32) -> Result<Self, ParseIntError> {
 		if src.is_empty() {
 			return Err(ParseIntError::Empty);
 		}
@@ -91,7 +99,7 @@ impl BigInt {
 			src = src.split_at(1).1;
 		}
 
-		if src[0] == b'+' {
+		if src.first() == Some(&b'+') {
 			src = src.split_at(1).1;
 		}
 		let mag = BigUInt::parse_helper(src, radix)?;
@@ -107,13 +115,68 @@ impl BigInt {
 		res += &self.magnitude.to_string_radix(radix, uppercase);
 		res
 	}
+
+	/// Formats self in balanced ternary, with the digits T = -1, 0 and 1 and no sign
+	pub fn to_balanced_ternary(&self) -> String {
+		let ternary = self.magnitude.to_string_radix(3, false);
+		// Replace the digit 2 by 3 - 1, carrying from the least significant digit
+		let mut digits = Vec::with_capacity(ternary.len() + 1);
+		let mut carry = 0;
+		for c in ternary.bytes().rev() {
+			let digit = (c - b'0') + carry;
+			carry = (digit >= 2) as u8;
+			digits.push(match digit % 3 {
+				0 => b'0',
+				1 => b'1',
+				_ => b'T',
+			});
+		}
+		if carry == 1 {
+			digits.push(b'1');
+		}
+		if self.is_negative() {
+			for digit in &mut digits {
+				*digit = match *digit {
+					b'1' => b'T',
+					b'T' => b'1',
+					d => d,
+				};
+			}
+		}
+		digits.reverse();
+		String::from_utf8(digits).unwrap()
+	}
+
+	/// Parses a balanced ternary number, see to_balanced_ternary
+	pub fn from_balanced_ternary(src: &str) -> Result<Self, ParseIntError> {
+		if src.is_empty() {
+			return Err(ParseIntError::Empty);
+		}
+		// src = positive digits - negative digits, both of which are plain ternary
+		let (mut pos, mut neg) = (Vec::with_capacity(src.len()), Vec::with_capacity(src.len()));
+		for c in src.bytes() {
+			let (p, n) = match c {
+				b'1' => (b'1', b'0'),
+				b'0' => (b'0', b'0'),
+				b'T' => (b'0', b'1'),
+				_ => return Err(ParseIntError::InvalidDigit),
+			};
+			pos.push(p);
+			neg.push(n);
+		}
+		let pos = BigUInt::from_ascii_radix(&pos, 3)?;
+		let neg = BigUInt::from_ascii_radix(&neg, 3)?;
+		Ok(BigInt::from(pos) - &BigInt::from(neg))
+	}
 }
 
 #[cfg(test)]
 mod tests {
-	use core::assert_matches::assert_matches;
+	use core::assert_matches;
 
 	use super::*;
+	use crate::bigfloat::BigFloat;
+	use crate::rational::Rational;
 
 	#[test]
 	fn test_from_str_radix() {
@@ -141,6 +204,24 @@ mod tests {
 		assert_matches!(BigInt::from_str(""), Err(_));
 	}
 
+	#[test]
+	fn test_from_str_radix_whitespace() {
+		assert_eq!(BigInt::from_str(" 42 ").unwrap(), BigInt::from(42));
+		assert_eq!(BigInt::from_str("\n-42\t").unwrap(), BigInt::from(-42));
+		assert_eq!(
+			BigInt::from_str_radix(" -ff ", 16).unwrap(),
+			BigInt::from(-255)
+		);
+		assert_eq!(BigInt::from_str(" "), Err(ParseIntError::Empty));
+		assert_eq!(BigInt::from_str("- 42"), Err(ParseIntError::InvalidDigit));
+		assert_eq!(BigInt::from_str("-4 2"), Err(ParseIntError::InvalidDigit));
+
+		// Only the integer parsers trim, numbers built from several parts still reject spaces
+		assert!(BigFloat::from_str("1 .5").is_err());
+		assert!(BigFloat::from_str("1. 5").is_err());
+		assert!(Rational::from_fraction_str("1 /2").is_err());
+	}
+
 	#[test]
 	fn test_to_string_radix() {
 		assert_eq!(
@@ -168,4 +249,88 @@ mod tests {
 			BigInt::from(-0xacd56dfi64).to_string_radix(16, false)
 		);
 	}
+
+	#[test]
+	fn test_balanced_ternary() {
+		let cases = [
+			(0, "0"),
+			(1, "1"),
+			(-1, "T"),
+			(2, "1T"),
+			(-2, "T1"),
+			(3, "10"),
+			(5, "1TT"),
+			(8, "10T"),
+			(-8, "T01"),
+			(13, "111"),
+			(-13, "TTT"),
+			(100, "11T01"),
+		];
+		for (n, s) in cases {
+			let n = BigInt::from(n);
+			assert_eq!(n.to_balanced_ternary(), s);
+			assert_eq!(BigInt::from_balanced_ternary(s), Ok(n));
+		}
+
+		let mut values = [i64::MAX, i64::MIN, 123456789, -987654321]
+			.map(BigInt::from)
+			.to_vec();
+		values.push(BigInt::from(BigUInt::from(3u32).pow(100)));
+		values.push(-(BigInt::ONE << 300u32) + 1u32);
+		for n in values {
+			let s = n.to_balanced_ternary();
+			assert!(!s.starts_with('0'));
+			assert_eq!(BigInt::from_balanced_ternary(&s), Ok(n.clone()));
+			// Negating swaps the nonzero digits
+			let swapped: String = s
+				.chars()
+				.map(|c| match c {
+					'1' => 'T',
+					'T' => '1',
+					c => c,
+				})
+				.collect();
+			assert_eq!((-n).to_balanced_ternary(), swapped);
+		}
+
+		assert_eq!(BigInt::from_balanced_ternary("00T"), Ok(BigInt::NEG_ONE));
+		assert_eq!(BigInt::from_balanced_ternary(""), Err(ParseIntError::Empty));
+		assert_eq!(
+			BigInt::from_balanced_ternary("12"),
+			Err(ParseIntError::InvalidDigit)
+		);
+		assert_eq!(
+			BigInt::from_balanced_ternary("-1"),
+			Err(ParseIntError::InvalidDigit)
+		);
+		assert_eq!(
+			BigInt::from_balanced_ternary("1t"),
+			Err(ParseIntError::InvalidDigit)
+		);
+	}
+
+	#[test]
+	fn test_negative_zero() {
+		let mut set = BigInt::ZERO;
+		set.set_sign(true);
+		let mut negated = BigInt::ZERO;
+		negated.neg_in_place();
+		let zeros = [
+			set,
+			negated,
+			-BigInt::ZERO,
+			BigInt::from_sign_and_magnitude(true, BigUInt::ZERO),
+			BigInt::from_str("-0").unwrap(),
+			BigInt::from(-5) + &BigInt::from(5),
+			&BigInt::from(-3) * &BigInt::ZERO,
+			&mut BigInt::from(-3) / &mut BigInt::from(5),
+			&mut BigInt::from(-10) % &mut BigInt::from(5),
+		];
+		for zero in zeros {
+			assert_eq!(zero, BigInt::ZERO);
+			assert!(!zero.is_negative());
+			assert_eq!(zero.to_string(), "0");
+			assert_eq!(zero.to_string_radix(16, false), "0");
+		}
+	}
 }
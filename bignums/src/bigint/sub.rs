@@ -31,6 +31,14 @@ impl Neg for BigInt {
 	}
 }
 
+impl Neg for &BigInt {
+	type Output = BigInt;
+
+	fn neg(self) -> Self::Output {
+		-self.clone()
+	}
+}
+
 impl SubAssign<&BigInt> for BigInt {
 	fn sub_assign(&mut self, rhs: &BigInt) {
 		self.neg_in_place();
@@ -189,6 +197,22 @@ mod tests {
 		assert_eq!(100u32 - BigInt::from(30), BigInt::from(70));
 	}
 
+	#[test]
+	fn test_bigint_neg_ref() {
+		for x in [
+			BigInt::from(0),
+			BigInt::from(7),
+			BigInt::from(-7),
+			BigInt::from(i128::MIN),
+		] {
+			let copy = x.clone();
+			assert_eq!(-&x, -x.clone());
+			assert_eq!(x, copy);
+		}
+		assert_eq!(-&BigInt::from(5), BigInt::from(-5));
+		assert!(!(-&BigInt::ZERO).is_negative());
+	}
+
 	#[test]
 	#[should_panic]
 	fn test_bigint_subtraction_overflow() {
@@ -8,14 +8,20 @@ mod cmp;
 mod convert;
 mod convert_data;
 mod div;
+mod divider;
 mod mul;
 mod num_theory;
 mod pow;
+mod prime;
+mod radix_powers;
 mod set_val;
 mod str;
 mod sub;
+#[cfg(feature = "zeroize")]
+mod zeroize;
 
 pub use div::*;
+pub use divider::*;
 pub use mul::*;
 pub use sub::*;
 
@@ -77,10 +83,16 @@ impl BigUInt {
 		self.data.push(1u64);
 	}
 
+	/// Releases excess capacity, e.g. after operations that over-allocated or shrank the value.
+	/// Values that fit in two digits are moved back inline.
+	pub fn shrink_to_fit(&mut self) {
+		self.data.shrink_to_fit();
+	}
+
 	fn truncate_leading_zeros(&mut self) {
-		while let Some(&0u64) = self.data.last() {
-			self.data.pop();
-		}
+		// Only scans the leading zeros, then drops them with a single truncate
+		let len = self.data.iter().rposition(|&x| x != 0).map_or(0, |i| i + 1);
+		self.data.truncate(len);
 	}
 }
 
@@ -99,4 +111,48 @@ impl Index<usize> for BigUInt {
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+	use smallvec::smallvec;
+
+	use super::*;
+
+	#[test]
+	fn test_truncate_leading_zeros() {
+		let mut a = BigUInt {
+			data: smallvec![0, 0, 0, 0],
+		};
+		a.truncate_leading_zeros();
+		assert!(a.is_zero());
+		assert_eq!(a, BigUInt::ZERO);
+
+		let mut a = BigUInt {
+			data: smallvec![1, 0, 2, 0, 0],
+		};
+		a.truncate_leading_zeros();
+		assert_eq!(a.inner().as_slice(), &[1, 0, 2]);
+
+		let mut a = BigUInt { data: smallvec![] };
+		a.truncate_leading_zeros();
+		assert!(a.is_zero());
+	}
+
+	#[test]
+	fn test_shrink_to_fit() {
+		let a = BigUInt::from_le_limbs(&[u64::MAX; 16]);
+		let mut res = BigUInt::ZERO;
+		res.mul_to(&a, &a);
+		assert_eq!(res.len(), 32);
+		res >>= 64 * 31;
+		assert_eq!(res.len(), 1);
+		assert!(res.capacity() >= 32);
+
+		let expected = res.clone();
+		res.shrink_to_fit();
+		assert!(res.capacity() < 32);
+		assert_eq!(res, expected);
+
+		let mut zero = BigUInt::ZERO;
+		zero.shrink_to_fit();
+		assert!(zero.is_zero());
+	}
+}
@@ -1,3 +1,4 @@
+use core::iter::Sum;
 use core::ops::{Add, AddAssign};
 
 use crate::biguint::BigUInt;
@@ -116,6 +117,24 @@ macro_rules! impl_add_i {
 
 impl_add_i! { i8, i16, i32, i64, i128, isize }
 
+impl Sum for BigUInt {
+	fn sum<I: Iterator<Item = BigUInt>>(iter: I) -> Self {
+		iter.fold(BigUInt::ZERO, |mut acc, x| {
+			acc += &x;
+			acc
+		})
+	}
+}
+
+impl<'a> Sum<&'a BigUInt> for BigUInt {
+	fn sum<I: Iterator<Item = &'a BigUInt>>(iter: I) -> Self {
+		iter.fold(BigUInt::ZERO, |mut acc, x| {
+			acc += x;
+			acc
+		})
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -132,4 +151,21 @@ mod tests {
 		let cmp2 = BigUInt::from(2u128 * u64::MAX as u128 + 1);
 		assert_eq!(num1, cmp2);
 	}
+
+	#[test]
+	fn test_sum() {
+		let values: [BigUInt; 4] = [
+			BigUInt::from(u64::MAX),
+			BigUInt::from(1u64),
+			BigUInt::from(u128::MAX),
+			BigUInt::ZERO,
+		];
+		let expected = BigUInt::from(u128::MAX) + &BigUInt::from(u64::MAX) + &BigUInt::ONE;
+		assert_eq!(values.iter().sum::<BigUInt>(), expected);
+		assert_eq!(values.into_iter().sum::<BigUInt>(), expected);
+		assert_eq!(
+			core::iter::empty::<&BigUInt>().sum::<BigUInt>(),
+			BigUInt::ZERO
+		);
+	}
 }
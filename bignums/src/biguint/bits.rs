@@ -8,8 +8,8 @@ use crate::biguint::BigUInt;
 use crate::util::VecExt;
 
 impl BigUInt {
-	/// Shift left by whole digits
-	/// A digit is 64 bits
+	/// Shift left by whole digits, i.e. multiply by 2^(64 * digits)
+	/// A digit (limb) is 64 bits
 	pub fn shl_digits(&mut self, digits: usize) {
 		if self.is_zero() {
 			return;
@@ -22,8 +22,8 @@ impl BigUInt {
 		self.data[0..digits].fill(0u64);
 	}
 
-	/// Shift right by whole digits
-	/// A digit is 64 bits
+	/// Shift right by whole digits, i.e. divide by 2^(64 * digits), rounding down
+	/// A digit (limb) is 64 bits
 	pub fn shr_digits(&mut self, digits: usize) {
 		if digits >= self.len() {
 			self.set_zero();
@@ -34,6 +34,43 @@ impl BigUInt {
 		self.data.truncate(self.len() - digits);
 	}
 
+	/// Splits the number into (low, high) s.t. self = low + high * 2^(64 * k),
+	/// where low has at most k digits
+	pub fn split_at_limb(&self, k: usize) -> (BigUInt, BigUInt) {
+		let k = usize::min(k, self.len());
+		let (low, high) = self.data.split_at(k);
+		(BigUInt::from_le_limbs(low), BigUInt::from_le_limbs(high))
+	}
+
+	/// self mod 2^k, i.e. the lowest k bits
+	pub fn mod_pow2(&self, k: u64) -> BigUInt {
+		let limbs = usize::try_from(k.div_ceil(u64::BITS as u64)).unwrap_or(usize::MAX);
+		let mut res = BigUInt::from_le_limbs(&self.data[..limbs.min(self.len())]);
+		res.mod_pow2_assign(k);
+		res
+	}
+
+	/// Clears all bits from position k up. Also drops leading zero digits, if any
+	pub(crate) fn mod_pow2_assign(&mut self, k: u64) {
+		let limbs = usize::try_from(k / u64::BITS as u64).unwrap_or(usize::MAX);
+		if limbs < self.len() {
+			let rem = k % u64::BITS as u64;
+			if rem == 0 {
+				self.data.truncate(limbs);
+			} else {
+				self.data.truncate(limbs + 1);
+				self.data[limbs] &= (1u64 << rem) - 1;
+			}
+		}
+		self.truncate_leading_zeros();
+	}
+
+	/// Return the number of trailing zero digits, i.e. the largest k s.t. 2^(64 * k) divides self.
+	/// For 0 returns 0
+	pub fn trailing_zero_limbs(&self) -> usize {
+		self.data.iter().take_while(|&&digit| digit == 0).count()
+	}
+
 	pub fn not_in_place(&mut self) {
 		for x in self.data.iter_mut() {
 			*x = !*x;
@@ -59,6 +96,40 @@ impl BigUInt {
 			.sum()
 	}
 
+	/// Returns the number of ones among the bits in [start, end), counting from the least significant bit.
+	/// Bits past the end of the number are zeros.
+	pub fn count_ones_in_range(&self, start: u64, end: u64) -> u64 {
+		assert!(start <= end, "bit range starts after it ends");
+		const BITS: u64 = u64::BITS as u64;
+
+		let end = end.min(self.data.len() as u64 * BITS);
+		if start >= end {
+			return 0;
+		}
+
+		let first = (start / BITS) as usize;
+		let last = ((end - 1) / BITS) as usize;
+		let first_mask = u64::MAX << (start % BITS);
+		let last_mask = u64::MAX >> (BITS - 1 - (end - 1) % BITS);
+		if first == last {
+			return (self.data[first] & first_mask & last_mask).count_ones() as u64;
+		}
+
+		let middle: u64 = self.data[first + 1..last]
+			.iter()
+			.map(|digit| digit.count_ones() as u64)
+			.sum();
+		middle
+			+ (self.data[first] & first_mask).count_ones() as u64
+			+ (self.data[last] & last_mask).count_ones() as u64
+	}
+
+	/// Returns the number of zeros among the bits in [start, end), counting from the least significant bit.
+	/// Bits past the end of the number are zeros.
+	pub fn count_zeros_in_range(&self, start: u64, end: u64) -> u64 {
+		(end - start) - self.count_ones_in_range(start, end)
+	}
+
 	/// Return the number of trailing zeros in the binary representation of the number.
 	/// For 0 returns 0
 	pub fn trailing_zeros(&self) -> u64 {
@@ -118,7 +189,82 @@ impl BigUInt {
 			.map(|&x| (x >> rem64) & 1u64 != 0)
 			.unwrap_or(false)
 	}
+
+	/// Returns len <= 64 bits starting at the given position, counting from the least significant bit.
+	pub fn bit_range(&self, start: u64, len: u32) -> u64 {
+		assert!(len <= u64::BITS, "bit range is wider than 64 bits");
+		if len == 0 {
+			return 0;
+		}
+
+		let mask = u64::MAX >> (u64::BITS - len);
+		let idx = (start / u64::BITS as u64) as usize;
+		let shift = (start % u64::BITS as u64) as u32;
+
+		let lo = self.data.get(idx).copied().unwrap_or(0) >> shift;
+		let hi = if shift == 0 {
+			0
+		} else {
+			self.data.get(idx + 1).copied().unwrap_or(0) << (u64::BITS - shift)
+		};
+		(lo | hi) & mask
+	}
+
+	/// Overwrites len <= 64 bits starting at the given position with the low len bits of value.
+	/// Grows the number if needed.
+	pub fn set_bit_range(&mut self, start: u64, len: u32, value: u64) {
+		assert!(len <= u64::BITS, "bit range is wider than 64 bits");
+		if len == 0 {
+			return;
+		}
+
+		let mask = u64::MAX >> (u64::BITS - len);
+		let value = value & mask;
+		let idx = (start / u64::BITS as u64) as usize;
+		let shift = (start % u64::BITS as u64) as u32;
+		let end_idx = ((start + len as u64 - 1) / u64::BITS as u64) as usize;
+
+		if self.len() <= end_idx {
+			self.data.extend_zero(end_idx + 1 - self.len());
+		}
+
+		self.data[idx] = (self.data[idx] & !(mask << shift)) | (value << shift);
+		if end_idx > idx {
+			// The range spills over into the next digit
+			let hi_shift = u64::BITS - shift;
+			self.data[end_idx] = (self.data[end_idx] & !(mask >> hi_shift)) | (value >> hi_shift);
+		}
+
+		self.truncate_leading_zeros();
+	}
+
+	/// Returns self & !other, i.e. clears the bits of self that are set in other
+	pub fn and_not(&self, other: &BigUInt) -> BigUInt {
+		let mut res = self.clone();
+		res.and_not_assign(other);
+		res
+	}
+
+	/// Clears the bits of self that are set in other
+	pub fn and_not_assign(&mut self, other: &BigUInt) {
+		// Bits of other above self.len() don't matter
+		for (x, y) in iter::zip(self.data.iter_mut(), other.data.iter()) {
+			*x &= !y;
+		}
+		self.truncate_leading_zeros();
+	}
+
+	/// Returns self << rhs, or None if the result would have isize::MAX bits or more,
+	/// where shifting panics.
+	pub fn checked_shl(self, rhs: u64) -> Option<BigUInt> {
+		if self.is_zero() {
+			return Some(self);
+		}
+		let bits = (self.ilog2() + 1).checked_add(rhs)?;
+		(bits < isize::MAX as u64).then(|| self << rhs)
+	}
 }
+
 macro_rules! impl_shl {
 	($($t:ty),*) => {$(
 		impl Shl<$t> for BigUInt {
@@ -388,6 +534,37 @@ mod tests {
 		assert_eq!(res_native, res_foreign)
 	}
 
+	#[test]
+	fn test_set_bit_range() {
+		let mut a = BigUInt::ZERO;
+		a.set_bit_range(0, 4, 0xA);
+		a.set_bit_range(4, 8, 0xBC);
+		a.set_bit_range(60, 8, 0xDE);
+		a.set_bit_range(100, 64, u64::MAX - 1);
+		a.set_bit_range(200, 1, 0xFF);
+
+		let mut expected = BigUInt::from(0xDEu128 << 60 | 0xBCA);
+		expected += &(BigUInt::from(u64::MAX - 1) << 100u32);
+		expected += &(BigUInt::ONE << 200u32);
+		assert_eq!(a, expected);
+
+		assert_eq!(a.bit_range(0, 4), 0xA);
+		assert_eq!(a.bit_range(4, 8), 0xBC);
+		assert_eq!(a.bit_range(60, 8), 0xDE);
+		assert_eq!(a.bit_range(100, 64), u64::MAX - 1);
+		assert_eq!(a.bit_range(200, 1), 1);
+		assert_eq!(a.bit_range(12, 48), 0);
+		assert_eq!(a.bit_range(300, 64), 0);
+
+		// Overwrite existing bits and clear the highest ones
+		a.set_bit_range(60, 8, 0x12);
+		assert_eq!(a.bit_range(60, 8), 0x12);
+		a.set_bit_range(200, 1, 0);
+		a.set_bit_range(100, 64, 0);
+		assert_eq!(a, BigUInt::from(0x12u128 << 60 | 0xBCA));
+		assert_eq!(a.len(), 2);
+	}
+
 	#[test]
 	fn test_leading_zeros() {
 		assert_eq!(BigUInt::ZERO.leading_zeros(), 0);
@@ -440,6 +617,59 @@ mod tests {
 		assert_eq!(y.trailing_zeros(), 160);
 	}
 
+	#[test]
+	fn test_trailing_zero_limbs() {
+		assert_eq!(BigUInt::ZERO.trailing_zero_limbs(), 0);
+		assert_eq!(BigUInt::ONE.trailing_zero_limbs(), 0);
+		assert_eq!((BigUInt::ONE << 63u32).trailing_zero_limbs(), 0);
+		assert_eq!((BigUInt::ONE << 64u32).trailing_zero_limbs(), 1);
+		assert_eq!(
+			BigUInt::from_le_limbs(&[0, 0, 5, 0, 7]).trailing_zero_limbs(),
+			2
+		);
+	}
+
+	#[test]
+	fn test_split_at_limb() {
+		let x = BigUInt::from_le_limbs(&[1, 2, 0, 0, 5, 6]);
+		for k in 0..8 {
+			let (low, mut high) = x.split_at_limb(k);
+			assert!(low.len() <= k);
+			high.shl_digits(k);
+			assert_eq!(low + &high, x, "k = {k}");
+		}
+
+		let (low, high) = x.split_at_limb(4);
+		assert_eq!(low, BigUInt::from_le_limbs(&[1, 2]));
+		assert_eq!(high, BigUInt::from_le_limbs(&[5, 6]));
+		let (low, high) = x.split_at_limb(2);
+		assert_eq!(low, BigUInt::from_le_limbs(&[1, 2]));
+		assert_eq!(high, BigUInt::from_le_limbs(&[0, 0, 5, 6]));
+
+		assert_eq!(
+			BigUInt::ZERO.split_at_limb(3),
+			(BigUInt::ZERO, BigUInt::ZERO)
+		);
+		assert_eq!(x.split_at_limb(0), (BigUInt::ZERO, x.clone()));
+		assert_eq!(x.split_at_limb(6), (x.clone(), BigUInt::ZERO));
+	}
+
+	#[test]
+	fn test_mod_pow2() {
+		let x = BigUInt::from_le_limbs(&[u64::MAX, 0, 5, u64::MAX]);
+		for k in 0..300u64 {
+			let expected = x.clone() - &((x.clone() >> k) << k);
+			assert_eq!(x.mod_pow2(k), expected, "k = {k}");
+		}
+		assert_eq!(x.mod_pow2(0), BigUInt::ZERO);
+		assert_eq!(x.mod_pow2(64), BigUInt::from(u64::MAX));
+		// The zero digit is dropped
+		assert_eq!(x.mod_pow2(128), BigUInt::from(u64::MAX));
+		assert_eq!(x.mod_pow2(131), BigUInt::from_le_limbs(&[u64::MAX, 0, 5]));
+		assert_eq!(x.mod_pow2(u64::MAX), x);
+		assert_eq!(BigUInt::ZERO.mod_pow2(10), BigUInt::ZERO);
+	}
+
 	#[test]
 	fn test_trailing_ones() {
 		assert_eq!(BigUInt::ZERO.trailing_ones(), 0);
@@ -453,4 +683,90 @@ mod tests {
 		x |= &BigUInt::from(u64::MAX);
 		assert_eq!(x.trailing_ones(), 128);
 	}
+
+	#[test]
+	fn test_and_not() {
+		let set = |bits: &[u64]| {
+			let mut res = BigUInt::ZERO;
+			for &bit in bits {
+				res.set_bit_range(bit, 1, 1);
+			}
+			res
+		};
+
+		assert_eq!(set(&[1, 2, 3]).and_not(&set(&[2])), set(&[1, 3]));
+		assert_eq!(set(&[1, 2, 3]).and_not(&BigUInt::ZERO), set(&[1, 2, 3]));
+		assert_eq!(set(&[1, 2, 3]).and_not(&set(&[1, 2, 3])), BigUInt::ZERO);
+		assert_eq!(BigUInt::ZERO.and_not(&set(&[5])), BigUInt::ZERO);
+
+		// Clearing the high bits drops the leading digits
+		let a = set(&[0, 70, 200]);
+		let b = set(&[200, 300]);
+		let res = a.and_not(&b);
+		assert_eq!(res, set(&[0, 70]));
+		assert_eq!(res.len(), 2);
+
+		let mut c = a.clone();
+		c.and_not_assign(&set(&[0, 1000]));
+		assert_eq!(c, set(&[70, 200]));
+
+		let a = BigUInt::from(0xdead_beef_1234_5678_9abc_def0_u128);
+		let b = BigUInt::from(0x0f0f_0f0f_ffff_0000_1111_2222_u128);
+		assert_eq!(
+			a.and_not(&b),
+			BigUInt::from(
+				0xdead_beef_1234_5678_9abc_def0_u128 & !0x0f0f_0f0f_ffff_0000_1111_2222_u128
+			)
+		);
+	}
+
+	#[test]
+	fn test_checked_shl() {
+		let a = BigUInt::from(0b1011u32);
+		assert_eq!(a.clone().checked_shl(0), Some(a.clone()));
+		assert_eq!(a.clone().checked_shl(3), Some(BigUInt::from(0b1011000u32)));
+		assert_eq!(a.clone().checked_shl(200), Some(a.clone() << 200u32));
+		assert_eq!(a.clone().checked_shl(u64::MAX), None);
+		assert_eq!(a.clone().checked_shl(isize::MAX as u64), None);
+		// The limit counts the bits of the result
+		assert_eq!(a.checked_shl(isize::MAX as u64 - 4), None);
+		assert_eq!(BigUInt::ZERO.checked_shl(u64::MAX), Some(BigUInt::ZERO));
+	}
+
+	#[test]
+	fn test_count_ones_in_range() {
+		let x = BigUInt::from_le_limbs(&[u64::MAX, 0, 0xF0F0_0000_0000_000F, 1]);
+		assert_eq!(x.count_ones_in_range(0, 64), 64);
+		assert_eq!(x.count_ones_in_range(60, 70), 4);
+		assert_eq!(x.count_ones_in_range(64, 128), 0);
+		assert_eq!(x.count_ones_in_range(120, 140), 4);
+		assert_eq!(x.count_ones_in_range(0, 256), x.count_ones());
+		assert_eq!(x.count_ones_in_range(5, 5), 0);
+		assert_eq!(x.count_zeros_in_range(60, 70), 6);
+		assert_eq!(x.count_zeros_in_range(0, 256), x.count_zeros());
+
+		// Past the end
+		assert_eq!(x.count_ones_in_range(193, 1000), 0);
+		assert_eq!(x.count_ones_in_range(500, 1000), 0);
+		assert_eq!(x.count_ones_in_range(190, u64::MAX), 3);
+		assert_eq!(x.count_zeros_in_range(500, 1000), 500);
+		assert_eq!(BigUInt::ZERO.count_ones_in_range(0, 64), 0);
+		assert_eq!(BigUInt::ZERO.count_zeros_in_range(3, 64), 61);
+
+		// Against counting bit by bit
+		let x = BigUInt::from_le_limbs(&[0x0123_4567_89AB_CDEF, 0xFEDC_BA98_7654_3210, 0x5555, 3]);
+		for start in (0..260).step_by(7) {
+			for end in (start..270).step_by(5) {
+				let naive = (start..end).filter(|&i| x.bit(i as usize)).count() as u64;
+				assert_eq!(x.count_ones_in_range(start, end), naive, "[{start}, {end})");
+				assert_eq!(x.count_zeros_in_range(start, end), end - start - naive);
+			}
+		}
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_count_ones_in_reversed_range() {
+		BigUInt::ONE.count_ones_in_range(5, 4);
+	}
 }
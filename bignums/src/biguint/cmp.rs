@@ -2,6 +2,20 @@ use core::cmp::Ordering;
 
 use crate::biguint::BigUInt;
 
+impl BigUInt {
+	/// Compares with a u128 without converting it to a BigUInt
+	pub fn cmp_u128(&self, other: u128) -> Ordering {
+		let low = match self.data[..] {
+			[] => 0,
+			[lo] => lo as u128,
+			[lo, hi] => ((hi as u128) << u64::BITS) | lo as u128,
+			// No leading zero digits, so anything longer is at least 2^128
+			_ => return Ordering::Greater,
+		};
+		Ord::cmp(&low, &other)
+	}
+}
+
 impl PartialOrd for BigUInt {
 	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
 		Some(self.cmp(other))
@@ -46,7 +60,7 @@ impl_partial_eq_u! { u8, u16, u32, u64, usize }
 
 impl PartialEq<u128> for BigUInt {
 	fn eq(&self, other: &u128) -> bool {
-		self == &BigUInt::from(*other)
+		self.cmp_u128(*other) == Ordering::Equal
 	}
 }
 
@@ -80,13 +94,13 @@ macro_rules! impl_partial_ord_u {
 	($($t:ty),*) => {$(
 		impl PartialOrd<$t> for BigUInt {
 			fn partial_cmp(&self, other: &$t) -> Option<Ordering> {
-				Some(self.cmp(&BigUInt::from(*other)))
+				Some(self.cmp_u128(*other as u128))
 			}
 		}
 
 		impl PartialOrd<BigUInt> for $t {
 			fn partial_cmp(&self, other: &BigUInt) -> Option<Ordering> {
-				Some(BigUInt::from(*self).cmp(other))
+				Some(other.cmp_u128(*self as u128).reverse())
 			}
 		}
 	)*}
@@ -98,19 +112,17 @@ macro_rules! impl_partial_ord_i {
 	($($t:ty),*) => {$(
 		impl PartialOrd<$t> for BigUInt {
 			fn partial_cmp(&self, other: &$t) -> Option<Ordering> {
-				match BigUInt::try_from(*other) {
-					Ok(rhs) => self.partial_cmp(&rhs),
-					Err(_) => Some(Ordering::Greater)
+				if *other < 0 {
+					Some(Ordering::Greater)
+				} else {
+					Some(self.cmp_u128(*other as u128))
 				}
 			}
 		}
 
 		impl PartialOrd<BigUInt> for $t {
 			fn partial_cmp(&self, other: &BigUInt) -> Option<Ordering> {
-				match BigUInt::try_from(*self) {
-					Ok(lhs) => lhs.partial_cmp(other),
-					Err(_) => Some(Ordering::Less)
-				}
+				other.partial_cmp(self).map(Ordering::reverse)
 			}
 		}
 	)*}
@@ -129,4 +141,60 @@ mod tests {
 		assert!(BigUInt::from(1u64) > -1);
 		assert!(BigUInt::from(0u64) >= 0u64);
 	}
+
+	#[test]
+	fn test_cmp_u128() {
+		let max = BigUInt::from(u128::MAX);
+		let above = max.clone() + &BigUInt::ONE;
+		assert_eq!(max.cmp_u128(u128::MAX), Ordering::Equal);
+		assert_eq!(max.cmp_u128(u128::MAX - 1), Ordering::Greater);
+		assert_eq!(above.cmp_u128(u128::MAX), Ordering::Greater);
+		assert_eq!(
+			(BigUInt::ONE << 1000u32).cmp_u128(u128::MAX),
+			Ordering::Greater
+		);
+		assert_eq!(BigUInt::from(u64::MAX).cmp_u128(1 << 64), Ordering::Less);
+		assert_eq!(BigUInt::ONE.cmp_u128(u128::MAX), Ordering::Less);
+		assert_eq!(BigUInt::ZERO.cmp_u128(0), Ordering::Equal);
+		assert_eq!(BigUInt::ZERO.cmp_u128(1), Ordering::Less);
+
+		// Both operand orders, since each goes through its own impl
+		assert!(max == u128::MAX);
+		assert!(u128::MAX == max);
+		assert!(above != u128::MAX);
+		assert!(above > u128::MAX);
+		assert!(u128::MAX < above);
+		assert!(above > i128::MAX);
+		assert!(i128::MAX < above);
+		assert!(max > i128::MAX);
+		assert!(BigUInt::from(i128::MAX as u128) == i128::MAX);
+		assert!(BigUInt::ZERO > i128::MIN);
+		assert!(i128::MIN < BigUInt::ZERO);
+		assert!(BigUInt::ZERO > -1i8);
+		assert!(-1i8 < BigUInt::ZERO);
+
+		// Agrees with comparing BigUInts, around the limb boundaries
+		let values = [
+			0,
+			1,
+			u64::MAX as u128,
+			1 << 64,
+			(1 << 64) + 1,
+			u128::MAX - 1,
+			u128::MAX,
+		];
+		for &a in &values {
+			for &b in &values {
+				let big = BigUInt::from(a);
+				assert_eq!(big.cmp_u128(b), a.cmp(&b), "{a} {b}");
+				assert_eq!(big.partial_cmp(&b), Some(a.cmp(&b)));
+				assert_eq!(b.partial_cmp(&big), Some(b.cmp(&a)));
+				assert_eq!(
+					big.partial_cmp(&(b as u64)),
+					Some(a.cmp(&(b as u64 as u128)))
+				);
+				assert_eq!(big == b, a == b);
+			}
+		}
+	}
 }
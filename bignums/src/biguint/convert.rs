@@ -3,6 +3,7 @@ use core::convert::{TryFrom, TryInto};
 use crate::bigint::BigInt;
 use crate::biguint::BigUInt;
 use crate::error::{TryFromIntError, TryIntoIntError};
+use crate::rational::Rational;
 use crate::util::u64s_to_u128;
 use crate::{SetVal, TrySetVal};
 
@@ -63,6 +64,13 @@ macro_rules! impl_try_into {
 
 impl_try_into! { u8, u16, u32, u64, usize, i8, i16, i32, i64, isize }
 
+impl BigUInt {
+	/// Returns self as a fraction with denominator one
+	pub fn to_rational(&self) -> Rational {
+		Rational::from(self.clone())
+	}
+}
+
 impl TryFrom<&BigUInt> for u128 {
 	type Error = TryIntoIntError;
 	fn try_from(val: &BigUInt) -> Result<Self, Self::Error> {
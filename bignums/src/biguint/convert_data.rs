@@ -36,6 +36,24 @@ impl BigUInt {
 		unsafe { Self::from_smallvec_le_unchecked(data.into()) }
 	}
 
+	/// Creates a bigint by copying a slice of little-endian digits
+	/// Leading zero digits are trimmed, empty slice corresponds to 0
+	pub fn from_le_limbs(data: &[u64]) -> Self {
+		Self::from_smallvec_le(SmallVec::from_slice(data))
+	}
+
+	/// Returns the digits of the bigint in little-endian, without leading zeros
+	/// Zero corresponds to an empty slice. Unlike inner, this doesn't expose the storage type.
+	pub fn as_limbs(&self) -> &[u64] {
+		&self.data
+	}
+
+	/// Returns a copy of the digits of the bigint in little-endian, without leading zeros
+	/// Zero corresponds to an empty vector, same as num_bigint's to_u64_digits
+	pub fn to_le_limbs(&self) -> Vec<u64> {
+		self.data.to_vec()
+	}
+
 	pub fn from_bytes_le(mut data: Vec<u8>) -> Self {
 		while data.len() % size_of::<u64>() != 0 {
 			data.push(0u8);
@@ -58,6 +76,29 @@ impl BigUInt {
 		// Self::from_vec_le(data_new)
 	}
 
+	/// Creates a bigint from big-endian bytes, leading zero bytes are ignored
+	/// Empty slice corresponds to 0
+	pub fn from_bytes_be(data: &[u8]) -> Self {
+		let limbs = data
+			.rchunks(size_of::<u64>())
+			.map(|chunk| chunk.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64))
+			.collect();
+		Self::from_smallvec_le(limbs)
+	}
+
+	/// Returns the big-endian bytes of the bigint, without leading zeros
+	/// Zero corresponds to an empty vector
+	pub fn to_bytes_be(&self) -> Vec<u8> {
+		let bytes: Vec<u8> = self
+			.data
+			.iter()
+			.rev()
+			.flat_map(|d| d.to_be_bytes())
+			.collect();
+		let leading_zeros = bytes.iter().take_while(|&&b| b == 0).count();
+		bytes[leading_zeros..].to_vec()
+	}
+
 	pub fn into_bytes_le(mut self) -> Vec<u8> {
 		// let data = self.into_inner().into_vec();
 
@@ -89,3 +130,94 @@ fn remap_endianness(data: &mut [u8]) {
 		"something went wrong remapping byte vec endianness"
 	);
 }
+
+impl AsRef<[u64]> for BigUInt {
+	/// Same as as_limbs
+	fn as_ref(&self) -> &[u64] {
+		self.as_limbs()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::util::to_foreign_biguint;
+
+	#[test]
+	fn test_le_limbs() {
+		for limbs in [&[][..], &[1], &[0, 1], &[u64::MAX, 5, 7]] {
+			let a = BigUInt::from_le_limbs(limbs);
+			assert_eq!(a.as_limbs(), limbs);
+			assert_eq!(a.to_le_limbs(), limbs);
+			assert_eq!(BigUInt::from_le_limbs(a.as_limbs()), a);
+		}
+
+		assert_eq!(
+			BigUInt::from_le_limbs(&[1, 2]),
+			BigUInt::from(2u128 << 64 | 1)
+		);
+	}
+
+	#[test]
+	fn test_as_limbs() {
+		assert!(BigUInt::ZERO.as_limbs().is_empty());
+		assert_eq!(BigUInt::ONE.as_limbs(), &[1]);
+		assert_eq!(BigUInt::from(u128::MAX).as_limbs(), &[u64::MAX, u64::MAX]);
+		assert_eq!(BigUInt::from(1u128 << 64).as_limbs(), &[0, 1]);
+
+		// No leading zero digit after operations that shrink the value
+		let mut a = BigUInt::from(u128::MAX);
+		a -= &BigUInt::from(u128::MAX - 5);
+		assert_eq!(a.as_limbs(), &[5]);
+		let a = (BigUInt::ONE << 200u32) >> 150u32;
+		assert_eq!(a.as_limbs(), &[1 << 50]);
+
+		let a = BigUInt::from(u128::MAX);
+		let limbs: &[u64] = a.as_ref();
+		assert_eq!(limbs, a.as_limbs());
+	}
+
+	#[test]
+	fn test_from_le_limbs_trims_leading_zeros() {
+		let a = BigUInt::from_le_limbs(&[3, 0, 0]);
+		assert_eq!(a.as_limbs(), &[3]);
+		assert_eq!(a, BigUInt::from(3u32));
+
+		let a = BigUInt::from_le_limbs(&[0, 0]);
+		assert!(a.as_limbs().is_empty());
+		assert_eq!(a, BigUInt::ZERO);
+	}
+
+	#[test]
+	fn test_bytes_be() {
+		assert!(BigUInt::ZERO.to_bytes_be().is_empty());
+		assert_eq!(BigUInt::from_bytes_be(&[]), BigUInt::ZERO);
+		assert_eq!(BigUInt::from_bytes_be(&[0, 0, 0]), BigUInt::ZERO);
+		assert_eq!(BigUInt::from_bytes_be(&[1, 2]), BigUInt::from(0x0102u32));
+		assert_eq!(BigUInt::from(0x0102u32).to_bytes_be(), [1, 2]);
+		assert_eq!(
+			BigUInt::from_bytes_be(&[0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0]),
+			BigUInt::ONE << 64u32
+		);
+
+		for bytes in [
+			&[1u8][..],
+			&[0xff; 8],
+			&[1, 0, 0, 0, 0, 0, 0, 0, 0],
+			&[0xab; 33],
+		] {
+			let a = BigUInt::from_bytes_be(bytes);
+			assert_eq!(a.to_bytes_be(), bytes);
+			assert_eq!(to_foreign_biguint(a.clone()).to_bytes_be(), bytes);
+		}
+	}
+
+	#[test]
+	fn test_le_limbs_match_foreign() {
+		assert!(BigUInt::ZERO.to_le_limbs().is_empty());
+		for limbs in [&[7][..], &[0, 1], &[u64::MAX, 0, 3]] {
+			let a = BigUInt::from_le_limbs(limbs);
+			assert_eq!(to_foreign_biguint(a).to_u64_digits(), limbs);
+		}
+	}
+}
@@ -3,7 +3,7 @@ use core::ops::{Div, Rem};
 use crate::SetVal;
 use crate::biguint::BigUInt;
 use crate::biguint::mul::MulTo;
-use crate::error::TryFromIntError;
+use crate::error::{DivByZeroError, TryFromIntError};
 use crate::util::u64s_to_u128;
 
 pub trait DivRem<RHS = Self> {
@@ -48,7 +48,13 @@ macro_rules! impl_div_rem_u {
 			}
 
 			fn div_rem_to(self, d: $t, q: &mut BigUInt, r: &mut BigUInt) {
-				self.div_rem_to(&mut BigUInt::from(d), q, r);
+				match u64::try_from(d) {
+					Ok(d) => {
+						q.set_val(&*self);
+						r.set_val(q.div_rem_u64_assign(d));
+					}
+					Err(_) => self.div_rem_to(&mut BigUInt::from(d), q, r),
+				}
 			}
 		}
 
@@ -65,7 +71,7 @@ macro_rules! impl_div_rem_u {
 				let n: u128 = self as u128;
 				if let Ok(d) = TryInto::<u128>::try_into(d) {
 					*q = BigUInt::from(n / d);
-					*r = BigUInt::from(n / d);
+					*r = BigUInt::from(n % d);
 				} else {
 					q.set_zero();
 					r.set_val(self);
@@ -106,7 +112,7 @@ macro_rules! impl_div_rem_i {
 				let n: u128 = self.try_into().map_err(|_| TryFromIntError).unwrap();
 				if let Ok(d) = TryInto::<u128>::try_into(d) {
 					*q = BigUInt::from(n / d);
-					*r = BigUInt::from(n / d);
+					*r = BigUInt::from(n % d);
 				} else {
 					q.set_zero();
 					r.set_val(n);
@@ -165,6 +171,43 @@ impl_div_and_rem! {
 }
 
 impl BigUInt {
+	/// Divides by a single digit, returns the quotient and remainder
+	/// # Panics
+	/// If d == 0
+	pub fn div_rem_u64(&self, d: u64) -> (BigUInt, u64) {
+		let mut q = self.clone();
+		let r = q.div_rem_u64_assign(d);
+		(q, r)
+	}
+
+	/// Divides self by a single digit in place, in one pass over the digits
+	/// Returns the remainder
+	/// # Panics
+	/// If d == 0
+	pub fn div_rem_u64_assign(&mut self, d: u64) -> u64 {
+		assert_ne!(d, 0, "division by zero");
+		let mut r = 0u64;
+		for x in self.data.iter_mut().rev() {
+			// r < d, so the quotient fits in one digit
+			let n = u64s_to_u128([*x, r]);
+			*x = (n / d as u128) as u64;
+			r = (n % d as u128) as u64;
+		}
+		self.truncate_leading_zeros();
+		r
+	}
+
+	/// Calculates the quotient and remainder
+	/// Returns an error instead of panicking if d == 0
+	pub fn div_rem_checked(&self, d: &BigUInt) -> Result<(BigUInt, BigUInt), DivByZeroError> {
+		if d.is_zero() {
+			return Err(DivByZeroError);
+		}
+		let mut n = self.clone();
+		let mut d = d.clone();
+		Ok(n.div_rem(&mut d))
+	}
+
 	/// Calculates the quotient and remainder
 	/// Preconditions:
 	///     * d != 0
@@ -172,12 +215,7 @@ impl BigUInt {
 	/// Puts quotient in q and remainder in r
 	/// n and d are mutable for implementation reasons, they are restored to original values before return.
 	/// Uses long division, internally uses Knuth's Algorithm D
-	pub fn div_rem_to_unchecked(
-		n: &mut BigUInt,
-		d: &mut BigUInt,
-		q: &mut BigUInt,
-		r: &mut BigUInt,
-	) {
+	fn div_rem_to_unchecked(n: &mut BigUInt, d: &mut BigUInt, q: &mut BigUInt, r: &mut BigUInt) {
 		debug_assert!(*d != 0, "division by zero");
 		debug_assert!(n >= d, "can't divide smaller number by bigger number");
 
@@ -213,10 +251,13 @@ impl BigUInt {
 		// Start from the most significant remaining digits of n
 		let iter = n.data[0..(n.len() - d.len())].iter().rev();
 		for &x in iter {
-			// If n_inter is guaranteed smaller than d, write a zero into q and
-			// continue with one more digit
-			if n_inter.len() < d.len() {
+			// If n_inter is smaller than d, write a zero into q and
+			// continue with one more digit.
+			// This also covers n_inter of the same length as d, which would break
+			// the comparisons in div_n_plus_1_digits_normalized once padded.
+			if n_inter < *d {
 				n_inter.data.insert(0, x);
+				n_inter.truncate_leading_zeros();
 				q.data.push(0u64);
 				continue;
 			}
@@ -240,12 +281,19 @@ impl BigUInt {
 			n_inter.set_val(&*r);
 			// Bring down another digit
 			n_inter.data.insert(0, x);
+			n_inter.truncate_leading_zeros();
 		}
-		if n_inter.len() == d.len() {
-			n_inter.data.push(0u64);
+		if n_inter < *d {
+			// The last digit of q is zero
+			q.data.push(0u64);
+			r.set_val(&n_inter);
+		} else {
+			if n_inter.len() == d.len() {
+				n_inter.data.push(0u64);
+			}
+			let q_i = div_n_plus_1_digits_normalized(&n_inter, d, r);
+			q.data.push(q_i);
 		}
-		let q_i = div_n_plus_1_digits_normalized(&n_inter, d, r);
-		q.data.push(q_i);
 
 		// Unfuck q
 		q.data.reverse();
@@ -290,7 +338,13 @@ fn div_n_plus_1_digits_normalized(n: &BigUInt, d: &BigUInt, r: &mut BigUInt) ->
 
 	let n_hi = [n[n.len() - 2], n[n.len() - 1]];
 	let d_hi = d[d.len() - 1];
-	let mut q_est = div_2_digits(n_hi, d_hi);
+	// The quotient of the leading digits may not fit in one digit even though the
+	// real quotient does, clamping keeps the estimate within 2 of the real quotient
+	let mut q_est = if n_hi[1] >= d_hi {
+		u64::MAX
+	} else {
+		div_2_digits(n_hi, d_hi)
+	};
 
 	correct_q(&mut q_est, r, n, d);
 
@@ -314,7 +368,7 @@ fn div_2_digits(n: [u64; 2], d: u64) -> u64 {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::util::{from_foreign_biguint, to_foreign_biguint};
+	use crate::util::{from_foreign_biguint, to_foreign_biguint, xorshift64};
 
 	#[test]
 	fn test_div_n_plus_1_digits_normalized() {
@@ -385,6 +439,84 @@ mod tests {
 		);
 
 		div_rem_helper(BigUInt::from(u128::MAX), BigUInt::from(u128::MAX));
+
+		// Intermediate remainders shorter than the denominator
+		let d = BigUInt::from_vec_le(vec![1, 1 << 63]);
+		div_rem_helper(d.clone() << 128, d.clone());
+		div_rem_helper((d.clone() << 128) + 5u64, d.clone());
+		div_rem_helper((d.clone() << 192) + 1u64, d);
+
+		// Intermediate remainders of the same length as, but smaller than the denominator
+		let d = BigUInt::from_vec_le(vec![5, 1 << 63]);
+		let n = BigUInt::from_vec_le(vec![7, 0, 1 << 63]);
+		div_rem_helper(n.clone(), d.clone());
+		div_rem_helper(n << 128, d);
+
+		// Leading digits of the numerator and denominator are equal
+		let d = BigUInt::from_vec_le(vec![u64::MAX, 1 << 63]);
+		let n = BigUInt::from_vec_le(vec![0, u64::MAX - 1, 1 << 63]);
+		div_rem_helper(n.clone(), d.clone());
+		div_rem_helper((n << 64) + 3u64, d);
+	}
+
+	#[test]
+	fn test_div_rem_checked() {
+		let n = BigUInt::from(5u64);
+		let d = BigUInt::from_vec_le(vec![1, 2]);
+		assert_eq!(n.div_rem_checked(&d), Ok((BigUInt::ZERO, n.clone())));
+
+		assert_eq!(
+			d.div_rem_checked(&n),
+			Ok((BigUInt::from(7378697629483820646u64), BigUInt::from(3u64)))
+		);
+		assert_eq!(d.div_rem_checked(&d), Ok((BigUInt::ONE, BigUInt::ZERO)));
+		assert_eq!(
+			BigUInt::ZERO.div_rem_checked(&d),
+			Ok((BigUInt::ZERO, BigUInt::ZERO))
+		);
+		assert_eq!(n.div_rem_checked(&BigUInt::ZERO), Err(DivByZeroError));
+	}
+
+	#[test]
+	fn test_div_rem_u64() {
+		let mut next = xorshift64(0x9E3779B97F4A7C15);
+
+		let mut values = vec![BigUInt::ZERO, BigUInt::ONE, BigUInt::from(u64::MAX)];
+		for len in 1..8 {
+			let digits: Vec<u64> = (0..len).map(|_| next()).collect();
+			values.push(BigUInt::from_vec_le(digits));
+		}
+		let divisors = [1, 2, 3, 10, 1 << 63, u64::MAX, next(), next() >> 32];
+
+		for n in &values {
+			for &d in &divisors {
+				let (q, r) = n.div_rem_u64(d);
+				let (q_expected, r_expected) = n.clone().div_rem(&mut BigUInt::from(d));
+				assert_eq!(q, q_expected, "{n} / {d}");
+				assert_eq!(r_expected, BigUInt::from(r));
+
+				let (q_op, r_op) = n.clone().div_rem(d);
+				assert_eq!((q_op, r_op), (q_expected, r_expected));
+			}
+		}
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_div_rem_u64_by_zero() {
+		let _ = BigUInt::from(5u64).div_rem_u64(0);
+	}
+
+	#[test]
+	fn test_div_rem_primitive_by_biguint() {
+		assert_eq!(
+			17u64.div_rem(&BigUInt::from(5u64)),
+			(BigUInt::from(3u64), BigUInt::from(2u64))
+		);
+		assert_eq!(
+			17i32.div_rem(&BigUInt::from(5u64)),
+			(BigUInt::from(3u64), BigUInt::from(2u64))
+		);
 	}
 
 	fn div_rem_helper(mut n: BigUInt, mut d: BigUInt) {
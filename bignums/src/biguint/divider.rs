@@ -0,0 +1,125 @@
+use crate::biguint::{BigUInt, DivRem};
+
+/// Precomputed data for dividing many numbers by the same divisor, using Barrett reduction.
+/// Dividends below 2^(2 * bits), where bits is the bit length of the divisor,
+/// take two multiplications instead of a long division. This covers products of two
+/// remainders, i.e. modular multiplication. Larger dividends fall back to long division.
+///
+/// Whether this is faster depends on the sizes involved, see the div_rem and Divider
+/// benchmarks: the reciprocal costs about as much as one long division,
+/// so it only pays off when dividing by the same number several times.
+#[derive(Debug, Clone)]
+pub struct Divider {
+	d: BigUInt,
+	// Bit length of d
+	bits: u64,
+	// floor(2^(2 * bits) / d)
+	mu: BigUInt,
+}
+
+impl Divider {
+	/// # Panics
+	/// If d == 0
+	pub fn new(d: BigUInt) -> Self {
+		assert!(!d.is_zero(), "division by zero");
+		let bits = d.ilog2() + 1;
+		let mut n = BigUInt::ONE << (2 * bits);
+		let (mu, _) = (&mut n).div_rem(&mut d.clone());
+		Self { d, bits, mu }
+	}
+
+	pub fn divisor(&self) -> &BigUInt {
+		&self.d
+	}
+
+	/// Returns the quotient and remainder of n divided by the divisor
+	pub fn divide(&self, n: &BigUInt) -> (BigUInt, BigUInt) {
+		if n < &self.d {
+			return (BigUInt::ZERO, n.clone());
+		}
+		if n.ilog2() >= 2 * self.bits {
+			return (&mut n.clone()).div_rem(&mut self.d.clone());
+		}
+
+		// Barrett reduction, see Handbook of Applied Cryptography, algorithm 14.42.
+		// The estimate is at most 2 below the quotient.
+		let mut q = &(n.clone() >> (self.bits - 1)) * &self.mu;
+		q >>= self.bits + 1;
+		let mut r = n.clone() - &(&q * &self.d);
+		while r >= self.d {
+			r -= &self.d;
+			q += 1u32;
+		}
+		(q, r)
+	}
+
+	/// Returns the remainder of n divided by the divisor
+	pub fn rem(&self, n: &BigUInt) -> BigUInt {
+		self.divide(n).1
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use alloc::vec::Vec;
+
+	use super::*;
+	use crate::util::xorshift64;
+
+	/// Deterministic pseudo-random numbers of the given lengths in limbs
+	fn random_biguints(lens: &[usize]) -> Vec<BigUInt> {
+		let mut next = xorshift64(0xD1B54A32D192ED03);
+		lens.iter()
+			.map(|&len| BigUInt::from_vec_le((0..len).map(|_| next()).collect()))
+			.collect()
+	}
+
+	#[test]
+	fn test_divider_matches_div_rem() {
+		let mut divisors = random_biguints(&[1, 1, 2, 3, 8, 17]);
+		divisors.extend([1u64, 2, 3, 10, u64::MAX].map(BigUInt::from));
+		divisors.push(BigUInt::ONE << 64u32);
+		divisors.push(BigUInt::ONE << 200u32);
+		let dividends = random_biguints(&[0, 1, 2, 3, 4, 5, 7, 16, 17, 33, 34, 35, 60]);
+
+		for d in divisors {
+			let divider = Divider::new(d.clone());
+			assert_eq!(divider.divisor(), &d);
+			let mut all = dividends.clone();
+			// Around the boundaries of the Barrett range
+			let square = &d * &d;
+			all.push(square.clone());
+			all.push(square.clone() - &BigUInt::ONE);
+			all.push(square + &BigUInt::ONE);
+			all.push(BigUInt::ONE << (2 * (d.ilog2() + 1)));
+			all.push((BigUInt::ONE << (2 * (d.ilog2() + 1))) - &BigUInt::ONE);
+			all.push(d.clone());
+			all.push(d.clone() - &BigUInt::ONE);
+
+			for n in &all {
+				let expected = n.div_rem_checked(&d).unwrap();
+				assert_eq!(divider.divide(n), expected, "{n} / {d}");
+				assert_eq!(divider.rem(n), expected.1);
+			}
+		}
+	}
+
+	#[test]
+	fn test_divider_modular_loop() {
+		// 3^1000 mod p by repeated multiplication, reducing every step
+		let p = (BigUInt::ONE << 127u32) - &BigUInt::ONE;
+		let divider = Divider::new(p.clone());
+		let mut x = BigUInt::ONE;
+		for _ in 0..1000 {
+			x = divider.rem(&(&x * &BigUInt::from(3u32)));
+		}
+		let (_, expected) = BigUInt::from(3u32).pow(1000).div_rem_checked(&p).unwrap();
+		assert_eq!(x, expected);
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_divider_zero() {
+		Divider::new(BigUInt::ZERO);
+	}
+}
@@ -1,3 +1,4 @@
+use core::iter::Product;
 use core::ops::{Mul, MulAssign};
 
 use crate::biguint::BigUInt;
@@ -30,6 +31,55 @@ impl MulTo for BigUInt {
 	}
 }
 
+impl BigUInt {
+	/// Multiplies self by a single digit in place, in one pass over the digits
+	pub fn mul_u64_assign(&mut self, rhs: u64) {
+		if rhs == 0 {
+			self.set_zero();
+			return;
+		}
+
+		let mut carry = 0u64;
+		for x in self.data.iter_mut() {
+			let (lo, hi) = carrying_mul(*x, rhs);
+			let (sum, overflow) = lo.overflowing_add(carry);
+			*x = sum;
+			// Can't overflow, u64 * u64 + u64 fits in 2 u64s
+			carry = hi + overflow as u64;
+		}
+		if carry != 0 {
+			self.data.push(carry);
+		}
+	}
+}
+
+impl BigUInt {
+	/// (self * rhs) mod 2^k, e.g. for wrapping k-bit arithmetic.
+	/// Unlike (self * rhs).mod_pow2(k), only computes the digits below 2^k.
+	pub fn mul_mod_pow2(&self, rhs: &BigUInt, k: u64) -> BigUInt {
+		let limbs = usize::try_from(k.div_ceil(u64::BITS as u64)).unwrap_or(usize::MAX);
+		let new_len = usize::min(limbs, self.len() + rhs.len());
+		let mut res = BigUInt::ZERO;
+		res.data.set_len_fill_zero(new_len);
+		for (i, &a_i) in self.data.iter().take(new_len).enumerate() {
+			// Digits from i + j = new_len on are cut off, along with the carries into them
+			let mut carry = 0u64;
+			for (j, &b_j) in rhs.data.iter().take(new_len - i).enumerate() {
+				let (lo, hi) = carrying_mul(a_i, b_j);
+				let (sum1, carry1) = lo.overflowing_add(carry);
+				let (sum2, carry2) = res[i + j].overflowing_add(sum1);
+				res.data[i + j] = sum2;
+				carry = hi + carry1 as u64 + carry2 as u64;
+			}
+			if i + rhs.len() < new_len {
+				res.data[i + rhs.len()] = carry;
+			}
+		}
+		res.mod_pow2_assign(k);
+		res
+	}
+}
+
 impl Mul<&BigUInt> for &BigUInt {
 	type Output = BigUInt;
 
@@ -60,7 +110,37 @@ macro_rules! impl_mul_u {
 	}
 }
 
-impl_mul_u! { u8, u16, u32, u64, u128, usize }
+impl_mul_u! { u128 }
+
+macro_rules! impl_mul_digit {
+	($($t:ty),*) => {
+		$(
+			impl Mul<$t> for &BigUInt {
+				type Output = BigUInt;
+				fn mul(self, rhs: $t) -> Self::Output {
+					let mut res = self.clone();
+					res.mul_u64_assign(rhs as u64);
+					res
+				}
+			}
+
+			impl Mul<&BigUInt> for $t {
+				type Output = BigUInt;
+				fn mul(self, rhs: &BigUInt) -> Self::Output {
+					rhs * self
+				}
+			}
+
+			impl MulAssign<$t> for BigUInt {
+				fn mul_assign(&mut self, rhs: $t) {
+					self.mul_u64_assign(rhs as u64);
+				}
+			}
+		)*
+	}
+}
+
+impl_mul_digit! { u8, u16, u32, u64, usize }
 
 macro_rules! impl_mul_i {
 	($($t:ty),*) => {
@@ -96,13 +176,31 @@ macro_rules! impl_mul_assign {
 	}
 }
 
-impl_mul_assign! { u8, u16, u32, u64, u128, usize, &BigUInt,
+impl_mul_assign! { u128, &BigUInt,
 i8, i16, i32, i64, i128, isize}
 
+impl Product for BigUInt {
+	fn product<I: Iterator<Item = BigUInt>>(iter: I) -> Self {
+		iter.fold(BigUInt::ONE, |mut acc, x| {
+			acc *= &x;
+			acc
+		})
+	}
+}
+
+impl<'a> Product<&'a BigUInt> for BigUInt {
+	fn product<I: Iterator<Item = &'a BigUInt>>(iter: I) -> Self {
+		iter.fold(BigUInt::ONE, |mut acc, x| {
+			acc *= x;
+			acc
+		})
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::util::{from_foreign_biguint, to_foreign_biguint};
+	use crate::util::{from_foreign_biguint, to_foreign_biguint, xorshift64};
 
 	#[test]
 	fn test_mul() {
@@ -134,9 +232,92 @@ mod tests {
 		mul_helper(BigUInt::from(u128::MAX), BigUInt::from(u128::MAX));
 	}
 
+	#[test]
+	fn test_mul_u64_assign() {
+		let mut next = xorshift64(0x2545F4914F6CDD1D);
+
+		let mut values = vec![BigUInt::ZERO, BigUInt::ONE, BigUInt::from(u64::MAX)];
+		for len in 1..8 {
+			let digits: Vec<u64> = (0..len).map(|_| next()).collect();
+			values.push(BigUInt::from_vec_le(digits));
+		}
+		let rhs = [0, 1, 2, 10, u64::MAX, next(), next()];
+
+		for a in &values {
+			for &b in &rhs {
+				let expected = a * &BigUInt::from_le_limbs(&[b]);
+				let mut in_place = a.clone();
+				in_place.mul_u64_assign(b);
+				assert_eq!(in_place, expected, "{a} * {b}");
+
+				let mut op = a.clone();
+				op *= b;
+				assert_eq!(op, expected);
+				assert_eq!(a * b, expected);
+				assert_eq!(b * a, expected);
+			}
+		}
+	}
+
+	#[test]
+	fn test_mul_mod_pow2() {
+		let mut next = xorshift64(0x9E3779B97F4A7C15);
+
+		let mut values = vec![BigUInt::ZERO, BigUInt::ONE, BigUInt::from(u128::MAX)];
+		for len in 1..6 {
+			let digits: Vec<u64> = (0..len).map(|_| next()).collect();
+			values.push(BigUInt::from_vec_le(digits));
+		}
+
+		for a in &values {
+			for b in &values {
+				let product = a * b;
+				// Cuts inside a digit, at digit boundaries and past the full product
+				for k in [0, 1, 13, 63, 64, 65, 127, 128, 200, 320, 640, 1000] {
+					let expected = product.mod_pow2(k);
+					assert_eq!(a.mul_mod_pow2(b, k), expected, "{a} * {b} mod 2^{k}");
+					assert_eq!(b.mul_mod_pow2(a, k), expected, "{b} * {a} mod 2^{k}");
+				}
+				assert_eq!(a.mul_mod_pow2(b, u64::MAX), product);
+			}
+		}
+
+		// Wrapping u64 and u128 multiplication
+		let (x, y) = (next(), next());
+		let (bx, by) = (BigUInt::from(x), BigUInt::from(y));
+		assert_eq!(bx.mul_mod_pow2(&by, 64), BigUInt::from(x.wrapping_mul(y)));
+		let (x, y) = (
+			(x as u128) << 64 | next() as u128,
+			(y as u128) << 64 | next() as u128,
+		);
+		let (bx, by) = (BigUInt::from(x), BigUInt::from(y));
+		assert_eq!(bx.mul_mod_pow2(&by, 128), BigUInt::from(x.wrapping_mul(y)));
+
+		// The low digits of the product are zero
+		let a = BigUInt::ONE << 300u32;
+		assert_eq!(a.mul_mod_pow2(&a, 600), BigUInt::ZERO);
+		assert_eq!(a.mul_mod_pow2(&a, 601), BigUInt::ONE << 600u32);
+	}
+
 	fn mul_helper(a: BigUInt, b: BigUInt) {
 		let res_native = &a * &b;
 		let res_foreign = from_foreign_biguint(to_foreign_biguint(a) * to_foreign_biguint(b));
 		assert_eq!(res_native, res_foreign)
 	}
+
+	#[test]
+	fn test_product() {
+		let values: [BigUInt; 3] = [
+			BigUInt::from(u64::MAX),
+			BigUInt::from(3u64),
+			BigUInt::from(u64::MAX),
+		];
+		let expected = &BigUInt::from(u64::MAX as u128 * u64::MAX as u128) * &BigUInt::from(3u64);
+		assert_eq!(values.iter().product::<BigUInt>(), expected);
+		assert_eq!(values.into_iter().product::<BigUInt>(), expected);
+		assert_eq!(
+			core::iter::empty::<&BigUInt>().product::<BigUInt>(),
+			BigUInt::ONE
+		);
+	}
 }
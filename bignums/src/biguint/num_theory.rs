@@ -20,8 +20,158 @@ impl BigUInt {
 		a
 	}
 
-	pub fn lcm(self, other: BigUInt) -> BigUInt {
-		&mut (&self * &other) / &mut self.gcd(other)
+	/// Least common multiple, which is 0 if either input is 0
+	pub fn lcm(&self, other: &BigUInt) -> BigUInt {
+		if self.is_zero() || other.is_zero() {
+			return BigUInt::ZERO;
+		}
+		// Divide first to keep the intermediate small
+		let mut gcd = self.clone().gcd(other.clone());
+		let mut res = &mut self.clone() / &mut gcd;
+		res *= other;
+		res
+	}
+
+	/// Whether gcd(self, other) = 1. 1 is coprime to every number, including 0,
+	/// and 0 is coprime to nothing else.
+	pub fn is_coprime(&self, other: &BigUInt) -> bool {
+		// Even numbers share the factor 2, no need for the gcd
+		if !self.bit(0) && !other.bit(0) {
+			return false;
+		}
+		self.clone().gcd(other.clone()).is_one()
+	}
+
+	/// Whether self = k * d for some integer k.
+	/// Zero is a multiple of every d, including 0, and no other number is a multiple of 0.
+	pub fn is_multiple_of(&self, d: &BigUInt) -> bool {
+		if self.is_zero() {
+			return true;
+		}
+		if d.is_zero() || self < d {
+			return false;
+		}
+		if let Some(k) = d.ilog2_exact() {
+			return self.trailing_zeros() >= k;
+		}
+		if let [d] = d.data[..] {
+			return self.div_rem_u64(d).1 == 0;
+		}
+		let (_, r) = (&mut self.clone()).div_rem(&mut d.clone());
+		r.is_zero()
+	}
+
+	/// Whether self divides n, i.e. n.is_multiple_of(self). 0 only divides 0.
+	pub fn divides(&self, n: &BigUInt) -> bool {
+		n.is_multiple_of(self)
+	}
+
+	/// Returns the inverse of self modulo `modulus` in [0, modulus),
+	/// or None if they aren't coprime or the modulus is zero.
+	pub fn modinv(&self, modulus: &BigUInt) -> Option<BigUInt> {
+		if modulus.is_zero() {
+			return None;
+		}
+		if modulus.is_one() {
+			return Some(BigUInt::ZERO);
+		}
+
+		let (_, a) = (&mut self.clone()).div_rem(&mut modulus.clone());
+		if modulus.bit(0) {
+			return a.modinv_odd(modulus);
+		}
+		if !a.bit(0) {
+			return None;
+		}
+		if a.is_one() {
+			return Some(a);
+		}
+
+		// The modulus is even and a is odd. With y = m^-1 (mod a) we have m * y = 1 + a * k,
+		// so a * (m - k) = 1 (mod m), where 0 < k < m since 0 < y < a.
+		let (_, m) = (&mut modulus.clone()).div_rem(&mut a.clone());
+		let y = m.modinv_odd(&a)?;
+		let mut k = modulus * &y;
+		k -= 1u64;
+		let k = &mut k / &mut a.clone();
+		let mut res = modulus.clone();
+		res -= &k;
+		Some(res)
+	}
+
+	/// Binary extended gcd, all values are kept in [0, modulus).
+	/// Expects self < modulus and an odd modulus > 1.
+	fn modinv_odd(&self, modulus: &BigUInt) -> Option<BigUInt> {
+		// Invariants: x1 * self = u (mod m), x2 * self = v (mod m)
+		let mut u = self.clone();
+		let mut v = modulus.clone();
+		let mut x1 = BigUInt::ONE;
+		let mut x2 = BigUInt::ZERO;
+
+		while !u.is_zero() {
+			Self::halve_mod(&mut u, &mut x1, modulus);
+			if u.is_one() {
+				return Some(x1);
+			}
+			Self::halve_mod(&mut v, &mut x2, modulus);
+			if v.is_one() {
+				return Some(x2);
+			}
+
+			if u >= v {
+				u -= &v;
+				Self::sub_mod(&mut x1, &x2, modulus);
+			} else {
+				v -= &u;
+				Self::sub_mod(&mut x2, &x1, modulus);
+			}
+		}
+
+		// u and v met at their gcd, which isn't 1
+		None
+	}
+
+	/// Divides u by its largest power of two dividing it, and x by the same power modulo an odd m.
+	fn halve_mod(u: &mut BigUInt, x: &mut BigUInt, m: &BigUInt) {
+		let shift = u.trailing_zeros();
+		*u >>= shift;
+		for _ in 0..shift {
+			if x.bit(0) {
+				*x += m;
+			}
+			*x >>= 1u32;
+		}
+	}
+
+	/// x = (x - y) mod m, where x, y < m
+	fn sub_mod(x: &mut BigUInt, y: &BigUInt, m: &BigUInt) {
+		if *x < *y {
+			*x += m;
+		}
+		*x -= y;
+	}
+
+	/// Binomial coefficient self choose k, which is 0 for k > self
+	pub fn binomial(&self, k: u64) -> BigUInt {
+		if *self < k {
+			return BigUInt::ZERO;
+		}
+		// C(n, k) = C(n, n - k)
+		let k = match u64::try_from(self) {
+			Ok(n) => k.min(n - k),
+			Err(_) => k,
+		};
+
+		// After step i, res = self * ... * (self - i + 1) / i!, which is an integer
+		let mut res = BigUInt::ONE;
+		let mut factor = self.clone();
+		for i in 1..=k {
+			res *= &factor;
+			let rem = res.div_rem_u64_assign(i);
+			debug_assert_eq!(rem, 0, "binomial step is not exact");
+			factor -= 1u64;
+		}
+		res
 	}
 
 	pub fn factorial(&self) -> BigUInt {
@@ -39,6 +189,7 @@ impl BigUInt {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::util::{to_foreign_biguint, xorshift64};
 
 	#[test]
 	fn test_gcd() {
@@ -63,6 +214,174 @@ mod tests {
 		assert_eq!(a.gcd(b), BigUInt::from(5u32));
 	}
 
+	#[test]
+	fn test_is_multiple_of() {
+		for n in 0..200u64 {
+			for d in 1..40u64 {
+				let (bn, bd) = (BigUInt::from(n), BigUInt::from(d));
+				assert_eq!(bn.is_multiple_of(&bd), n % d == 0, "{n} {d}");
+				assert_eq!(bd.divides(&bn), n % d == 0, "{n} {d}");
+			}
+		}
+
+		// Zero divisor
+		assert!(BigUInt::ZERO.is_multiple_of(&BigUInt::ZERO));
+		assert!(!BigUInt::ONE.is_multiple_of(&BigUInt::ZERO));
+		assert!(BigUInt::ZERO.divides(&BigUInt::ZERO));
+		assert!(!BigUInt::ZERO.divides(&BigUInt::from(5u32)));
+		assert!(BigUInt::from(5u32).divides(&BigUInt::ZERO));
+
+		// Powers of two, including ones past the first digit
+		let x = BigUInt::from(3u32) << 200u32;
+		assert!(x.is_multiple_of(&(BigUInt::ONE << 200u32)));
+		assert!(!x.is_multiple_of(&(BigUInt::ONE << 201u32)));
+		assert!(x.is_multiple_of(&BigUInt::ONE));
+		assert!(!(x.clone() + 1u32).is_multiple_of(&BigUInt::from(2u32)));
+
+		// Multi-digit divisors
+		let p = (BigUInt::ONE << 127u32) - &BigUInt::ONE;
+		let q = BigUInt::from(u64::MAX - 58);
+		let pq = &p * &q;
+		assert!(pq.is_multiple_of(&p) && pq.is_multiple_of(&q));
+		assert!(p.divides(&pq) && q.divides(&pq));
+		assert!(!(pq.clone() + 1u32).is_multiple_of(&p));
+		assert!(!p.is_multiple_of(&pq));
+		assert!(pq.is_multiple_of(&pq));
+	}
+
+	#[test]
+	fn test_binomial() {
+		let binomial = |n: u64, k: u64| BigUInt::from(n).binomial(k);
+		assert_eq!(binomial(0, 0), BigUInt::ONE);
+		assert_eq!(binomial(5, 0), BigUInt::ONE);
+		assert_eq!(binomial(5, 5), BigUInt::ONE);
+		assert_eq!(binomial(5, 2), BigUInt::from(10u64));
+		assert_eq!(binomial(5, 6), BigUInt::ZERO);
+		assert_eq!(binomial(52, 5), BigUInt::from(2598960u64));
+		assert_eq!(binomial(u64::MAX, u64::MAX - 1), BigUInt::from(u64::MAX));
+
+		// Pascal's rule
+		for n in 1..60u64 {
+			for k in 1..=n {
+				assert_eq!(binomial(n, k), binomial(n - 1, k - 1) + &binomial(n - 1, k));
+			}
+		}
+
+		let n = BigUInt::ONE << 100u32;
+		assert_eq!(n.binomial(1), n);
+		let product = &(&n * &(n.clone() - 1u32)) * &(n.clone() - 2u32);
+		assert_eq!(&n.binomial(3) * &BigUInt::from(6u32), product);
+	}
+
+	#[test]
+	fn test_lcm() {
+		let lcm = |a: u64, b: u64| BigUInt::from(a).lcm(&BigUInt::from(b));
+		assert_eq!(lcm(4, 6), BigUInt::from(12u64));
+		assert_eq!(lcm(7, 13), BigUInt::from(91u64));
+		assert_eq!(lcm(12, 12), BigUInt::from(12u64));
+		assert_eq!(lcm(1, 9), BigUInt::from(9u64));
+		assert_eq!(lcm(0, 9), BigUInt::ZERO);
+		assert_eq!(lcm(9, 0), BigUInt::ZERO);
+		assert_eq!(lcm(0, 0), BigUInt::ZERO);
+
+		let p = &BigUInt::from(u64::MAX) * &BigUInt::from(1_000_003u64);
+		let pairs = [
+			(BigUInt::from(48u64), BigUInt::from(18u64)),
+			(BigUInt::from(17u64), BigUInt::from(19u64)),
+			(&p * &BigUInt::from(6u64), &p * &BigUInt::from(10u64)),
+			(p.clone(), BigUInt::from(u64::MAX - 1)),
+			(
+				(BigUInt::ONE << 200u32) - 1u64,
+				(BigUInt::ONE << 120u32) - 1u64,
+			),
+		];
+		for (a, b) in pairs {
+			let gcd = a.clone().gcd(b.clone());
+			let lcm = a.lcm(&b);
+			assert_eq!(&gcd * &lcm, &a * &b, "{a}, {b}");
+			assert_eq!(lcm, b.lcm(&a));
+		}
+	}
+
+	#[test]
+	fn test_is_coprime() {
+		let coprime = |a: u64, b: u64| BigUInt::from(a).is_coprime(&BigUInt::from(b));
+		for a in 0..60u64 {
+			for b in 0..60u64 {
+				let expected = BigUInt::from(a).gcd(BigUInt::from(b)).is_one();
+				assert_eq!(coprime(a, b), expected, "{a} {b}");
+			}
+		}
+		assert!(coprime(7, 13));
+		assert!(coprime(8, 15));
+		assert!(!coprime(6, 9));
+		assert!(!coprime(4, 10));
+		// Zero and one
+		assert!(coprime(1, 0));
+		assert!(coprime(0, 1));
+		assert!(coprime(1, 1));
+		assert!(!coprime(0, 0));
+		assert!(!coprime(0, 5));
+		assert!(!coprime(5, 0));
+
+		let p = (BigUInt::ONE << 127u32) - 1u64;
+		let q = BigUInt::from(u64::MAX - 58);
+		assert!(p.is_coprime(&q));
+		assert!(!(&p * &q).is_coprime(&(&q * 3u64)));
+		assert!((&p * &q).is_coprime(&(BigUInt::ONE << 300u32)));
+		assert!(!(BigUInt::ONE << 300u32).is_coprime(&(BigUInt::ONE << 64u32)));
+	}
+
+	#[test]
+	fn test_modinv() {
+		let inv = |a: u64, m: u64| BigUInt::from(a).modinv(&BigUInt::from(m));
+		assert_eq!(inv(3, 7), Some(BigUInt::from(5u64)));
+		assert_eq!(inv(10, 7), Some(BigUInt::from(5u64)));
+		assert_eq!(inv(3, 8), Some(BigUInt::from(3u64)));
+		assert_eq!(inv(1, 2), Some(BigUInt::ONE));
+		assert_eq!(inv(5, 1), Some(BigUInt::ZERO));
+		assert_eq!(inv(0, 1), Some(BigUInt::ZERO));
+		assert_eq!(inv(0, 7), None);
+		assert_eq!(inv(14, 7), None);
+		assert_eq!(inv(6, 9), None);
+		assert_eq!(inv(4, 10), None);
+		assert_eq!(inv(5, 10), None);
+		assert_eq!(inv(3, 0), None);
+	}
+
+	#[test]
+	fn test_modinv_foreign() {
+		let mut next = xorshift64(0x2545F4914F6CDD1D);
+
+		let mut checked = 0;
+		for i in 0..400 {
+			let a_len = 1 + i % 4;
+			let m_len = 1 + (i / 4) % 4;
+			let a = BigUInt::from_vec_le((0..a_len).map(|_| next()).collect());
+			let mut m = BigUInt::from_vec_le((0..m_len).map(|_| next()).collect());
+			if i % 3 == 0 {
+				// even moduli take a different path
+				m <<= 5u32;
+			}
+
+			let expected = to_foreign_biguint(a.clone()).modinv(&to_foreign_biguint(m.clone()));
+			let res = a.modinv(&m);
+			assert_eq!(
+				res.clone().map(to_foreign_biguint),
+				expected,
+				"{a}^-1 mod {m}"
+			);
+
+			if let Some(x) = res {
+				assert!(x < m);
+				let (_, r) = (&mut (&a * &x)).div_rem(&mut m.clone());
+				assert!(r.is_one());
+				checked += 1;
+			}
+		}
+		assert!(checked > 100);
+	}
+
 	#[test]
 	fn test_factorial() {
 		assert_eq!(BigUInt::from(0u32).factorial(), BigUInt::from(1u32));
@@ -1,8 +1,73 @@
+use core::mem;
+
 use crate::SetVal;
+use crate::bigfloat::BigFloat;
 use crate::biguint::{BigUInt, MulTo};
 
 impl BigUInt {
-	pub fn pow(&self, mut power: u64) -> Self {
+	/// Returns self^power, where 0^0 = 1
+	pub fn pow(&self, power: u64) -> Self {
+		if self.is_zero() {
+			return if power == 0 {
+				BigUInt::ONE
+			} else {
+				BigUInt::ZERO
+			};
+		}
+		// Covers self = 1 with k = 0
+		if let Some(k) = self.ilog2_exact() {
+			let shift = k.checked_mul(power).expect("attempt to pow with overflow");
+			return BigUInt::ONE << shift;
+		}
+		self.pow_by_squaring(power)
+	}
+
+	/// Raises self to the power in place, where 0^0 = 1
+	pub fn pow_assign(&mut self, power: u64) {
+		if power == 0 {
+			self.set_val(1u64);
+			return;
+		}
+		if self.is_zero() {
+			return;
+		}
+		if let Some(k) = self.ilog2_exact() {
+			let shift = k.checked_mul(power).expect("attempt to pow with overflow");
+			self.set_val(1u64);
+			*self <<= shift;
+			return;
+		}
+
+		// Left-to-right binary exponentiation, using self as the accumulator
+		let base = self.clone();
+		let mut tmp = BigUInt::ZERO;
+		for i in (0..power.ilog2()).rev() {
+			tmp.mul_to(self, self);
+			mem::swap(self, &mut tmp);
+			if (power >> i) & 1 == 1 {
+				tmp.mul_to(self, &base);
+				mem::swap(self, &mut tmp);
+			}
+		}
+	}
+
+	/// Whether self^power is certainly below 2^max_log2,
+	/// judging by the upper bound power * (ilog2(self) + 1) on its bit length
+	pub fn pow_fits(&self, power: u64, max_log2: u64) -> bool {
+		if self.is_zero() || self.is_one() || power == 0 {
+			return max_log2 > 0;
+		}
+		(self.ilog2() + 1)
+			.checked_mul(power)
+			.is_some_and(|max_bits| max_bits <= max_log2)
+	}
+
+	/// Returns self^power, or None if the result could be 2^max_log2 or more, see pow_fits
+	pub fn checked_pow(&self, power: u64, max_log2: u64) -> Option<Self> {
+		self.pow_fits(power, max_log2).then(|| self.pow(power))
+	}
+
+	fn pow_by_squaring(&self, mut power: u64) -> Self {
 		let mut tmp = BigUInt::ZERO;
 		let mut power_of_self = self.clone();
 		let mut res = BigUInt::ONE;
@@ -20,6 +85,56 @@ impl BigUInt {
 		res
 	}
 
+	/// Returns the square root as a BigFloat. Absolute error < 2^-prec.
+	pub fn sqrt_float(&self, prec: i64) -> BigFloat {
+		BigFloat::from(self.clone()).sqrt(prec)
+	}
+
+	/// floor(sqrt(self)) by Newton's method, approaching from above
+	pub fn isqrt(&self) -> BigUInt {
+		if self.is_zero() {
+			return BigUInt::ZERO;
+		}
+		let mut x = BigUInt::ONE << (self.ilog2() / 2 + 1);
+		loop {
+			let y = (&mut self.clone() / &mut x.clone() + &x) >> 1u32;
+			if y >= x {
+				return x;
+			}
+			x = y;
+		}
+	}
+
+	/// floor(self^(1/n)) by Newton's method, approaching from above
+	/// # Panics
+	/// If n is 0
+	pub fn nth_root(&self, n: u32) -> BigUInt {
+		assert!(n != 0, "attempt to take the 0th root");
+		if self.is_zero() {
+			return BigUInt::ZERO;
+		}
+		if n == 1 {
+			return self.clone();
+		}
+		let bits = self.ilog2() + 1;
+		if u64::from(n) >= bits {
+			// self < 2^n
+			return BigUInt::ONE;
+		}
+
+		// x = ((n - 1) * x + self / x^(n - 1)) / n
+		let mut x = BigUInt::ONE << bits.div_ceil(u64::from(n));
+		loop {
+			let mut y = &mut self.clone() / &mut x.pow(u64::from(n) - 1);
+			y += &(&x * u64::from(n - 1));
+			y.div_rem_u64_assign(u64::from(n));
+			if y >= x {
+				return x;
+			}
+			x = y;
+		}
+	}
+
 	/// Returns log2(self) if self is a power of 2, otherwise None.
 	pub fn ilog2_exact(&self) -> Option<u64> {
 		let hi = self.data.last().copied()?;
@@ -35,14 +150,23 @@ impl BigUInt {
 	}
 
 	pub fn ilog2(&self) -> u64 {
-		let hi = self.data.last().copied().expect("attempt to take ilog2(0)");
-		(u64::BITS as u64 - hi.leading_zeros() as u64 - 1u64)
-			+ (self.len() as u64 - 1u64) * (u64::BITS as u64)
+		self.checked_ilog2().expect("attempt to take ilog2(0)")
+	}
+
+	/// Returns floor(log2(self)), or None if self is zero.
+	pub fn checked_ilog2(&self) -> Option<u64> {
+		let hi = self.data.last().copied()?;
+		Some(
+			(u64::BITS as u64 - hi.leading_zeros() as u64 - 1u64)
+				+ (self.len() as u64 - 1u64) * (u64::BITS as u64),
+		)
 	}
 }
 
 #[cfg(test)]
 mod tests {
+	use alloc::vec;
+
 	use super::*;
 
 	#[test]
@@ -75,6 +199,22 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_checked_ilog2() {
+		assert_eq!(BigUInt::ZERO.checked_ilog2(), None);
+		assert_eq!(BigUInt::ONE.checked_ilog2(), Some(0));
+		assert_eq!(BigUInt::from(u64::MAX).checked_ilog2(), Some(63));
+		let x = BigUInt::from_vec_le(vec![5, 0, 3]);
+		assert_eq!(x.checked_ilog2(), Some(129));
+		assert_eq!(x.checked_ilog2(), Some(x.ilog2()));
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_ilog2_zero() {
+		let _ = BigUInt::ZERO.ilog2();
+	}
+
 	#[test]
 	fn test_ilog2() {
 		assert_eq!(BigUInt::from(1u64).ilog2(), 0);
@@ -100,4 +240,128 @@ mod tests {
 		assert_eq!(BigUInt::from(3u64).pow(4), BigUInt::from(81u64));
 		assert_eq!(BigUInt::from(5u64).pow(2), BigUInt::from(25u64));
 	}
+
+	#[test]
+	fn test_pow_shortcuts() {
+		assert_eq!(BigUInt::ZERO.pow(0), BigUInt::ONE);
+		assert_eq!(BigUInt::ZERO.pow(1), BigUInt::ZERO);
+		assert_eq!(BigUInt::ZERO.pow(u64::MAX), BigUInt::ZERO);
+		assert_eq!(BigUInt::ONE.pow(0), BigUInt::ONE);
+		assert_eq!(BigUInt::ONE.pow(u64::MAX), BigUInt::ONE);
+		assert_eq!(BigUInt::from(8u32).pow(5), BigUInt::ONE << 15u32);
+		assert_eq!((BigUInt::ONE << 100u32).pow(30), BigUInt::ONE << 3000u32);
+
+		// Same results as the general algorithm
+		let bases = [0u32, 1, 2, 4, 64, 1 << 31]
+			.map(BigUInt::from)
+			.into_iter()
+			.chain([BigUInt::ONE << 64u32, BigUInt::ONE << 127u32]);
+		for base in bases {
+			for power in [0, 1, 2, 3, 7, 64, 65] {
+				assert_eq!(
+					base.pow(power),
+					base.pow_by_squaring(power),
+					"{base}^{power}"
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn test_pow_assign() {
+		let bases = [0u64, 1, 2, 3, 10, 64, u64::MAX]
+			.map(BigUInt::from)
+			.into_iter()
+			.chain([BigUInt::from(u128::MAX), BigUInt::ONE << 127u32]);
+		for base in bases {
+			for power in [0, 1, 2, 3, 7, 64, 65] {
+				let mut x = base.clone();
+				x.pow_assign(power);
+				assert_eq!(x, base.pow_by_squaring(power), "{base}^{power}");
+			}
+		}
+	}
+
+	#[test]
+	fn test_checked_pow() {
+		let a = BigUInt::from(10u32);
+		assert_eq!(a.checked_pow(0, 1), Some(BigUInt::ONE));
+		// 10 has 4 bits, so 10^20 < 2^80
+		assert_eq!(a.checked_pow(20, 80), Some(BigUInt::from(10u128.pow(20))));
+		assert_eq!(a.checked_pow(20, 79), None);
+		assert_eq!(a.checked_pow(u64::MAX, u64::MAX), None);
+		assert_eq!(a.checked_pow(1 << 62, u64::MAX), None);
+		assert_eq!(BigUInt::ZERO.checked_pow(u64::MAX, 1), Some(BigUInt::ZERO));
+		assert_eq!(BigUInt::ONE.checked_pow(u64::MAX, 1), Some(BigUInt::ONE));
+		assert_eq!(BigUInt::ONE.checked_pow(5, 0), None);
+		assert_eq!(BigUInt::from(2u32).checked_pow(u64::MAX, u64::MAX), None);
+		assert_eq!((BigUInt::ONE << 100u32).checked_pow(1 << 20, 1 << 26), None);
+
+		assert!(BigUInt::from(255u32).pow_fits(8, 64));
+		assert!(!BigUInt::from(256u32).pow_fits(8, 64));
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_pow_pow2_overflow() {
+		BigUInt::from(4u32).pow(u64::MAX);
+	}
+
+	#[test]
+	fn test_isqrt() {
+		for n in 0..2000u64 {
+			let r = BigUInt::from(n).isqrt();
+			assert_eq!(r, BigUInt::from(n.isqrt()), "{n}");
+		}
+		let big = BigUInt::from(u128::MAX);
+		assert_eq!(big.isqrt(), BigUInt::from(u64::MAX));
+		let square = &big * &big;
+		assert_eq!(square.isqrt(), big);
+		assert_eq!((square - 1u64).isqrt(), big.clone() - 1u64);
+	}
+
+	#[test]
+	fn test_nth_root() {
+		for n in 0..3000u64 {
+			let x = BigUInt::from(n);
+			assert_eq!(x.nth_root(1), x);
+			assert_eq!(x.nth_root(2), x.isqrt(), "{n}");
+			for k in 3..12u32 {
+				let r = x.nth_root(k);
+				assert!(r.pow(k as u64) <= x, "{n} {k}");
+				assert!((r + 1u64).pow(k as u64) > x, "{n} {k}");
+			}
+		}
+
+		// Perfect powers and their neighbours
+		let base = BigUInt::from(u64::MAX - 58);
+		for k in [3u32, 5, 7, 30] {
+			let x = base.pow(k as u64);
+			assert_eq!(x.nth_root(k), base);
+			assert_eq!((x.clone() - 1u64).nth_root(k), base.clone() - 1u64);
+			assert_eq!((x + 1u64).nth_root(k), base);
+		}
+		let x = BigUInt::ONE << 3000u32;
+		assert_eq!(x.nth_root(3), BigUInt::ONE << 1000u32);
+		assert_eq!(x.nth_root(3000), BigUInt::from(2u32));
+		assert_eq!(x.nth_root(3001), BigUInt::ONE);
+		assert_eq!(BigUInt::ONE.nth_root(u32::MAX), BigUInt::ONE);
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_nth_root_zero() {
+		BigUInt::from(8u32).nth_root(0);
+	}
+
+	#[test]
+	fn test_sqrt_float() {
+		let prec = 200;
+		let sqrt2 = BigUInt::from(2u32).sqrt_float(prec);
+		assert!((&sqrt2 - &BigFloat::sqrt2(prec)).abs() < (BigFloat::ONE >> (prec - 1)));
+
+		assert_eq!(BigUInt::from(144u32).sqrt_float(prec), BigFloat::from(12));
+		assert_eq!(BigUInt::ZERO.sqrt_float(prec), BigFloat::ZERO);
+		assert_eq!(BigUInt::ONE.sqrt_float(prec), BigFloat::ONE);
+	}
 }
@@ -0,0 +1,302 @@
+use crate::bigint::BigInt;
+use crate::biguint::{BigUInt, Divider};
+
+/// Primes tried by trial division before the probable prime tests
+const SMALL_PRIMES: [u64; 25] = [
+	2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+];
+
+impl BigUInt {
+	/// Baillie-PSW primality test: a strong Fermat test to base 2 followed by a strong Lucas test
+	/// with Selfridge's parameters. It's deterministic, exact for all n < 2^64,
+	/// and no composite passing it is known.
+	pub fn is_prime_bpsw(&self) -> bool {
+		for p in SMALL_PRIMES {
+			if *self == p {
+				return true;
+			}
+			if self.div_rem_u64(p).1 == 0 {
+				return false;
+			}
+		}
+		if *self < 100u64 {
+			// 0 and 1, everything else below 100 is a multiple of a small prime or one of them
+			return false;
+		}
+
+		let divider = Divider::new(self.clone());
+		self.is_strong_probable_prime_base2(&divider)
+			&& self.is_strong_lucas_probable_prime(&divider)
+	}
+
+	/// Euler's totient, the count of k in [1, self] coprime to self, with phi(0) = 0.
+	/// It factors self by trial division, stopping early once the remaining cofactor is prime,
+	/// so it's only practical when every prime factor but the largest one is small.
+	pub fn euler_phi(&self) -> BigUInt {
+		if self.is_zero() {
+			return BigUInt::ZERO;
+		}
+
+		// phi(n) = n * (1 - 1/p) * ... over the distinct primes p dividing n
+		let mut res = self.clone();
+		let mut n = self.clone();
+		let mut n_changed = true;
+		let mut p = 2u64;
+		while !n.is_one() {
+			if n.cmp_u128(u128::from(p) * u128::from(p)).is_lt() || n_changed && n.is_prime_bpsw() {
+				// The cofactor is the last prime factor
+				res = &mut res / &mut n.clone();
+				res *= &(n - 1u64);
+				break;
+			}
+
+			n_changed = false;
+			let (mut q, mut r) = n.div_rem_u64(p);
+			if r == 0 {
+				while r == 0 {
+					n = q;
+					(q, r) = n.div_rem_u64(p);
+				}
+				res.div_rem_u64_assign(p);
+				res *= p - 1;
+				n_changed = true;
+			}
+			p = if p == 2 { 3 } else { p + 2 };
+		}
+		res
+	}
+
+	/// Miller-Rabin test to base 2, expects an odd self > 2
+	fn is_strong_probable_prime_base2(&self, divider: &Divider) -> bool {
+		let n_minus_one = self.clone() - 1u64;
+		let s = n_minus_one.trailing_zeros();
+		let d = n_minus_one.clone() >> s;
+
+		let mut x = pow_mod(&BigUInt::from(2u64), &d, divider);
+		if x.is_one() || x == n_minus_one {
+			return true;
+		}
+		for _ in 1..s {
+			x = divider.rem(&(&x * &x));
+			if x == n_minus_one {
+				return true;
+			}
+		}
+		false
+	}
+
+	/// Strong Lucas test with P = 1, Q = (1 - D) / 4 for the first D in 5, -7, 9, -11, ...
+	/// with Jacobi symbol (D/n) = -1. Expects an odd self > 2 without small factors.
+	fn is_strong_lucas_probable_prime(&self, divider: &Divider) -> bool {
+		// There is no such D for squares, the search would not end
+		if self.isqrt().pow(2) == *self {
+			return false;
+		}
+
+		let mut d = 5i64;
+		loop {
+			match BigInt::jacobi(&BigInt::from(d), self) {
+				-1 => break,
+				// self shares a factor with |d| < self
+				0 if *self != d.unsigned_abs() => return false,
+				_ => d = if d > 0 { -d - 2 } else { -d + 2 },
+			}
+		}
+		let d_mod = to_residue(d, self);
+		let q_mod = to_residue((1 - d) / 4, self);
+
+		// n + 1 = k * 2^s with k odd
+		let n_plus_one = self.clone() + 1u64;
+		let s = n_plus_one.trailing_zeros();
+		let k = n_plus_one >> s;
+
+		// U_k, V_k and Q^k by binary expansion of k, starting from U_1 = 1, V_1 = P = 1
+		let mut u = BigUInt::ONE;
+		let mut v = BigUInt::ONE;
+		let mut q_k = q_mod.clone();
+		for i in (0..k.ilog2()).rev() {
+			// U_2j = U_j * V_j, V_2j = V_j^2 - 2 * Q^j
+			u = divider.rem(&(&u * &v));
+			v = sub_mod(
+				divider.rem(&(&v * &v)),
+				&add_mod(q_k.clone(), &q_k, self),
+				self,
+			);
+			q_k = divider.rem(&(&q_k * &q_k));
+			if k.bit(i as usize) {
+				// U_j+1 = (U_j + V_j) / 2, V_j+1 = (D * U_j + V_j) / 2
+				let next_u = half_mod(add_mod(u.clone(), &v, self), self);
+				let next_v = half_mod(add_mod(divider.rem(&(&d_mod * &u)), &v, self), self);
+				u = next_u;
+				v = next_v;
+				q_k = divider.rem(&(&q_k * &q_mod));
+			}
+		}
+
+		if u.is_zero() || v.is_zero() {
+			return true;
+		}
+		// V_(k * 2^r) for r < s
+		for _ in 1..s {
+			v = sub_mod(
+				divider.rem(&(&v * &v)),
+				&add_mod(q_k.clone(), &q_k, self),
+				self,
+			);
+			if v.is_zero() {
+				return true;
+			}
+			q_k = divider.rem(&(&q_k * &q_k));
+		}
+		false
+	}
+}
+
+/// base^exp mod the divisor, by binary exponentiation
+fn pow_mod(base: &BigUInt, exp: &BigUInt, divider: &Divider) -> BigUInt {
+	let mut res = BigUInt::ONE;
+	for i in (0..=exp.ilog2()).rev() {
+		res = divider.rem(&(&res * &res));
+		if exp.bit(i as usize) {
+			res = divider.rem(&(&res * base));
+		}
+	}
+	res
+}
+
+/// x mod n in [0, n), for |x| < n
+fn to_residue(x: i64, n: &BigUInt) -> BigUInt {
+	let abs = BigUInt::from(x.unsigned_abs());
+	if x < 0 { n.clone() - &abs } else { abs }
+}
+
+/// a + b mod n, for a, b < n
+fn add_mod(a: BigUInt, b: &BigUInt, n: &BigUInt) -> BigUInt {
+	let res = a + b;
+	if res >= *n { res - n } else { res }
+}
+
+/// a - b mod n, for a, b < n
+fn sub_mod(a: BigUInt, b: &BigUInt, n: &BigUInt) -> BigUInt {
+	if a >= *b { a - b } else { a + n - b }
+}
+
+/// a / 2 mod odd n, for a < n
+fn half_mod(a: BigUInt, n: &BigUInt) -> BigUInt {
+	if a.bit(0) { (a + n) >> 1u32 } else { a >> 1u32 }
+}
+
+#[cfg(test)]
+mod tests {
+	use alloc::vec;
+	use alloc::vec::Vec;
+
+	use super::*;
+
+	fn sieve(limit: usize) -> Vec<bool> {
+		let mut is_prime = vec![true; limit];
+		is_prime[0] = false;
+		is_prime[1] = false;
+		for i in 2..limit {
+			if is_prime[i] {
+				for j in (i * i..limit).step_by(i) {
+					is_prime[j] = false;
+				}
+			}
+		}
+		is_prime
+	}
+
+	#[test]
+	fn test_is_prime_bpsw_small() {
+		for (n, &expected) in sieve(10000).iter().enumerate() {
+			assert_eq!(BigUInt::from(n).is_prime_bpsw(), expected, "{n}");
+		}
+	}
+
+	#[test]
+	fn test_is_prime_bpsw_large() {
+		let mersenne = |p: u32| (BigUInt::ONE << p) - 1u64;
+		let primes = [
+			BigUInt::from(u64::MAX - 58),
+			BigUInt::from(1_000_000_007u64),
+			mersenne(61),
+			mersenne(89),
+			mersenne(127),
+			mersenne(521),
+			(BigUInt::ONE << 255u32) - 19u64,
+		];
+		for p in &primes {
+			assert!(p.is_prime_bpsw(), "{p}");
+		}
+
+		let composites = [
+			// Carmichael numbers
+			BigUInt::from(561u64),
+			BigUInt::from(825265u64),
+			BigUInt::from(41041u64),
+			// Strong pseudoprime to bases 2, 3, 5 and 7
+			BigUInt::from(3215031751u64),
+			mersenne(67),
+			mersenne(128),
+			&primes[2] * &primes[3],
+			&primes[4] * &primes[4],
+			&primes[0] * 3u64,
+		];
+		for n in &composites {
+			assert!(!n.is_prime_bpsw(), "{n}");
+		}
+	}
+
+	#[test]
+	fn test_bpsw_halves() {
+		// Composites that pass one of the two tests must fail the other
+		for n in [2047u64, 3277, 4033, 4681, 8321, 3215031751] {
+			let n = BigUInt::from(n);
+			let divider = Divider::new(n.clone());
+			assert!(n.is_strong_probable_prime_base2(&divider), "{n}");
+			assert!(!n.is_strong_lucas_probable_prime(&divider), "{n}");
+		}
+		for n in [5459u64, 5777, 10877, 16109, 18971] {
+			let n = BigUInt::from(n);
+			let divider = Divider::new(n.clone());
+			assert!(n.is_strong_lucas_probable_prime(&divider), "{n}");
+			assert!(!n.is_strong_probable_prime_base2(&divider), "{n}");
+		}
+	}
+
+	#[test]
+	fn test_euler_phi() {
+		let phi = |n: u64| BigUInt::from(n).euler_phi();
+		assert_eq!(phi(0), BigUInt::ZERO);
+		assert_eq!(phi(1), BigUInt::ONE);
+		assert_eq!(phi(12), BigUInt::from(4u64));
+		assert_eq!(phi(97), BigUInt::from(96u64));
+		assert_eq!(phi(1 << 40), BigUInt::from(1u64 << 39));
+
+		// Count of coprimes
+		for n in 1..300u64 {
+			let n = BigUInt::from(n);
+			let mut count = 0u64;
+			let mut k = BigUInt::ONE;
+			while k <= n {
+				if k.is_coprime(&n) {
+					count += 1;
+				}
+				k += 1u64;
+			}
+			assert_eq!(n.euler_phi(), BigUInt::from(count), "{n}");
+		}
+
+		// Large primes and products with a single large factor
+		let p = (BigUInt::ONE << 127u32) - 1u64;
+		assert_eq!(p.euler_phi(), p.clone() - 1u64);
+		let q = BigUInt::from(u64::MAX - 58);
+		assert_eq!(q.euler_phi(), q.clone() - 1u64);
+		let n = &(&p * 10007u64) * 9u64;
+		let expected = &(&(p.clone() - 1u64) * 10006u64) * 6u64;
+		assert_eq!(n.euler_phi(), expected);
+		let n = &q * 4u64;
+		assert_eq!(n.euler_phi(), &(q - 1u64) * 2u64);
+	}
+}
@@ -0,0 +1,74 @@
+use alloc::vec::Vec;
+
+use crate::biguint::BigUInt;
+
+/// Lazily computed table of radix^(2^k)
+/// Used by divide-and-conquer base conversions, both for parsing and printing,
+/// so that the same powers are not recomputed at every level of recursion.
+pub(crate) struct RadixPowers {
+	radix: u32,
+	// powers[k] = radix^(2^k)
+	powers: Vec<BigUInt>,
+}
+
+impl RadixPowers {
+	pub(crate) fn new(radix: u32) -> Self {
+		assert!((2..=36).contains(&radix), "radix must be between 2 and 36");
+		Self {
+			radix,
+			powers: Vec::new(),
+		}
+	}
+
+	pub(crate) fn radix(&self) -> u32 {
+		self.radix
+	}
+
+	/// Number of powers computed so far
+	#[cfg(test)]
+	pub(crate) fn cached(&self) -> usize {
+		self.powers.len()
+	}
+
+	/// Returns radix^(2^k), computing any missing powers
+	pub(crate) fn get(&mut self, k: usize) -> &BigUInt {
+		self.get_mut(k)
+	}
+
+	/// Returns radix^(2^k), computing any missing powers
+	/// The returned value is only mutable so that it can be passed as a divisor,
+	/// callers must leave it unchanged.
+	pub(crate) fn get_mut(&mut self, k: usize) -> &mut BigUInt {
+		while self.powers.len() <= k {
+			let next = match self.powers.last() {
+				None => BigUInt::from(self.radix),
+				Some(prev) => prev * prev,
+			};
+			self.powers.push(next);
+		}
+		&mut self.powers[k]
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_radix_powers() {
+		let mut powers = RadixPowers::new(10);
+		assert_eq!(powers.cached(), 0);
+
+		assert_eq!(*powers.get(0), BigUInt::from(10u32));
+		assert_eq!(powers.cached(), 1);
+
+		assert_eq!(*powers.get(3), BigUInt::from(100_000_000u32));
+		assert_eq!(powers.cached(), 4);
+
+		assert_eq!(*powers.get(1), BigUInt::from(100u32));
+		assert_eq!(powers.cached(), 4);
+
+		assert_eq!(*powers.get(6), BigUInt::from(10u32).pow(64));
+		assert_eq!(powers.cached(), 7);
+	}
+}
@@ -1,14 +1,15 @@
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt::{Binary, Debug, Display, Formatter, LowerHex, Octal, UpperHex};
+use core::iter;
 use core::str::FromStr;
 
-use crate::SetVal;
 use crate::biguint::BigUInt;
 use crate::biguint::div::DivRem;
 use crate::biguint::mul::MulTo;
+use crate::biguint::radix_powers::RadixPowers;
 use crate::error::ParseIntError;
-use crate::util::{digit_to_ascii, parse_ascii_digit};
+use crate::util::{carrying_mul, digit_to_ascii, parse_ascii_digit};
 
 impl FromStr for BigUInt {
 	type Err = ParseIntError;
@@ -63,7 +64,14 @@ impl BigUInt {
 		Self::from_ascii_radix(src, 10)
 	}
 
-	pub fn from_ascii_radix(mut src: &[u8], radix: u32) -> Result<Self, ParseIntError> {
+	/// Parses an optional + followed by digits. Leading and trailing ASCII whitespace is ignored,
+	/// whitespace between the digits is not.
+	pub fn from_ascii_radix(src: &[u8], radix: u32) -> Result<Self, ParseIntError> {
+		Self::parse_untrimmed(src.trim_ascii(), radix)
+	}
+
+	/// Same as from_ascii_radix, without trimming whitespace, for parsing parts of a larger number
+	pub(crate) fn parse_untrimmed(mut src: &[u8], radix: u32) -> Result<Self, ParseIntError> {
 		if src.is_empty() {
 			return Err(ParseIntError::Empty);
 		}
@@ -79,31 +87,78 @@ impl BigUInt {
 	}
 
 	pub(crate) fn parse_helper(src: &[u8], radix: u32) -> Result<Self, ParseIntError> {
-		assert!((2..=36).contains(&radix), "radix must be between 2 and 36");
-
 		if src.is_empty() {
 			return Err(ParseIntError::Empty);
 		}
 
+		validate_digits(src, radix)?;
+
+		let mut powers = RadixPowers::new(radix);
+		Ok(Self::from_digits_dc(src, &mut powers))
+	}
+
+	/// Parses already validated digits
+	/// Splits src in two, parses both halves and combines them as hi * radix^len(lo) + lo,
+	/// where len(lo) is a power of 2 so that radix^len(lo) can be taken from the cache
+	fn from_digits_dc(src: &[u8], powers: &mut RadixPowers) -> Self {
+		if src.len() <= PARSE_DC_THRESHOLD {
+			let mut res = Self::ZERO;
+			for &c in src {
+				res.push_digit(parse_ascii_digit(c).unwrap(), powers.radix());
+			}
+			return res;
+		}
+
+		// Largest k s.t. 2^k < len(src)
+		let k = (src.len() - 1).ilog2() as usize;
+		let (hi, lo) = src.split_at(src.len() - (1 << k));
+
+		let hi = Self::from_digits_dc(hi, powers);
+		let lo = Self::from_digits_dc(lo, powers);
+
 		let mut res = Self::ZERO;
+		res.mul_to(&hi, powers.get(k));
+		res += &lo;
+		res
+	}
+
+	/// Appends the digits of src to the end of self, i.e. self = self * radix^len(src) + src
+	/// Signs are not allowed. On error self is left unchanged.
+	pub fn extend_from_str_radix(&mut self, src: &str, radix: u32) -> Result<(), ParseIntError> {
+		self.extend_from_ascii_radix(src.as_bytes(), radix)
+	}
 
-		let mut power_of_radix = BigUInt::from(1u64);
+	/// Appends the digits of src to the end of self, i.e. self = self * radix^len(src) + src
+	/// Signs are not allowed. On error self is left unchanged.
+	pub fn extend_from_ascii_radix(&mut self, src: &[u8], radix: u32) -> Result<(), ParseIntError> {
+		assert!((2..=36).contains(&radix), "radix must be between 2 and 36");
 
-		// To reduce allocations
-		let mut tmp = Self::ZERO;
+		// Validate everything first so that self stays untouched on error
+		validate_digits(src, radix)?;
 
-		for &c in src.iter().rev() {
-			let d = parse_ascii_digit(c).ok_or(ParseIntError::InvalidDigit)?;
-			if d >= radix as u8 {
-				return Err(ParseIntError::InvalidDigit);
-			}
-			let d = d.into();
-			tmp.mul_to(&d, &power_of_radix);
-			res += &tmp;
-			tmp.mul_to(&power_of_radix, &radix.into());
-			power_of_radix.set_val(&tmp);
+		for &c in src {
+			self.push_digit(parse_ascii_digit(c).unwrap(), radix);
+		}
+		Ok(())
+	}
+
+	/// Appends a single digit to the end of self, i.e. self = self * radix + digit
+	/// Works in place, without any temporary allocations.
+	pub fn push_digit(&mut self, digit: u8, radix: u32) {
+		assert!((2..=36).contains(&radix), "radix must be between 2 and 36");
+		assert!((digit as u32) < radix, "digit is out of range for radix");
+
+		let mut carry = digit as u64;
+		for x in self.data.iter_mut() {
+			let (lo, hi) = carrying_mul(*x, radix as u64);
+			let (sum, overflow) = lo.overflowing_add(carry);
+			*x = sum;
+			// Can't overflow, hi < radix
+			carry = hi + overflow as u64;
+		}
+		if carry != 0 {
+			self.data.push(carry);
 		}
-		Ok(res)
 	}
 
 	pub fn to_string_radix(&self, radix: u32, uppercase: bool) -> String {
@@ -113,29 +168,111 @@ impl BigUInt {
 			return "0".to_string();
 		}
 
-		let mut n = self.clone();
+		if radix.is_power_of_two() {
+			return self.to_string_pow2(radix.trailing_zeros(), uppercase);
+		}
+
+		let mut res = String::new();
+		let mut powers = RadixPowers::new(radix);
+		self.clone()
+			.write_digits_dc(0, uppercase, &mut powers, &mut res);
+		res
+	}
+
+	/// Writes the digits of self into out, left-padded with zeros to at least min_len digits
+	/// Splits self as q * radix^(2^k) + r, where radix^(2^k) is taken from the cache,
+	/// and writes both parts, padding r to exactly 2^k digits
+	fn write_digits_dc(
+		mut self,
+		min_len: usize,
+		uppercase: bool,
+		powers: &mut RadixPowers,
+		out: &mut String,
+	) {
+		if self.len() <= PRINT_DC_THRESHOLD {
+			let digits = self.to_digits_naive(powers.radix(), uppercase);
+			out.extend(iter::repeat_n('0', min_len.saturating_sub(digits.len())));
+			out.extend(digits.iter().rev());
+			return;
+		}
+
+		// Largest k s.t. radix^(2^k) <= self
+		let mut k = 0usize;
+		while *powers.get(k + 1) <= self {
+			k += 1;
+		}
+
+		let mut q = BigUInt::ZERO;
 		let mut r = BigUInt::ZERO;
-		let mut tmp = BigUInt::ZERO;
-		let mut digits = Vec::new();
-		let mut radix_big = BigUInt::from(radix);
+		(&mut self).div_rem_to(powers.get_mut(k), &mut q, &mut r);
+
+		q.write_digits_dc(min_len.saturating_sub(1 << k), uppercase, powers, out);
+		r.write_digits_dc(1 << k, uppercase, powers, out);
+	}
+
+	/// Formats self in radix 2^bits by reading the digits directly as groups of bits,
+	/// without any division. Self must not be zero.
+	fn to_string_pow2(&self, bits: u32, uppercase: bool) -> String {
+		let bit_len = self.ilog2() + 1;
+		let len = bit_len.div_ceil(bits as u64);
+		(0..len)
+			.rev()
+			.map(|i| digit_to_ascii(self.bit_range(i * bits as u64, bits) as u8, uppercase))
+			.collect()
+	}
 
+	/// Returns the digits of self by repeated division by radix, least significant first
+	/// Returns no digits for zero
+	fn to_digits_naive(&self, radix: u32, uppercase: bool) -> Vec<char> {
+		// Divide by the largest power of radix that fits in a digit,
+		// then split each remainder into chunk_len digits
+		let radix = radix as u64;
+		let mut chunk = radix;
+		let mut chunk_len = 1;
+		while let Some(next) = chunk.checked_mul(radix) {
+			chunk = next;
+			chunk_len += 1;
+		}
+
+		let mut n = self.clone();
+		let mut digits = Vec::new();
 		while !n.is_zero() {
-			n.div_rem_to(&mut radix_big, &mut tmp, &mut r);
-			n.set_val(&tmp);
-			let digit = TryInto::<u8>::try_into(&r).unwrap();
-			digits.push(digit_to_ascii(digit, uppercase));
+			let mut r = n.div_rem_u64_assign(chunk);
+			for _ in 0..chunk_len {
+				if n.is_zero() && r == 0 {
+					break;
+				}
+				digits.push(digit_to_ascii((r % radix) as u8, uppercase));
+				r /= radix;
+			}
 		}
 
-		digits.iter().rev().collect()
+		digits
+	}
+}
+
+/// Numbers with at most this many digits are parsed one digit at a time
+const PARSE_DC_THRESHOLD: usize = 256;
+/// Numbers with at most this many limbs are printed by repeated division by radix
+const PRINT_DC_THRESHOLD: usize = 8;
+
+fn validate_digits(src: &[u8], radix: u32) -> Result<(), ParseIntError> {
+	for &c in src {
+		let d = parse_ascii_digit(c).ok_or(ParseIntError::InvalidDigit)?;
+		if d >= radix as u8 {
+			return Err(ParseIntError::InvalidDigit);
+		}
 	}
+	Ok(())
 }
 
 #[cfg(test)]
 mod tests {
 	use alloc::string::ToString;
-	use core::assert_matches::assert_matches;
+	use core::assert_matches;
 
 	use super::*;
+	use crate::util::xorshift64;
 
 	#[test]
 	fn test_from_str_radix() {
@@ -160,6 +297,118 @@ mod tests {
 		assert_matches!(BigUInt::from_str(""), Err(_));
 	}
 
+	#[test]
+	fn test_from_str_radix_whitespace() {
+		assert_eq!(BigUInt::from_str(" 42 ").unwrap(), BigUInt::from(42u32));
+		assert_eq!(
+			BigUInt::from_str("\t+42\r\n").unwrap(),
+			BigUInt::from(42u32)
+		);
+		assert_eq!(
+			BigUInt::from_str_radix("  ff\n", 16).unwrap(),
+			BigUInt::from(255u32)
+		);
+		assert_eq!(BigUInt::from_str("   "), Err(ParseIntError::Empty));
+		assert_eq!(BigUInt::from_str(" - 42"), Err(ParseIntError::Negative));
+		assert_eq!(BigUInt::from_str("4 2"), Err(ParseIntError::InvalidDigit));
+		assert_eq!(BigUInt::from_str("+ 42"), Err(ParseIntError::InvalidDigit));
+		assert_eq!(
+			BigUInt::from_str("42\u{a0}"),
+			Err(ParseIntError::InvalidDigit)
+		);
+	}
+
+	#[test]
+	fn test_push_digit() {
+		let digits = "98765432109876543210987654321098765432109876543210";
+		let mut acc = BigUInt::ZERO;
+		for c in digits.bytes() {
+			acc.push_digit(c - b'0', 10);
+		}
+		assert_eq!(acc, BigUInt::from_str(digits).unwrap());
+
+		let mut acc = BigUInt::ZERO;
+		for c in "ffffffffffffffffffffffffffffffff".bytes() {
+			acc.push_digit(parse_ascii_digit(c).unwrap(), 16);
+		}
+		assert_eq!(acc, BigUInt::from(u128::MAX));
+
+		let mut acc = BigUInt::ZERO;
+		acc.push_digit(0, 10);
+		assert!(acc.is_zero());
+	}
+
+	#[test]
+	fn test_extend_from_str_radix() {
+		let mut acc = BigUInt::ZERO;
+		acc.extend_from_str_radix("123456789", 10).unwrap();
+		acc.extend_from_str_radix("", 10).unwrap();
+		acc.extend_from_str_radix("987654321987654321", 10).unwrap();
+		assert_eq!(
+			acc,
+			BigUInt::from_str("123456789987654321987654321").unwrap()
+		);
+
+		let before = acc.clone();
+		assert_eq!(
+			acc.extend_from_str_radix("12a", 10),
+			Err(ParseIntError::InvalidDigit)
+		);
+		assert_eq!(acc, before);
+	}
+
+	#[test]
+	fn test_dc_conversions_match_naive() {
+		for radix in [2u32, 7, 10, 16, 36] {
+			// Long enough to go through several levels of recursion,
+			// with runs of zeros to exercise padding
+			let mut src = String::new();
+			for i in 0..3000u32 {
+				let d = if i % 97 < 40 { 0 } else { (i * 31 + 7) % radix };
+				src.push(digit_to_ascii(d as u8, false));
+			}
+			src.insert(0, '1');
+
+			let dc = BigUInt::from_str_radix(&src, radix).unwrap();
+			let mut naive = BigUInt::ZERO;
+			for c in src.bytes() {
+				naive.push_digit(parse_ascii_digit(c).unwrap(), radix);
+			}
+			assert_eq!(dc, naive);
+
+			let printed = dc.to_string_radix(radix, false);
+			let naive_printed: String = dc.to_digits_naive(radix, false).iter().rev().collect();
+			assert_eq!(printed, naive_printed);
+			assert_eq!(printed, src);
+		}
+	}
+
+	#[test]
+	fn test_to_string_pow2_matches_naive() {
+		let mut next = xorshift64(0x2545F4914F6CDD1D);
+
+		for len in [1usize, 2, 3, 9, 20] {
+			let mut x = BigUInt::from_vec_le((0..len).map(|_| next()).collect());
+			// Vary the length of the top limb
+			x >>= (next() % 64) as u32;
+			for radix in [2u32, 4, 8, 16, 32] {
+				let naive: String = x.to_digits_naive(radix, true).iter().rev().collect();
+				assert_eq!(x.to_string_radix(radix, true), naive, "radix {radix}");
+			}
+		}
+
+		assert_eq!(BigUInt::ZERO.to_string_radix(16, false), "0");
+		assert_eq!(BigUInt::ONE.to_string_radix(8, false), "1");
+		assert_eq!(
+			BigUInt::from(u128::MAX).to_string_radix(8, false),
+			alloc::format!("{:o}", u128::MAX)
+		);
+		assert_eq!(
+			(BigUInt::ONE << 64u32).to_string_radix(16, true),
+			"10000000000000000"
+		);
+	}
+
 	#[test]
 	fn test_to_string_radix() {
 		assert_eq!(
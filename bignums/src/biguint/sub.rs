@@ -46,6 +46,25 @@ macro_rules! impl_checked_sub_i {
 impl_checked_sub_i! { i8, i16, i32, i64, i128, isize }
 
 impl BigUInt {
+	/// Calculates self - rhs, saves result into out, reusing its buffer
+	/// Returns false and leaves garbage in out on overflow.
+	pub fn checked_sub_into(&self, rhs: &Self, out: &mut BigUInt) -> bool {
+		if self.len() < rhs.len() {
+			return false;
+		}
+		out.data.set_len_fill_zero(self.len());
+		let mut borrow = 0u64;
+		for i in 0..self.len() {
+			let b = rhs.data.get_or_default(i);
+			let (diff1, borrow1) = self.data[i].overflowing_sub(b);
+			let (diff2, borrow2) = diff1.overflowing_sub(borrow);
+			out.data[i] = diff2;
+			borrow = borrow1 as u64 + borrow2 as u64;
+		}
+		out.truncate_leading_zeros();
+		borrow == 0
+	}
+
 	/// Calculates self - lhs, saves result into self
 	/// Returns false and leaves garbage in self on overflow.
 	pub(crate) fn checked_sub_assign(&mut self, rhs: &Self) -> bool {
@@ -200,7 +219,10 @@ impl_sub_i! { i8, i16, i32, i64, i128, isize }
 
 #[cfg(test)]
 mod tests {
+	use alloc::vec::Vec;
+
 	use super::*;
+	use crate::util::xorshift64;
 
 	#[test]
 	fn test_sub() {
@@ -213,6 +235,46 @@ mod tests {
 		assert_eq!(num2, cmp2);
 	}
 
+	#[test]
+	fn test_checked_sub_into() {
+		let mut next = xorshift64(0x2545F4914F6CDD1D);
+		let mut values: Vec<BigUInt> = [1, 2, 3, 8, 9, 40]
+			.iter()
+			.map(|&len| BigUInt::from_vec_le((0..len).map(|_| next()).collect()))
+			.collect();
+		values.push(BigUInt::ZERO);
+		values.push(BigUInt::from(u128::MAX));
+		values.push(BigUInt::ONE << 640u32);
+
+		let mut out = BigUInt::ZERO;
+		for a in &values {
+			for b in &values {
+				let expected = a.clone().checked_sub(b);
+				let succ = a.checked_sub_into(b, &mut out);
+				assert_eq!(succ, expected.is_some());
+				if let Some(expected) = expected {
+					assert_eq!(out, expected);
+				}
+			}
+			// Equal operands, the result has no digits
+			assert!(a.checked_sub_into(a, &mut out));
+			assert!(out.is_zero());
+		}
+
+		// out's heap buffer is reused once it is large enough
+		let a = BigUInt::ONE << 4000u32;
+		let b = BigUInt::from(12345u32);
+		let mut out = BigUInt::ZERO;
+		assert!(a.checked_sub_into(&b, &mut out));
+		let (ptr, capacity) = (out.data.as_ptr(), out.data.capacity());
+		for c in &values {
+			assert!(a.checked_sub_into(c, &mut out));
+			assert_eq!(out, a.clone() - c);
+			assert_eq!(out.data.as_ptr(), ptr);
+			assert_eq!(out.data.capacity(), capacity);
+		}
+	}
+
 	#[test]
 	#[should_panic]
 	fn test_sub2() {
@@ -0,0 +1,50 @@
+use ::zeroize::Zeroize;
+
+use crate::biguint::BigUInt;
+
+/// Overwrites the whole limb buffer, including spare capacity left over from
+/// earlier, larger values, and leaves self equal to zero.
+/// Call this before dropping a BigUInt that held a secret.
+impl Zeroize for BigUInt {
+	fn zeroize(&mut self) {
+		// Within capacity, so this doesn't reallocate
+		self.data.resize(self.data.capacity(), 0);
+		self.data.as_mut_slice().zeroize();
+		self.data.clear();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// All limbs of the buffer, including the ones past the length
+	fn whole_buffer(x: &mut BigUInt) -> &[u64] {
+		let cap = x.data.capacity();
+		// The buffer was fully initialized by zeroize
+		unsafe { x.data.set_len(cap) };
+		x.data.as_slice()
+	}
+
+	#[test]
+	fn test_zeroize() {
+		// Spilled to the heap, and shrunk so that secret limbs lie past the length
+		let mut x = (BigUInt::ONE << 1000u32) - &BigUInt::ONE;
+		x >>= 600u32;
+		assert!(x.capacity() > x.len());
+		let ptr = x.data.as_ptr();
+		let cap = x.capacity();
+
+		x.zeroize();
+		assert_eq!(x, BigUInt::ZERO);
+		assert_eq!(x.data.as_ptr(), ptr);
+		assert_eq!(x.capacity(), cap);
+		assert!(whole_buffer(&mut x).iter().all(|&limb| limb == 0));
+
+		// Inline storage
+		let mut x = BigUInt::from(u128::MAX);
+		x.zeroize();
+		assert_eq!(x, BigUInt::ZERO);
+		assert!(whole_buffer(&mut x).iter().all(|&limb| limb == 0));
+	}
+}
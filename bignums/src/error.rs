@@ -26,6 +26,18 @@ impl ParseIntError {
 	}
 }
 
+impl From<ParseIntError> for ParseRationalError {
+	fn from(e: ParseIntError) -> Self {
+		e.to_rational_error()
+	}
+}
+
+impl From<ParseIntError> for ParseFloatError {
+	fn from(e: ParseIntError) -> Self {
+		e.to_float_error()
+	}
+}
+
 impl Display for ParseIntError {
 	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
 		write!(
@@ -109,6 +121,30 @@ impl Display for TryFromFloatError {
 	}
 }
 
+impl Error for TryFromFloatError {}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum TryFromBigFloatError {
+	NotInteger,
+	Negative,
+}
+
+impl Display for TryFromBigFloatError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		write!(
+			f,
+			"{}",
+			match self {
+				TryFromBigFloatError::NotInteger =>
+					"can't convert a non-integer value to an integer type",
+				TryFromBigFloatError::Negative => "can't construct BigUInt from a negative value",
+			}
+		)
+	}
+}
+
+impl Error for TryFromBigFloatError {}
+
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
 pub enum ParseFloatError {
 	Empty,
@@ -127,3 +163,225 @@ impl Display for ParseFloatError {
 		)
 	}
 }
+
+impl Error for ParseFloatError {}
+
+#[derive(Default, Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ZeroStepError;
+
+impl Display for ZeroStepError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		write!(f, "range step cannot be zero")
+	}
+}
+
+impl Error for ZeroStepError {}
+
+#[derive(Default, Debug, Eq, PartialEq, Clone, Copy)]
+pub struct DivByZeroError;
+
+impl Display for DivByZeroError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		write!(f, "attempt to divide by zero")
+	}
+}
+
+impl Error for DivByZeroError {}
+
+#[derive(Default, Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ZeroDenominatorError;
+
+impl Display for ZeroDenominatorError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		write!(f, "denominator must not be zero")
+	}
+}
+
+impl Error for ZeroDenominatorError {}
+
+#[derive(Default, Debug, Eq, PartialEq, Clone, Copy)]
+pub struct DomainError;
+
+impl Display for DomainError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		write!(f, "argument is outside the domain of the function")
+	}
+}
+
+impl Error for DomainError {}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum BigFloatError {
+	Overflow,
+}
+
+impl Display for BigFloatError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		write!(
+			f,
+			"{}",
+			match self {
+				BigFloatError::Overflow => "result is too large",
+			}
+		)
+	}
+}
+
+impl Error for BigFloatError {}
+
+/// Any of the errors of this crate, so that `?` composes across modules.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum ArithError {
+	ParseInt(ParseIntError),
+	ParseRational(ParseRationalError),
+	ParseFloat(ParseFloatError),
+	TryFromInt(TryFromIntError),
+	TryIntoInt(TryIntoIntError),
+	TryFromFloat(TryFromFloatError),
+	TryFromBigFloat(TryFromBigFloatError),
+	ZeroStep(ZeroStepError),
+	DivByZero(DivByZeroError),
+	ZeroDenominator(ZeroDenominatorError),
+	Domain(DomainError),
+	BigFloat(BigFloatError),
+}
+
+impl ArithError {
+	fn inner(&self) -> &(dyn Error + 'static) {
+		match self {
+			ArithError::ParseInt(e) => e,
+			ArithError::ParseRational(e) => e,
+			ArithError::ParseFloat(e) => e,
+			ArithError::TryFromInt(e) => e,
+			ArithError::TryIntoInt(e) => e,
+			ArithError::TryFromFloat(e) => e,
+			ArithError::TryFromBigFloat(e) => e,
+			ArithError::ZeroStep(e) => e,
+			ArithError::DivByZero(e) => e,
+			ArithError::ZeroDenominator(e) => e,
+			ArithError::Domain(e) => e,
+			ArithError::BigFloat(e) => e,
+		}
+	}
+}
+
+impl Display for ArithError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		Display::fmt(self.inner(), f)
+	}
+}
+
+impl Error for ArithError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		Some(self.inner())
+	}
+}
+
+macro_rules! impl_from_error {
+	($($variant:ident($t:ty)),*) => {$(
+		impl From<$t> for ArithError {
+			fn from(e: $t) -> Self {
+				ArithError::$variant(e)
+			}
+		}
+	)*};
+}
+
+impl_from_error! {
+	ParseInt(ParseIntError),
+	ParseRational(ParseRationalError),
+	ParseFloat(ParseFloatError),
+	TryFromInt(TryFromIntError),
+	TryIntoInt(TryIntoIntError),
+	TryFromFloat(TryFromFloatError),
+	TryFromBigFloat(TryFromBigFloatError),
+	ZeroStep(ZeroStepError),
+	DivByZero(DivByZeroError),
+	ZeroDenominator(ZeroDenominatorError),
+	Domain(DomainError),
+	BigFloat(BigFloatError)
+}
+
+#[cfg(test)]
+mod tests {
+	use alloc::string::ToString;
+
+	use super::*;
+	use crate::bigfloat::BigFloat;
+	use crate::bigint::BigInt;
+	use crate::biguint::BigUInt;
+
+	#[test]
+	fn test_display() {
+		let errors: [ArithError; 19] = [
+			ParseIntError::Empty.into(),
+			ParseIntError::InvalidDigit.into(),
+			ParseIntError::Negative.into(),
+			ParseRationalError::Empty.into(),
+			ParseRationalError::InvalidDigit.into(),
+			ParseRationalError::DenominatorZero.into(),
+			ParseFloatError::Empty.into(),
+			ParseFloatError::InvalidDigit.into(),
+			TryFromIntError.into(),
+			TryIntoIntError.into(),
+			TryFromFloatError::NaN.into(),
+			TryFromFloatError::Infinite.into(),
+			TryFromBigFloatError::NotInteger.into(),
+			TryFromBigFloatError::Negative.into(),
+			ZeroStepError.into(),
+			DivByZeroError.into(),
+			ZeroDenominatorError.into(),
+			DomainError.into(),
+			BigFloatError::Overflow.into(),
+		];
+		for e in errors {
+			let msg = e.to_string();
+			assert!(!msg.is_empty());
+			let source = e.source().unwrap();
+			assert_eq!(source.to_string(), msg);
+			assert!(source.source().is_none());
+		}
+	}
+
+	#[test]
+	fn test_from() {
+		assert_eq!(
+			ParseRationalError::from(ParseIntError::InvalidDigit),
+			ParseRationalError::InvalidDigit
+		);
+		assert_eq!(
+			ParseFloatError::from(ParseIntError::Negative),
+			ParseFloatError::InvalidDigit
+		);
+
+		fn parse_and_convert(src: &str) -> Result<u64, ArithError> {
+			let x = BigFloat::from_str_radix(src, 10)?;
+			let n = BigUInt::try_from(&x)?;
+			let (q, _) = n.div_rem_checked(&BigUInt::from(2u64))?;
+			Ok(u64::try_from(&BigInt::from(q))?)
+		}
+		assert_eq!(parse_and_convert("84"), Ok(42));
+		assert_eq!(
+			parse_and_convert("2.5"),
+			Err(ArithError::TryFromBigFloat(
+				TryFromBigFloatError::NotInteger
+			))
+		);
+		assert_eq!(
+			parse_and_convert("100000000000000000000000"),
+			Err(ArithError::TryIntoInt(TryIntoIntError))
+		);
+		assert_eq!(
+			parse_and_convert("x"),
+			Err(ArithError::ParseFloat(ParseFloatError::InvalidDigit))
+		);
+		assert_eq!(
+			parse_and_convert("-4"),
+			Err(ArithError::TryFromBigFloat(TryFromBigFloatError::Negative))
+		);
+		assert_eq!(
+			parse_and_convert("1e100"),
+			Err(ArithError::ParseFloat(ParseFloatError::InvalidDigit))
+		);
+	}
+}
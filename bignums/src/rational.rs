@@ -2,14 +2,20 @@ mod add;
 mod cmp;
 mod convert;
 mod div;
+mod farey;
 mod mul;
+mod pow;
 mod round;
 mod set_val;
 mod str;
 mod sub;
 
+pub use farey::*;
+pub use str::FractionStyle;
+
 use crate::bigint::BigInt;
 use crate::biguint::BigUInt;
+use crate::error::ZeroDenominatorError;
 
 /// Dynamic, arbitrary-sized rational type
 pub struct Rational {
@@ -53,6 +59,22 @@ impl Rational {
 		&mut self.d
 	}
 
+	/// Replaces the denominator, which must not be zero. On error self is left unchanged.
+	/// Safe alternative to denominator_mut.
+	pub fn set_denominator(&mut self, d: BigUInt) -> Result<(), ZeroDenominatorError> {
+		if d.is_zero() {
+			return Err(ZeroDenominatorError);
+		}
+		self.d = d;
+		Ok(())
+	}
+
+	/// Returns self with the denominator replaced, which must not be zero
+	pub fn with_denominator(mut self, d: BigUInt) -> Result<Self, ZeroDenominatorError> {
+		self.set_denominator(d)?;
+		Ok(self)
+	}
+
 	pub fn inner(&self) -> (&BigInt, &BigUInt) {
 		(&self.n, &self.d)
 	}
@@ -148,4 +170,20 @@ mod tests {
 		r.reduce();
 		assert_eq!(r.to_string(), "-1/1");
 	}
+
+	#[test]
+	fn test_set_denominator() {
+		let mut r = Rational::new(BigInt::from(-3), BigUInt::from(4u64));
+		assert_eq!(r.set_denominator(BigUInt::ZERO), Err(ZeroDenominatorError));
+		assert_eq!(r.to_string(), "-3/4");
+
+		assert_eq!(r.set_denominator(BigUInt::from(9u64)), Ok(()));
+		assert_eq!(r.to_string(), "-3/9");
+		assert_eq!(r, Rational::new(BigInt::from(-1), BigUInt::from(3u64)));
+
+		let r = Rational::from(5).with_denominator(BigUInt::from(2u64));
+		assert_eq!(r.unwrap().to_string(), "5/2");
+		let r = Rational::from(5).with_denominator(BigUInt::ZERO);
+		assert!(r.is_err());
+	}
 }
@@ -1,9 +1,28 @@
+use core::mem;
 use core::ops::{Add, AddAssign};
 
 use crate::bigint::BigInt;
-use crate::biguint::BigUInt;
+use crate::biguint::{BigUInt, MulTo};
 use crate::rational::Rational;
 
+impl Rational {
+	/// Adds other to self in place, reusing the buffers of self where possible
+	pub fn add_assign_ref(&mut self, other: &Rational) {
+		if self.d == other.d {
+			self.n += &other.n;
+			return;
+		}
+
+		// a/b + c/d = (a*d + c*b) / (b*d)
+		let mut tmp = BigInt::ZERO;
+		tmp.mul_to_u(&self.n, &other.d);
+		self.n.mul_to_u(&other.n, &self.d);
+		self.n += &tmp;
+		tmp.magnitude.mul_to(&self.d, &other.d);
+		mem::swap(&mut self.d, &mut tmp.magnitude);
+	}
+}
+
 impl Add<&Rational> for &Rational {
 	type Output = Rational;
 
@@ -50,7 +69,13 @@ macro_rules! impl_add_assign {
 	)*}
 }
 
-impl_add_assign! { u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, &BigUInt, &BigInt, &Rational }
+impl_add_assign! { u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, &BigUInt, &BigInt }
+
+impl AddAssign<&Rational> for Rational {
+	fn add_assign(&mut self, rhs: &Rational) {
+		self.add_assign_ref(rhs);
+	}
+}
 
 #[cfg(test)]
 mod tests {
@@ -96,4 +121,37 @@ mod tests {
 		assert_eq!(*a.numerator(), BigInt::from(5));
 		assert_eq!(*a.denominator(), BigUInt::from(2u64));
 	}
+
+	#[test]
+	fn test_add_assign_ref() {
+		let values = [
+			Rational::new(BigInt::from(1), BigUInt::from(2u64)),
+			Rational::new(BigInt::from(-5), BigUInt::from(3u64)),
+			Rational::new(BigInt::from(7), BigUInt::from(3u64)),
+			Rational::new(BigInt::ZERO, BigUInt::from(9u64)),
+			Rational::new(BigInt::from(u64::MAX), BigUInt::from(u128::MAX)),
+		];
+
+		for a in &values {
+			for b in &values {
+				let mut in_place = a.clone();
+				in_place.add_assign_ref(b);
+				assert_eq!(in_place, a + b);
+			}
+		}
+
+		// Accumulate 1/1 + 1/2 + ... + 1/10
+		let mut sum = Rational::ZERO;
+		let mut expected = Rational::ZERO;
+		for i in 1..=10u64 {
+			let term = Rational::new(BigInt::ONE, BigUInt::from(i));
+			sum += &term;
+			expected = &expected + &term;
+		}
+		assert_eq!(sum, expected);
+		assert_eq!(
+			sum,
+			Rational::new(BigInt::from(7381), BigUInt::from(2520u64))
+		);
+	}
 }
@@ -1,7 +1,9 @@
+use core::cmp::Ordering;
+
 use crate::SetVal;
 use crate::bigfloat::BigFloat;
 use crate::bigint::BigInt;
-use crate::biguint::BigUInt;
+use crate::biguint::{BigUInt, DivRem};
 use crate::rational::Rational;
 
 impl From<BigUInt> for Rational {
@@ -16,6 +18,18 @@ impl From<BigInt> for Rational {
 	}
 }
 
+impl From<&BigUInt> for Rational {
+	fn from(value: &BigUInt) -> Self {
+		Self::from(value.clone())
+	}
+}
+
+impl From<&BigInt> for Rational {
+	fn from(value: &BigInt) -> Self {
+		Self::from(value.clone())
+	}
+}
+
 macro_rules! impl_from {
 	($($t:ty),*) => {
 		$(impl From<$t> for Rational {
@@ -30,11 +44,236 @@ macro_rules! impl_from {
 
 impl_from! { u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize }
 
+impl BigFloat {
+	/// Returns q rounded to the nearest multiple of 2^-prec, with ties to even.
+	/// Unlike Rational::to_float, which only bounds the error, the result is correctly rounded.
+	pub fn from_rational_round_to_nearest(q: &Rational, prec: i64) -> BigFloat {
+		// |q| * 2^prec = m + r / d, with exactly one of the two scaled by a power of 2
+		let mut n = q.n.magnitude.clone();
+		let mut d = q.d.clone();
+		if prec >= 0 {
+			n <<= prec as u64;
+		} else {
+			d <<= prec.unsigned_abs();
+		}
+		let (mut m, mut r) = (&mut n).div_rem(&mut d.clone());
+
+		r <<= 1u32;
+		match r.cmp(&d) {
+			Ordering::Greater => m += 1u32,
+			Ordering::Equal if m.bit(0) => m += 1u32,
+			_ => {}
+		}
+		let m = BigInt::from_sign_and_magnitude(q.is_negative(), m);
+		BigFloat::from_mantissa_exponent(m, -prec)
+	}
+}
+
 impl Rational {
+	/// Returns self with absolute error < 2^-prec.
+	/// See BigFloat::from_rational_round_to_nearest for a correctly rounded result.
 	pub fn to_float(&self, prec: i64) -> BigFloat {
+		if self.is_zero() {
+			return BigFloat::ZERO;
+		}
+
 		let n = BigFloat::from(self.n.clone());
 		let d = BigFloat::from(self.d.clone());
 
 		n.div(&d, prec)
 	}
+
+	// Mixed arithmetic with floats, which promotes self to a float precise enough for the result.
+	// All of them have absolute error < 2^-prec.
+
+	pub fn add_bigfloat(&self, f: &BigFloat, prec: i64) -> BigFloat {
+		self.to_float(prec + 2).add_with_precision(f, prec + 1)
+	}
+
+	/// Returns self - f
+	pub fn sub_bigfloat(&self, f: &BigFloat, prec: i64) -> BigFloat {
+		self.to_float(prec + 2).sub_with_precision(f, prec + 1)
+	}
+
+	pub fn mul_bigfloat(&self, f: &BigFloat, prec: i64) -> BigFloat {
+		if f.is_zero() {
+			return BigFloat::ZERO;
+		}
+		// The error of self gets scaled by |f| < 2^(ilog2(f) + 1)
+		let self_prec = prec + 2 + i64::max(f.ilog2() + 1, 0);
+		self.to_float(self_prec).mul_with_precision(f, prec + 1)
+	}
+
+	/// Returns self / f
+	/// # Panics
+	/// If f is zero
+	pub fn div_bigfloat(&self, f: &BigFloat, prec: i64) -> BigFloat {
+		assert!(!f.is_zero(), "division by zero");
+		// The error of self gets scaled by 1 / |f| <= 2^-ilog2(f)
+		let self_prec = prec + 2 - f.ilog2();
+		self.to_float(self_prec).div(f, prec + 1)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::util::assert_close;
+
+	#[test]
+	fn test_promote_to_bigfloat() {
+		let third = Rational::new(BigInt::ONE, BigUInt::from(3u64));
+		for prec in [0, 64, 200, 1000] {
+			let sqrt2 = BigFloat::from(2).sqrt(prec + 64);
+			let third_f = third.to_float(prec + 64);
+			assert_close(
+				&third.add_bigfloat(&BigFloat::from(2).sqrt(prec + 2), prec),
+				&(&third_f + &sqrt2),
+				prec,
+			);
+			assert_close(
+				&third.sub_bigfloat(&sqrt2, prec),
+				&(&third_f - &sqrt2),
+				prec,
+			);
+			assert_close(
+				&third.mul_bigfloat(&sqrt2, prec),
+				&third_f.mul_with_precision(&sqrt2, prec + 64),
+				prec,
+			);
+			assert_close(
+				&third.div_bigfloat(&sqrt2, prec),
+				&third_f.div(&sqrt2, prec + 64),
+				prec,
+			);
+		}
+
+		// Large and small floats need extra precision for the rational
+		let big = BigFloat::from_mantissa_exponent(BigInt::from(3), 500);
+		let x = third.mul_bigfloat(&big, 10);
+		assert_eq!(x, BigFloat::from_mantissa_exponent(BigInt::ONE, 500));
+		let small = BigFloat::from_mantissa_exponent(BigInt::ONE, -500);
+		let x = (-&third).div_bigfloat(&small, 10);
+		assert_close(
+			&x,
+			&-(third.mul_bigfloat(&BigFloat::from_mantissa_exponent(BigInt::ONE, 500), 64)),
+			10,
+		);
+		assert!(third.mul_bigfloat(&BigFloat::ZERO, 10).is_zero());
+
+		// Comparisons are exact
+		let third_f = third.to_float(100);
+		let eps = BigFloat::ONE >> 99u32;
+		assert!(&third_f - &eps < third && third < &third_f + &eps);
+		assert_ne!(third_f.partial_cmp(&third), Some(Ordering::Equal));
+		assert!(third < BigFloat::from(2).sqrt(10));
+		assert!(-&third > -&BigFloat::ONE);
+		assert!(BigFloat::ONE > third);
+		assert_eq!(
+			BigFloat::from(3).partial_cmp(&Rational::from(3)),
+			Some(Ordering::Equal)
+		);
+	}
+
+	#[test]
+	fn test_from_integers() {
+		let values = [
+			BigInt::ZERO,
+			BigInt::ONE,
+			BigInt::from(-7),
+			BigInt::from(u128::MAX),
+			-(BigInt::ONE << 300u32),
+		];
+		for n in values {
+			let q = Rational::from(n.clone());
+			assert_eq!(q, n);
+			assert!(q.denominator().is_one());
+			assert_eq!(n.to_rational(), q);
+			assert_eq!(q.is_negative(), n.is_negative());
+
+			let u = n.unsigned_abs();
+			let q = Rational::from(u.clone());
+			assert_eq!(q, u);
+			assert!(q.denominator().is_one());
+			assert_eq!(u.to_rational(), q);
+			assert_eq!(Rational::from(&u), q);
+		}
+
+		let q = Rational::from(BigInt::from(-5));
+		assert_eq!(q.numerator(), &BigInt::from(-5));
+		assert_eq!(q.denominator(), &BigUInt::ONE);
+		assert_eq!(q.to_string(), "-5/1");
+		assert_eq!(Rational::from(&BigInt::from(-5)), q);
+
+		let zero = Rational::from(BigInt::from_sign_and_magnitude(true, BigUInt::ZERO));
+		assert!(!zero.is_negative());
+		assert_eq!(zero, Rational::ZERO);
+
+		let q = Rational::from(BigUInt::from(20u32).factorial());
+		assert_eq!(q, Rational::from(2432902008176640000u64));
+	}
+
+	#[test]
+	fn test_from_rational_round_to_nearest_ties() {
+		let round = |n: i64, d: u64, prec: i64| {
+			let q = Rational::new(BigInt::from(n), BigUInt::from(d));
+			BigFloat::from_rational_round_to_nearest(&q, prec)
+		};
+		let float = |x: f64| BigFloat::try_from(x).unwrap();
+
+		// Halfway between multiples of 1/4, towards the even multiple
+		assert_eq!(round(3, 8, 2), float(0.5));
+		assert_eq!(round(5, 8, 2), float(0.5));
+		assert_eq!(round(7, 8, 2), float(1.0));
+		assert_eq!(round(1, 8, 2), BigFloat::ZERO);
+		assert_eq!(round(-3, 8, 2), float(-0.5));
+		assert_eq!(round(-1, 8, 2), BigFloat::ZERO);
+		// Integers, with ties at x.5
+		assert_eq!(round(5, 2, 0), BigFloat::from(2));
+		assert_eq!(round(7, 2, 0), BigFloat::from(4));
+		assert_eq!(round(-5, 2, 0), BigFloat::from(-2));
+		// Multiples of 4
+		assert_eq!(round(6, 1, -2), BigFloat::from(8));
+		assert_eq!(round(10, 1, -2), BigFloat::from(8));
+		assert_eq!(round(14, 1, -2), BigFloat::from(16));
+		assert_eq!(round(-2, 1, -2), BigFloat::ZERO);
+		// Not ties
+		assert_eq!(round(1, 3, 1), float(0.5));
+		assert_eq!(round(2, 3, 1), float(0.5));
+		assert_eq!(round(5, 6, 1), float(1.0));
+		assert_eq!(round(11, 1, -2), BigFloat::from(12));
+
+		// A tie far below the leading bit
+		let x = (BigInt::ONE << 200u32) + &BigInt::from(3);
+		let q = Rational::new(x, BigUInt::from(2u32));
+		let expected = BigFloat::from((BigInt::ONE << 199u32) + &BigInt::from(2));
+		assert_eq!(BigFloat::from_rational_round_to_nearest(&q, 0), expected);
+	}
+
+	#[test]
+	fn test_from_rational_round_to_nearest_exhaustive() {
+		let pow2 = |k: i64| {
+			let mut x = Rational::from(2);
+			x.pow_assign(k);
+			x
+		};
+		for n in -100..100i64 {
+			for d in 1..20u64 {
+				let q = Rational::new(BigInt::from(n), BigUInt::from(d));
+				for prec in -3..6i64 {
+					let res = BigFloat::from_rational_round_to_nearest(&q, prec);
+					// A multiple of 2^-prec, at most half of that away from q
+					let units = (&res.to_rational() * &pow2(prec)).round_to_int();
+					assert_eq!(res, BigFloat::from_mantissa_exponent(units.clone(), -prec));
+					let error = (&res.to_rational() - &q).abs();
+					let half_unit = pow2(-prec - 1);
+					match error.cmp(&half_unit) {
+						Ordering::Less => {}
+						Ordering::Equal => assert!(!units.inner().bit(0), "{q} at {prec}: {res}"),
+						Ordering::Greater => panic!("{q} at {prec}: {res}"),
+					}
+				}
+			}
+		}
+	}
 }
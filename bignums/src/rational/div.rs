@@ -204,6 +204,43 @@ mod tests {
 		assert_eq!(*result.denominator(), BigUInt::from(1u64));
 	}
 
+	#[test]
+	fn test_integer_div_rational_signs() {
+		let half = Rational::new(BigInt::from(1), BigUInt::from(2u64)); // 1/2
+		let neg_half = Rational::new(BigInt::from(-1), BigUInt::from(2u64)); // -1/2
+
+		assert_eq!(-6i32 / &neg_half, Rational::from(12));
+		assert_eq!(-6i32 / &half, Rational::from(-12));
+		assert_eq!(6i32 / &neg_half, Rational::from(-12));
+		assert_eq!(-6i64 / &neg_half, Rational::from(12));
+		assert_eq!(-6i128 / &half, Rational::from(-12));
+		assert_eq!(-6isize / &neg_half, Rational::from(12));
+
+		assert_eq!(&BigInt::from(-6) / &neg_half, Rational::from(12));
+		assert_eq!(&BigInt::from(-6) / &half, Rational::from(-12));
+		assert_eq!(&BigInt::from(6) / &neg_half, Rational::from(-12));
+		assert_eq!(6u32 / &neg_half, Rational::from(-12));
+		assert_eq!(&BigUInt::from(6u32) / &neg_half, Rational::from(-12));
+
+		// The denominator of the result is always positive, with and without reduction
+		let neg_two_thirds = Rational::new(BigInt::from(-4), BigUInt::from(6u64));
+		for result in [-6i8 / &neg_two_thirds, &BigInt::from(-6) / &neg_two_thirds] {
+			assert!(!result.is_negative());
+			assert_eq!(result, Rational::from(9));
+		}
+		assert_eq!(&Rational::from(-6) / &neg_two_thirds, Rational::from(9));
+		// black_box keeps clippy from flagging a division of a literal zero
+		let zero = core::hint::black_box(0i32);
+		assert_eq!(zero / &neg_half, Rational::ZERO);
+		assert!(!(zero / &neg_half).is_negative());
+	}
+
+	#[test]
+	#[should_panic(expected = "division by zero")]
+	fn test_integer_div_rational_by_zero() {
+		let _ = -6i32 / &Rational::ZERO;
+	}
+
 	#[test]
 	fn test_rational_div_assign() {
 		let mut a = Rational::new(BigInt::from(1), BigUInt::from(2u64)); // 1/2
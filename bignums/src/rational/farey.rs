@@ -0,0 +1,212 @@
+use core::cmp::Ordering;
+use core::fmt::{Display, Formatter};
+use core::iter::FusedIterator;
+
+use crate::bigfloat::BigFloat;
+use crate::bigint::BigInt;
+use crate::biguint::BigUInt;
+use crate::rational::Rational;
+
+/// A step from a node of the Stern-Brocot tree to one of its children
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SternBrocotMove {
+	/// Towards smaller values
+	Left,
+	/// Towards larger values
+	Right,
+}
+
+impl Display for SternBrocotMove {
+	fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+		match self {
+			SternBrocotMove::Left => write!(f, "L"),
+			SternBrocotMove::Right => write!(f, "R"),
+		}
+	}
+}
+
+/// Iterator over the path from the root 1/1 of the Stern-Brocot tree to a positive rational.
+/// Yields each move together with the node it leads to. No fraction with a smaller denominator
+/// lies between a node and the target, which makes the nodes good rational approximations.
+/// Created by Rational::stern_brocot. Use take to limit the depth.
+#[derive(Debug, Clone)]
+pub struct SternBrocot {
+	target_n: BigUInt,
+	target_d: BigUInt,
+	// The node is the mediant of the bounds, starting with 0/1 and 1/0
+	left: (BigUInt, BigUInt),
+	right: (BigUInt, BigUInt),
+	node: (BigUInt, BigUInt),
+}
+
+impl Rational {
+	/// Returns the path from the root of the Stern-Brocot tree to self
+	/// # Panics
+	/// If self is not positive
+	pub fn stern_brocot(&self) -> SternBrocot {
+		assert!(
+			self.is_positive() && !self.is_zero(),
+			"Stern-Brocot tree only contains positive rationals"
+		);
+		SternBrocot {
+			target_n: self.n.magnitude.clone(),
+			target_d: self.d.clone(),
+			left: (BigUInt::ZERO, BigUInt::ONE),
+			right: (BigUInt::ONE, BigUInt::ZERO),
+			node: (BigUInt::ONE, BigUInt::ONE),
+		}
+	}
+}
+
+impl BigFloat {
+	/// Returns the path from the root of the Stern-Brocot tree to self, see Rational::stern_brocot
+	/// # Panics
+	/// If self is not positive
+	pub fn stern_brocot(&self) -> SternBrocot {
+		self.to_rational().stern_brocot()
+	}
+}
+
+impl SternBrocot {
+	/// The node reached so far, which is 1/1 before the first move
+	pub fn node(&self) -> Rational {
+		Rational::new(BigInt::from(self.node.0.clone()), self.node.1.clone())
+	}
+
+	/// Whether the node reached so far is the target
+	pub fn is_finished(&self) -> bool {
+		self.cmp_target() == Ordering::Equal
+	}
+
+	/// Compares the target with the current node
+	fn cmp_target(&self) -> Ordering {
+		let lhs = &self.target_n * &self.node.1;
+		let rhs = &self.node.0 * &self.target_d;
+		lhs.cmp(&rhs)
+	}
+}
+
+impl Iterator for SternBrocot {
+	type Item = (SternBrocotMove, Rational);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let step = match self.cmp_target() {
+			Ordering::Equal => return None,
+			Ordering::Less => {
+				self.right = self.node.clone();
+				SternBrocotMove::Left
+			}
+			Ordering::Greater => {
+				self.left = self.node.clone();
+				SternBrocotMove::Right
+			}
+		};
+		self.node = (
+			self.left.0.clone() + &self.right.0,
+			self.left.1.clone() + &self.right.1,
+		);
+		Some((step, self.node()))
+	}
+}
+
+impl FusedIterator for SternBrocot {}
+
+#[cfg(test)]
+mod tests {
+	use alloc::string::String;
+	use alloc::vec::Vec;
+
+	use super::*;
+
+	fn r(n: i64, d: u64) -> Rational {
+		Rational::new(BigInt::from(n), BigUInt::from(d))
+	}
+
+	fn path(target: &Rational) -> String {
+		target
+			.stern_brocot()
+			.map(|(step, _)| step.to_string())
+			.collect()
+	}
+
+	#[test]
+	fn test_stern_brocot_path() {
+		let mut it = r(3, 5).stern_brocot();
+		assert_eq!(it.node(), Rational::ONE);
+		let nodes: Vec<_> = it.by_ref().collect();
+		assert_eq!(
+			nodes,
+			[
+				(SternBrocotMove::Left, r(1, 2)),
+				(SternBrocotMove::Right, r(2, 3)),
+				(SternBrocotMove::Left, r(3, 5)),
+			]
+		);
+		assert!(it.is_finished());
+		assert_eq!(it.node(), r(3, 5));
+		assert_eq!(it.next(), None);
+
+		assert_eq!(path(&r(3, 5)), "LRL");
+		assert_eq!(path(&r(6, 10)), "LRL");
+		assert_eq!(path(&Rational::ONE), "");
+		assert_eq!(path(&r(5, 1)), "RRRR");
+		assert_eq!(path(&r(1, 4)), "LLL");
+		assert_eq!(path(&r(7, 5)), "RLLR");
+		assert_eq!(path(&r(355, 113)), "RRRLLLLLLLRRRRRRRRRRRRRRR");
+	}
+
+	#[test]
+	fn test_stern_brocot_depth_limit() {
+		let third = BigFloat::ONE.div(&BigFloat::from(3), 200);
+		let nodes: Vec<_> = third.stern_brocot().take(10).collect();
+		assert_eq!(nodes.len(), 10);
+		// The path to 1/3 is LL, the approximation is slightly larger and continues RLLL...
+		assert_eq!(nodes[1], (SternBrocotMove::Left, r(1, 3)));
+		assert_eq!(nodes[9].1, r(9, 26));
+
+		// Runs of equal moves are as long as the partial quotients, so the full path
+		// to third has about 2^198 moves
+		assert_eq!(third.stern_brocot().nth(500).unwrap().1, r(500, 1499));
+
+		let x = BigFloat::from_mantissa_exponent(BigInt::from(3), -3);
+		assert_eq!(path(&x.to_rational()), "LLRL");
+		let mut it = x.stern_brocot();
+		assert_eq!(it.by_ref().last().unwrap().1, r(3, 8));
+		assert!(it.is_finished());
+	}
+
+	#[test]
+	fn test_stern_brocot_simplest_between() {
+		// No fraction with a smaller denominator lies strictly between a node and the target
+		let (tn, td) = (415u64, 93u64);
+		for (_, node) in r(tn as i64, td).stern_brocot() {
+			let n = u64::try_from(node.numerator()).unwrap();
+			let d = u64::try_from(&BigInt::from(node.denominator().clone())).unwrap();
+			for s in 1..d {
+				for k in 0..=s * 5 {
+					// k/s strictly between n/d and tn/td
+					let above_node = k * d > n * s;
+					let below_target = k * td < tn * s;
+					let below_node = k * d < n * s;
+					let above_target = k * td > tn * s;
+					assert!(
+						!(above_node && below_target) && !(below_node && above_target),
+						"{k}/{s} is between {node} and {tn}/{td}"
+					);
+				}
+			}
+		}
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_stern_brocot_zero() {
+		Rational::ZERO.stern_brocot();
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_stern_brocot_negative() {
+		r(-3, 5).stern_brocot();
+	}
+}
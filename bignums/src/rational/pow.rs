@@ -0,0 +1,97 @@
+use core::mem;
+
+use crate::rational::Rational;
+
+impl Rational {
+	/// Raises self to the power exp in place. Negative powers take the reciprocal.
+	/// # Panics
+	/// If self is zero and exp is negative
+	pub fn pow_assign(&mut self, exp: i64) {
+		let abs_exp = exp.unsigned_abs();
+		let is_negative = self.is_negative() && abs_exp % 2 == 1;
+		if exp < 0 {
+			assert!(!self.n.is_zero(), "division by zero");
+			mem::swap(&mut self.n.magnitude, &mut self.d);
+		}
+
+		self.n.magnitude.pow_assign(abs_exp);
+		self.d.pow_assign(abs_exp);
+		self.n.set_sign(is_negative);
+	}
+
+	/// Returns self^exp, or None if its numerator or denominator could be 2^max_log2 or more,
+	/// see BigUInt::checked_pow
+	/// # Panics
+	/// If self is zero and exp is negative
+	pub fn checked_pow(&self, exp: i64, max_log2: u64) -> Option<Rational> {
+		let abs_exp = exp.unsigned_abs();
+		if !self.n.magnitude.pow_fits(abs_exp, max_log2) || !self.d.pow_fits(abs_exp, max_log2) {
+			return None;
+		}
+		let mut res = self.clone();
+		res.pow_assign(exp);
+		Some(res)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::bigint::BigInt;
+	use crate::biguint::BigUInt;
+
+	#[test]
+	fn test_pow_assign() {
+		let values = [
+			Rational::new(BigInt::from(2), BigUInt::from(3u64)),
+			Rational::new(BigInt::from(-5), BigUInt::from(7u64)),
+			Rational::new(BigInt::from(-1), BigUInt::ONE),
+			Rational::new(BigInt::from(u64::MAX), BigUInt::from(3u64)),
+		];
+
+		for x in &values {
+			let mut expected = Rational::ONE;
+			for exp in 0..10 {
+				let mut in_place = x.clone();
+				in_place.pow_assign(exp);
+				assert_eq!(in_place, expected);
+
+				let mut in_place = x.clone();
+				in_place.pow_assign(-exp);
+				assert_eq!(in_place, expected.clone().reciprocal());
+
+				expected = &expected * x;
+			}
+		}
+
+		let mut x = Rational::new(BigInt::from(-2), BigUInt::from(3u64));
+		x.pow_assign(-3);
+		assert_eq!(x, Rational::new(BigInt::from(-27), BigUInt::from(8u64)));
+
+		let mut zero = Rational::ZERO;
+		zero.pow_assign(5);
+		assert!(zero.is_zero());
+		zero.pow_assign(0);
+		assert!(zero.is_one());
+	}
+
+	#[test]
+	fn test_checked_pow() {
+		let x = Rational::new(BigInt::from(-5), BigUInt::from(7u64));
+		// 7 has 3 bits
+		assert_eq!(
+			x.checked_pow(-3, 9),
+			Some(Rational::new(BigInt::from(-343), BigUInt::from(125u64)))
+		);
+		assert_eq!(x.checked_pow(-3, 8), None);
+		assert_eq!(x.checked_pow(i64::MIN, u64::MAX), None);
+		assert_eq!(Rational::ONE.checked_pow(i64::MAX, 1), Some(Rational::ONE));
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_pow_assign_zero_negative() {
+		let mut zero = Rational::ZERO;
+		zero.pow_assign(-1);
+	}
+}
@@ -1,8 +1,11 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use core::fmt::{Debug, Display, Formatter};
 use core::str::FromStr;
 
 use crate::bigint::BigInt;
-use crate::biguint::BigUInt;
+use crate::biguint::{BigUInt, DivRem};
 use crate::error::ParseRationalError;
 use crate::rational::Rational;
 
@@ -26,7 +29,74 @@ impl Debug for Rational {
 	}
 }
 
+/// How to format a rational number, see Rational::to_string_styled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FractionStyle {
+	/// "7/4"
+	#[default]
+	Fraction,
+	/// Whole part and proper fraction, "1 3/4"
+	Mixed,
+	/// "1.75" if the decimal expansion terminates, otherwise same as Fraction
+	Decimal,
+}
+
 impl Rational {
+	/// Formats the number in lowest terms using the given style.
+	/// Integers are always formatted without a fractional part.
+	pub fn to_string_styled(&self, style: FractionStyle) -> String {
+		let mut x = self.clone();
+		x.reduce();
+		if x.d.is_one() {
+			return x.n.to_string();
+		}
+
+		match style {
+			FractionStyle::Fraction => x.to_string(),
+			FractionStyle::Mixed => {
+				let sign = if x.n.is_negative() { "-" } else { "" };
+				let (whole, fract) = (&mut x.n.unsigned_abs()).div_rem(&mut x.d);
+				format!("{sign}{whole} {fract}/{0}", x.d)
+			}
+			FractionStyle::Decimal => match x.terminating_decimal_digits() {
+				Some(digits) => x.to_decimal_string(digits),
+				None => x.to_string(),
+			},
+		}
+	}
+
+	/// Returns the number of fractional digits of the decimal expansion if it terminates,
+	/// i.e. if the denominator is of the form 2^a * 5^b. Expects self to be reduced.
+	fn terminating_decimal_digits(&self) -> Option<u64> {
+		let twos = self.d.trailing_zeros();
+		let mut d = self.d.clone() >> twos;
+		let mut fives = 0u64;
+		loop {
+			let (q, r) = (&mut d).div_rem(5u64);
+			if !r.is_zero() {
+				break;
+			}
+			d = q;
+			fives += 1;
+		}
+		d.is_one().then_some(twos.max(fives))
+	}
+
+	/// Formats the number with the given amount of fractional digits,
+	/// which must be enough to represent it exactly.
+	fn to_decimal_string(&self, digits: u64) -> String {
+		let mut scaled = &self.n.magnitude * &BigUInt::from(10u32).pow(digits);
+		let (q, r) = (&mut scaled).div_rem(&mut self.d.clone());
+		debug_assert!(r.is_zero(), "not enough digits");
+
+		let digits = digits as usize;
+		let s = q.to_string();
+		let s = format!("{s:0>width$}", width = digits + 1);
+		let (whole, fract) = s.split_at(s.len() - digits);
+		let sign = if self.n.is_negative() { "-" } else { "" };
+		format!("{sign}{whole}.{fract}")
+	}
+
 	pub fn from_decimal_str(src: &str) -> Result<Self, ParseRationalError> {
 		Self::from_decimal_str_radix(src, 10)
 	}
@@ -40,7 +110,16 @@ impl Rational {
 	}
 
 	pub fn from_decimal_ascii_radix(src: &[u8], radix: u32) -> Result<Self, ParseRationalError> {
-		todo!()
+		let (whole, fract) = src.split_once(|&c| c == b'.').unwrap_or((src, b""));
+
+		// whole.fract = (whole ++ fract) / radix^len(fract)
+		let mut digits = Vec::with_capacity(whole.len() + fract.len());
+		digits.extend_from_slice(whole);
+		digits.extend_from_slice(fract);
+
+		let n = BigInt::parse_untrimmed(&digits, radix)?;
+		let d = BigUInt::from(radix).pow(fract.len() as u64);
+		Ok(Self::new(n, d))
 	}
 
 	pub fn from_fraction_str(src: &str) -> Result<Self, ParseRationalError> {
@@ -57,17 +136,121 @@ impl Rational {
 
 	pub fn from_fraction_ascii_radix(src: &[u8], radix: u32) -> Result<Self, ParseRationalError> {
 		if let Some((n_str, d_str)) = src.split_once(|&c| c == b'/') {
-			let n = BigInt::from_ascii_radix(n_str, radix).map_err(|e| e.to_rational_error())?;
-			let d = BigUInt::from_ascii_radix(d_str, radix).map_err(|e| e.to_rational_error())?;
+			let n = BigInt::parse_untrimmed(n_str, radix)?;
+			let d = BigUInt::parse_untrimmed(d_str, radix)?;
 			if d.is_zero() {
 				Err(ParseRationalError::DenominatorZero)
 			} else {
 				Ok(Self::new(n, d))
 			}
 		} else {
-			Ok(Self::from(
-				BigInt::from_ascii_radix(src, radix).map_err(|e| e.to_rational_error())?,
-			))
+			Ok(Self::from(BigInt::parse_untrimmed(src, radix)?))
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_from_decimal_str() {
+		assert_eq!(
+			Rational::from_decimal_str("0.25").unwrap(),
+			Rational::new(BigInt::from(1), BigUInt::from(4u64))
+		);
+		assert_eq!(
+			Rational::from_decimal_str("-12.5").unwrap(),
+			Rational::new(BigInt::from(-25), BigUInt::from(2u64))
+		);
+		assert_eq!(
+			Rational::from_decimal_str(".5").unwrap(),
+			Rational::new(BigInt::from(1), BigUInt::from(2u64))
+		);
+		assert_eq!(Rational::from_decimal_str("42").unwrap(), 42);
+		assert_eq!(Rational::from_decimal_str("42.").unwrap(), 42);
+		assert_eq!(
+			Rational::from_decimal_str_radix("0.8", 16).unwrap(),
+			Rational::new(BigInt::from(1), BigUInt::from(2u64))
+		);
+		assert_eq!(
+			Rational::from_decimal_str(""),
+			Err(ParseRationalError::Empty)
+		);
+		assert_eq!(
+			Rational::from_decimal_str("."),
+			Err(ParseRationalError::Empty)
+		);
+		assert_eq!(
+			Rational::from_decimal_str("-"),
+			Err(ParseRationalError::Empty)
+		);
+		assert_eq!(
+			Rational::from_decimal_str("1.2.3"),
+			Err(ParseRationalError::InvalidDigit)
+		);
+	}
+
+	#[test]
+	fn test_to_string_styled() {
+		let q = Rational::new(BigInt::from(3), BigUInt::from(4u64));
+		assert_eq!(q.to_string_styled(FractionStyle::Fraction), "3/4");
+		assert_eq!(q.to_string_styled(FractionStyle::Mixed), "0 3/4");
+		assert_eq!(q.to_string_styled(FractionStyle::Decimal), "0.75");
+
+		let q = Rational::new(BigInt::from(-14), BigUInt::from(8u64));
+		assert_eq!(q.to_string_styled(FractionStyle::Fraction), "-7/4");
+		assert_eq!(q.to_string_styled(FractionStyle::Mixed), "-1 3/4");
+		assert_eq!(q.to_string_styled(FractionStyle::Decimal), "-1.75");
+
+		let q = Rational::new(BigInt::from(1), BigUInt::from(3u64));
+		assert_eq!(q.to_string_styled(FractionStyle::Fraction), "1/3");
+		assert_eq!(q.to_string_styled(FractionStyle::Mixed), "0 1/3");
+		assert_eq!(q.to_string_styled(FractionStyle::Decimal), "1/3");
+
+		let q = Rational::new(BigInt::from(7), BigUInt::from(6u64));
+		assert_eq!(q.to_string_styled(FractionStyle::Decimal), "7/6");
+
+		let q = Rational::new(BigInt::from(1), BigUInt::from(1000u64));
+		assert_eq!(q.to_string_styled(FractionStyle::Decimal), "0.001");
+		let q = Rational::new(BigInt::from(-3), BigUInt::from(1u64 << 10));
+		assert_eq!(q.to_string_styled(FractionStyle::Decimal), "-0.0029296875");
+
+		let q = Rational::new(BigInt::from(-10), BigUInt::from(5u64));
+		for style in [
+			FractionStyle::Fraction,
+			FractionStyle::Mixed,
+			FractionStyle::Decimal,
+		] {
+			assert_eq!(q.to_string_styled(style), "-2");
+		}
+	}
+
+	#[test]
+	fn test_negative_zero() {
+		let mut set = Rational::ZERO;
+		set.set_sign(true);
+		let zeros = [
+			set,
+			-Rational::ZERO,
+			Rational::new(
+				BigInt::from_sign_and_magnitude(true, BigUInt::ZERO),
+				BigUInt::from(3u64),
+			),
+			Rational::from_str("-0/7").unwrap(),
+			Rational::from_decimal_str("-0.00").unwrap(),
+			&Rational::from(-2) * &Rational::ZERO,
+		];
+		for zero in zeros {
+			assert_eq!(zero, Rational::ZERO);
+			assert!(!zero.is_negative());
+			for style in [
+				FractionStyle::Fraction,
+				FractionStyle::Mixed,
+				FractionStyle::Decimal,
+			] {
+				assert_eq!(zero.to_string_styled(style), "0");
+			}
 		}
 	}
 }
@@ -19,6 +19,14 @@ impl Neg for Rational {
 	}
 }
 
+impl Neg for &Rational {
+	type Output = Rational;
+
+	fn neg(self) -> Self::Output {
+		-self.clone()
+	}
+}
+
 impl Sub<&Rational> for &Rational {
 	type Output = Rational;
 
@@ -119,4 +127,16 @@ mod tests {
 		assert_eq!(*result.numerator(), BigInt::from(-1));
 		assert_eq!(*result.denominator(), BigUInt::from(2u64));
 	}
+
+	#[test]
+	fn test_rational_neg_ref() {
+		let a = Rational::new(BigInt::from(-3), BigUInt::from(4u64)); // -3/4
+		let result = -&a;
+		assert_eq!(result, -a.clone());
+		assert_eq!(*result.numerator(), BigInt::from(3));
+		assert_eq!(*result.denominator(), BigUInt::from(4u64));
+		// Unchanged
+		assert_eq!(*a.numerator(), BigInt::from(-3));
+		assert_eq!(-&-&a, a);
+	}
 }
@@ -2,6 +2,7 @@ mod add;
 mod consts;
 mod convert;
 mod div;
+mod exp;
 mod log;
 mod mul;
 mod set_val;
@@ -11,16 +11,7 @@ impl Real {
 			return Err(self);
 		}
 		let n_round = self.eval(0);
-		let n_ilog2 = if n_round.is_zero() {
-			0
-		} else {
-			n_round.ilog2()
-		};
-		let d_ilog2 = if d_round.is_zero() {
-			0
-		} else {
-			d_round.ilog2()
-		};
+		let n_ilog2 = n_round.checked_ilog2().unwrap_or(0);
 
 		let d_lower_bound_ilog2 = if d_round.is_negative() {
 			(&d_round + &tau).ilog2()
@@ -29,9 +20,11 @@ impl Real {
 		};
 
 		Ok(Real::new(move |prec| {
+			// The error of n is scaled by 1/d and the error of d by n/d^2,
+			// so small divisors need more bits, not fewer
 			let actual_prec = prec + 1;
-			let prec_d = actual_prec + 2 * d_lower_bound_ilog2 + n_ilog2 + 2;
-			let prec_n = actual_prec + 2 * d_lower_bound_ilog2 + d_ilog2 + 2;
+			let prec_d = actual_prec - 2 * d_lower_bound_ilog2 + n_ilog2 + 5;
+			let prec_n = actual_prec - d_lower_bound_ilog2 + 3;
 
 			let d = rhs.eval(prec_d);
 			let n = self.eval(prec_n);
@@ -40,3 +33,34 @@ impl Real {
 		}))
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::bigint::BigInt;
+	use crate::biguint::BigUInt;
+	use crate::rational::Rational;
+
+	#[test]
+	fn test_div_small_divisor() {
+		let tenth = Real::from(Rational::new(BigInt::ONE, BigUInt::from(10u32)));
+		let q = Real::one().div(tenth, 64).ok().unwrap();
+		for prec in [-4, 0, 1, 10, 64, 200] {
+			let delta = (&q.eval(prec) - &BigFloat::from(10)).abs();
+			assert!(delta <= (BigFloat::ONE >> prec), "{prec}");
+		}
+
+		let tiny = Real::from(BigFloat::from_mantissa_exponent(BigInt::from(3), -100));
+		let q = Real::from(BigFloat::from(7)).div(tiny, 200).ok().unwrap();
+		let expected = Rational::new(BigInt::from(7) << 100u32, BigUInt::from(3u32));
+		for prec in [0, 64] {
+			let delta = (&q.eval(prec) - &expected.to_float(prec + 10)).abs();
+			assert!(delta <= (BigFloat::ONE >> prec), "{prec}");
+		}
+
+		let big = Real::from(BigFloat::from(1000));
+		let q = Real::from(BigFloat::from(3)).div(big, 64).ok().unwrap();
+		let expected = BigFloat::from(3).div(&BigFloat::from(1000), 80);
+		assert!((&q.eval(64) - &expected).abs() <= (BigFloat::ONE >> 64));
+	}
+}
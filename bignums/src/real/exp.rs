@@ -0,0 +1,63 @@
+use crate::bigfloat::BigFloat;
+use crate::real::Real;
+
+impl Real {
+	/// # Panics
+	/// If the result could be 2^BigFloat::DEFAULT_MAX_LOG2 or more
+	pub fn exp(self) -> Real {
+		match self.try_exp(BigFloat::DEFAULT_MAX_LOG2) {
+			Ok(res) => res,
+			Err(_) => panic!("exp overflowed"),
+		}
+	}
+
+	/// exp, returns Err(self) if the result could be 2^max_log2 or more
+	pub fn try_exp(self, max_log2: i64) -> Result<Real, Real> {
+		// exp(x + d) = exp(x) * exp(d), so an error d in x turns into a relative error of about d.
+		// |x - x_round| < 1, so exp(x) < 2^growth
+		let x_round = self.eval(0);
+		let log2_bound = (x_round.to_f64() + 1.0) * core::f64::consts::LOG2_E;
+		if log2_bound >= max_log2 as f64 {
+			return Err(self);
+		}
+		let growth = if x_round.is_negative() {
+			0
+		} else {
+			log2_bound as i64 + 1
+		};
+
+		Ok(Real::new(move |prec| {
+			let actual_prec = prec + 1;
+			let x = self.eval(actual_prec + growth + 2);
+
+			// x is within 1 of x_round, the slack covers the rounding of the bounds
+			x.try_exp(actual_prec, max_log2.saturating_add(2))
+				.expect("exp is bounded by log2_bound")
+		}))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::bigfloat::BigFloat;
+
+	#[test]
+	fn test_exp() {
+		let e = Real::one().exp().eval(200);
+		let expected = BigFloat::ONE.exp(300);
+		assert!((&e - &expected).abs() < (BigFloat::ONE >> 200));
+
+		// exp(ln(2)) = 2
+		let two = Real::ln2().exp().eval(300);
+		assert!((&two - 2u32).abs() < (BigFloat::ONE >> 300));
+	}
+
+	#[test]
+	fn test_try_exp() {
+		// exp(100) < 2^145
+		assert!(Real::from(BigFloat::from(100)).try_exp(150).is_ok());
+		assert!(Real::from(BigFloat::from(100)).try_exp(140).is_err());
+		assert!(Real::from(BigFloat::from(-100000)).try_exp(1).is_ok());
+	}
+}
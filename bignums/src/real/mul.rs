@@ -8,16 +8,8 @@ impl Mul<Real> for Real {
 	fn mul(self, rhs: Real) -> Self::Output {
 		let a_round = self.eval(0);
 		let b_round = rhs.eval(0);
-		let a_ilog2 = if a_round.is_zero() {
-			0
-		} else {
-			a_round.ilog2()
-		};
-		let b_ilog2 = if b_round.is_zero() {
-			0
-		} else {
-			b_round.ilog2()
-		};
+		let a_ilog2 = a_round.checked_ilog2().unwrap_or(0);
+		let b_ilog2 = b_round.checked_ilog2().unwrap_or(0);
 		Real::new(move |prec| {
 			let actual_prec = prec + 1;
 
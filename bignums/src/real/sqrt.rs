@@ -1,3 +1,4 @@
+use crate::bigfloat::BigFloat;
 use crate::real::Real;
 
 impl Real {
@@ -11,4 +12,27 @@ impl Real {
 			x.sqrt(actual_prec)
 		})
 	}
+
+	/// Real n-th root, returns Err(self) for even roots of numbers less than -2^-tol
+	/// Evaluating it to prec bits evaluates self to about n * prec bits, so callers should bound n
+	pub fn nth_root(self, n: u32, tol: i64) -> Result<Real, Real> {
+		assert!(n != 0, "Cannot take 0th root");
+		let is_even = n.is_multiple_of(2);
+		if is_even && self.eval(tol) < -(BigFloat::ONE >> tol) {
+			return Err(self);
+		}
+
+		Ok(Real::new(move |prec| {
+			let actual_prec = prec + 1;
+			// |x^(1/n) - y^(1/n)| <= 2 * |x - y|^(1/n)
+			let prec_x = (actual_prec + 1) * n as i64;
+
+			let mut x = self.eval(prec_x);
+			if is_even {
+				x.abs_in_place();
+			}
+
+			x.nth_root(n, actual_prec)
+		}))
+	}
 }
@@ -279,10 +279,26 @@ pub(crate) fn parse_ascii_digit(c: u8) -> Option<u8> {
 	}
 }
 
+#[cfg(test)]
+use crate::bigfloat::{BigFloat, TranscendentalOptions};
+#[cfg(test)]
+use crate::bigint::BigInt;
 #[cfg(test)]
 use crate::biguint::BigUInt;
 use crate::error::TryFromFloatError;
 
+/// xorshift64, deterministic pseudo-random digits for tests
+#[cfg(test)]
+pub(crate) fn xorshift64(seed: u64) -> impl FnMut() -> u64 {
+	let mut state = seed;
+	move || {
+		state ^= state << 13;
+		state ^= state >> 7;
+		state ^= state << 17;
+		state
+	}
+}
+
 #[cfg(test)]
 pub(crate) fn to_foreign_biguint(a: BigUInt) -> num_bigint::BigUint {
 	let data = a.into_bytes_le();
@@ -295,6 +311,87 @@ pub(crate) fn from_foreign_biguint(a: num_bigint::BigUint) -> BigUInt {
 	BigUInt::from_bytes_le(data)
 }
 
+#[cfg(test)]
+pub(crate) fn to_foreign_bigint(a: BigInt) -> num_bigint::BigInt {
+	let sign = if a.is_negative() {
+		num_bigint::Sign::Minus
+	} else {
+		num_bigint::Sign::Plus
+	};
+	num_bigint::BigInt::from_biguint(sign, to_foreign_biguint(a.into_inner()))
+}
+
+#[cfg(test)]
+pub(crate) fn from_foreign_bigint(a: num_bigint::BigInt) -> BigInt {
+	let (sign, magnitude) = a.into_parts();
+	BigInt::from_sign_and_magnitude(
+		sign == num_bigint::Sign::Minus,
+		from_foreign_biguint(magnitude),
+	)
+}
+
+/// Asserts that x is within 2^-prec of expected
+#[cfg(test)]
+pub(crate) fn assert_close(x: &BigFloat, expected: &BigFloat, prec: i64) {
+	let delta = (x - expected).abs();
+	assert!(delta < (BigFloat::ONE >> prec), "{x} != {expected}");
+}
+
+/// Checks f_opts against f with the default options, that more guard bits never hurt
+/// and that capping the iterations at 2 loses accuracy.
+#[cfg(test)]
+pub(crate) fn check_transcendental_opts(
+	name: &str,
+	inputs: &[&str],
+	f: impl Fn(&BigFloat, i64) -> BigFloat,
+	f_opts: impl Fn(&BigFloat, i64, TranscendentalOptions) -> BigFloat,
+) {
+	use core::str::FromStr;
+
+	let prec = 500;
+	let epsilon = BigFloat::ONE >> prec;
+	// Rounding to the result precision can move the result by this much either way
+	let ulp = BigFloat::ONE >> (prec + 2);
+	for x in inputs {
+		let x = BigFloat::from_str(x).unwrap();
+		let reference = f(&x, prec + 128);
+		assert_eq!(
+			f_opts(&x, prec, TranscendentalOptions::default()),
+			f(&x, prec)
+		);
+
+		let mut last_delta: Option<BigFloat> = None;
+		for extra_guard_bits in [0, 4, 16, 64, 256] {
+			let opts = TranscendentalOptions {
+				extra_guard_bits,
+				..Default::default()
+			};
+			let delta = (&f_opts(&x, prec, opts) - &reference).abs();
+			assert!(
+				delta < epsilon,
+				"{name}({x}) with {extra_guard_bits} guard bits"
+			);
+			if let Some(last_delta) = last_delta {
+				assert!(
+					delta <= &last_delta + &ulp,
+					"{name}({x}) with {extra_guard_bits} guard bits"
+				);
+			}
+			last_delta = Some(delta);
+		}
+
+		// Two Newton steps, AGM steps or Taylor series terms are not enough for 500 bits
+		let opts = TranscendentalOptions {
+			max_iterations: Some(2),
+			..Default::default()
+		};
+		assert!(
+			(&f_opts(&x, prec, opts) - &reference).abs() > epsilon,
+			"{name}({x}) with 2 iterations"
+		);
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
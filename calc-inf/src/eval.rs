@@ -1,4 +1,11 @@
-use bignums::error::ParseFloatError;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use bignums::bigfloat::BigFloat;
+use bignums::bigint::BigInt;
+use bignums::biguint::{BigUInt, DivRem};
+use bignums::error::{ParseFloatError, ParseRationalError};
+use bignums::rational::{FractionStyle, Rational};
 use bignums::real::Real;
 use crate::parser::{BinOp, Expr, UnaryOp};
 
@@ -8,6 +15,13 @@ pub enum EvalError {
 	ParseFloatError(ParseFloatError),
 	InvalidConst(String),
 	InvalidFnCall(String),
+	NonIntegerOperand,
+	InvalidRootDegree,
+	InvalidExponent,
+	InvalidRangeBound,
+	TooManyTerms,
+	DivisionByZero,
+	Overflow,
 }
 
 impl std::fmt::Display for EvalError {
@@ -17,50 +31,963 @@ impl std::fmt::Display for EvalError {
 			EvalError::ParseFloatError(e) => write!(f, "Parse float error: {}", e),
 			EvalError::InvalidConst(s) => write!(f, "Invalid constant: {}", s),
 			EvalError::InvalidFnCall(s) => write!(f, "Invalid function call: {}", s),
+			EvalError::NonIntegerOperand => write!(f, "Bitwise operations require integer operands"),
+			EvalError::InvalidRootDegree => write!(f, "Root degree must be an integer from 1 to {}", MAX_ROOT_DEGREE),
+			EvalError::InvalidExponent => write!(f, "Exponent must be an integer"),
+			EvalError::InvalidRangeBound => write!(f, "Range bounds must be integers"),
+			EvalError::TooManyTerms => write!(f, "Range has more than {} terms", MAX_RANGE_TERMS),
+			EvalError::DivisionByZero => write!(f, "Division by zero"),
+			EvalError::Overflow => write!(f, "Result is too large"),
+		}
+	}
+}
+
+/// Guard bits added to the tolerance for every level of nested function calls,
+/// so that inner results don't lose bits before the outer function is applied
+const FN_GUARD_BITS: i64 = 16;
+
+/// Default bound on log2 of intermediate results, larger ones fail with EvalError::Overflow
+pub const DEFAULT_MAX_LOG2: u64 = BigFloat::DEFAULT_MAX_LOG2 as u64;
+
+/// Bound on the number of terms of sum and prod
+pub const MAX_RANGE_TERMS: u64 = 100_000;
+
+/// Bound on the degree of root, which needs about degree times as many bits of the radicand
+pub const MAX_ROOT_DEGREE: u32 = 1000;
+
+/// A variable bound by sum or prod, linked to the variables of the enclosing calls
+struct Binding<'s> {
+	name: &'s str,
+	value: BigInt,
+	outer: Scope<'s>,
+}
+
+/// The variables in scope, innermost first
+type Scope<'s> = Option<&'s Binding<'s>>;
+
+fn lookup<'s>(scope: Scope<'s>, name: &str) -> Option<&'s BigInt> {
+	let mut binding = scope;
+	while let Some(b) = binding {
+		if b.name == name {
+			return Some(&b.value);
+		}
+		binding = b.outer;
+	}
+	None
+}
+
+/// Settings shared by every level of Expr::eval_internal
+#[derive(Clone, Copy)]
+struct EvalCtx<'t, 'e, 'a> {
+	max_log2: u64,
+	/// Collects each sub-expression with its value when tracing, see Expr::eval_traced
+	steps: Option<&'t RefCell<Vec<(&'e Expr<'a>, Real)>>>,
+}
+
+/// Result of evaluating an expression both exactly and approximately
+#[derive(Debug, Clone)]
+pub struct Evaluation {
+	/// The exact value, if the expression only involves rational operations
+	pub exact: Option<Rational>,
+	/// The value rounded to the requested precision
+	pub approx: BigFloat,
+}
+
+/// A result that is exact as long as only rational operations were involved
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+	/// Exact result, in lowest terms
+	Rational(Rational),
+	/// Result of a function or constant that is only known approximately
+	Float(BigFloat),
+}
+
+impl Value {
+	pub fn to_float(&self, prec: i64) -> BigFloat {
+		match self {
+			Value::Rational(q) => rational_to_float(q, prec),
+			Value::Float(x) => x.clone(),
+		}
+	}
+}
+
+impl std::fmt::Display for Value {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Value::Rational(q) => write!(f, "{}", q.to_string_styled(FractionStyle::Fraction)),
+			Value::Float(x) => write!(f, "{}", x),
+		}
+	}
+}
+
+/// A sub-expression and its value, see Expr::eval_traced
+#[derive(Debug, Clone)]
+pub struct Step<'e, 'a> {
+	pub expr: &'e Expr<'a>,
+	pub value: BigFloat,
+}
+
+impl std::fmt::Display for Step<'_, '_> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{} = {}", self.expr, self.value)
+	}
+}
+
+impl Evaluation {
+	/// The exact value if there is one, otherwise the approximation
+	pub fn value(self) -> Value {
+		match self.exact {
+			Some(q) => Value::Rational(q),
+			None => Value::Float(self.approx),
 		}
 	}
 }
 
 impl<'a> Expr<'a> {
+	/// Evaluates to an exact rational if possible, falling back to a float with the given precision
+	pub fn evaluate_value(&self, prec: i64) -> Result<Value, EvalError> {
+		Ok(self.evaluate(prec)?.value())
+	}
+
+	pub fn evaluate(&self, prec: i64) -> Result<Evaluation, EvalError> {
+		self.evaluate_with_max_log2(prec, DEFAULT_MAX_LOG2)
+	}
+
+	/// Same as evaluate, but fails with EvalError::Overflow once a result could reach 2^max_log2
+	pub fn evaluate_with_max_log2(&self, prec: i64, max_log2: u64) -> Result<Evaluation, EvalError> {
+		self.evaluation(prec, max_log2, || Ok(self.eval_with_max_log2(prec, max_log2)?.eval(prec)))
+	}
+
+	/// Same as evaluate(prec), also returning the value of every operation and function call
+	/// in the order they are computed, so the last step is self unless it's a number or constant.
+	/// Bodies of sum and prod are not traced, as their value depends on the bound variable.
+	pub fn eval_traced(&self, prec: i64) -> Result<(Evaluation, Vec<Step<'_, 'a>>), EvalError> {
+		let steps = RefCell::new(Vec::new());
+		let ctx = EvalCtx { max_log2: DEFAULT_MAX_LOG2, steps: Some(&steps) };
+		let value = self.eval_internal(prec, ctx, None)?.eval(prec);
+		// Outer steps evaluate the inner ones to a higher precision, which the inner steps
+		// then reuse, so each sub-expression is only computed once
+		let mut steps: Vec<_> = steps
+			.into_inner()
+			.into_iter()
+			.rev()
+			.map(|(expr, x)| Step { expr, value: x.eval(prec) })
+			.collect();
+		steps.reverse();
+		Ok((self.evaluation(prec, DEFAULT_MAX_LOG2, || Ok(value))?, steps))
+	}
+
+	/// The exact value if there is one, paired with its rounding or otherwise with approx()
+	fn evaluation(
+		&self,
+		prec: i64,
+		max_log2: u64,
+		approx: impl FnOnce() -> Result<BigFloat, EvalError>,
+	) -> Result<Evaluation, EvalError> {
+		let exact = self.eval_exact(max_log2, None).map(|mut q| {
+			q.reduce();
+			q
+		});
+		let approx = match &exact {
+			Some(q) => rational_to_float(q, prec),
+			None => approx()?,
+		};
+		Ok(Evaluation { exact, approx })
+	}
+
+	fn eval_exact(&self, max_log2: u64, scope: Scope) -> Option<Rational> {
+		match self {
+			Expr::Number(s) => parse_number(s, max_log2).ok()?,
+			Expr::FnCall { name: "pow", args } => {
+				check_arity("pow", args).ok()?;
+				let n = rational_to_integer(args[1].eval_exact(max_log2, scope)?)?;
+				rational_pow(args[0].eval_exact(max_log2, scope)?, &n, max_log2)
+			}
+			Expr::FnCall { name: name @ ("sum" | "prod"), args } => {
+				let (var, body) = range_var_and_body(name, args).ok()?;
+				let mut k = rational_to_integer(args[1].eval_exact(max_log2, scope)?)?;
+				let hi = rational_to_integer(args[2].eval_exact(max_log2, scope)?)?;
+				let len = range_len(&k, &hi).ok()?;
+
+				let is_sum = *name == "sum";
+				let mut res = if is_sum { Rational::ZERO } else { Rational::ONE };
+				for _ in 0..len {
+					let binding = Binding { name: var, value: k.clone(), outer: scope };
+					let term = body.eval_exact(max_log2, Some(&binding))?;
+					res = if is_sum { &res + &term } else { &res * &term };
+					res.reduce();
+					let bits = u64::max(res.numerator().inner().checked_ilog2().unwrap_or(0), res.denominator().ilog2());
+					if bits >= max_log2 {
+						return None;
+					}
+					k += 1u32;
+				}
+				Some(res)
+			}
+			Expr::Const(s) => lookup(scope, s).map(Rational::from),
+			Expr::FnCall { .. } => None,
+			Expr::Binary { op, left, right } => {
+				let l = left.eval_exact(max_log2, scope)?;
+				let r = right.eval_exact(max_log2, scope)?;
+				match *op {
+					BinOp::Add => Some(&l + &r),
+					BinOp::Sub => Some(&l - &r),
+					BinOp::Mul => Some(&l * &r),
+					BinOp::Div if r.is_zero() => None,
+					BinOp::Div => Some(&l / &r),
+					BinOp::Rem if r.is_zero() => None,
+					BinOp::Rem => Some(rational_rem(&l, &r)),
+					_ => {
+						let res = integer_op(op, &rational_to_integer(l)?, &rational_to_integer(r)?, max_log2).ok()?;
+						Some(Rational::new(res, BigUInt::ONE))
+					}
+				}
+			}
+			Expr::Unary { op, operand } => {
+				let mut arg = operand.eval_exact(max_log2, scope)?;
+				match *op {
+					UnaryOp::Neg => arg.neg_in_place(),
+					UnaryOp::Pos => {}
+					UnaryOp::Not => arg = Rational::new(!rational_to_integer(arg)?, BigUInt::ONE),
+					UnaryOp::Percent => arg = &arg / 100,
+				}
+				Some(arg)
+			}
+		}
+	}
+
 	pub fn eval(&self, tol: i64) -> Result<Real, EvalError> {
-		std::panic::catch_unwind(|| self.eval_internal(tol)).map_err(|_| EvalError::ArithmeticError)?
+		self.eval_with_max_log2(tol, DEFAULT_MAX_LOG2)
 	}
-	
-	fn eval_internal(&self, tol: i64) -> Result<Real, EvalError> {
+
+	fn eval_with_max_log2(&self, tol: i64, max_log2: u64) -> Result<Real, EvalError> {
+		self.eval_internal(tol, EvalCtx { max_log2, steps: None }, None)
+	}
+
+	fn eval_internal<'e>(&'e self, tol: i64, ctx: EvalCtx<'_, 'e, 'a>, scope: Scope) -> Result<Real, EvalError> {
+		let res = self.eval_node(tol, ctx, scope)?;
+		match (self, ctx.steps) {
+			(Expr::Number(_) | Expr::Const(_), _) | (_, None) => Ok(res),
+			(_, Some(steps)) => {
+				let res = memoize(res);
+				steps.borrow_mut().push((self, res.clone()));
+				Ok(res)
+			}
+		}
+	}
+
+	fn eval_node<'e>(&'e self, tol: i64, ctx: EvalCtx<'_, 'e, 'a>, scope: Scope) -> Result<Real, EvalError> {
 		match self {
 			Expr::Number(s) => {
-				Ok(Real::from_string(s.to_string()).map_err(|e| EvalError::ParseFloatError(e))?)
+				Ok(parse_number(s, ctx.max_log2)?.map_or_else(|| Real::from(0), Real::from))
 			}
 			Expr::Const(s) => {
+				if let Some(k) = lookup(scope, s) {
+					return Ok(Real::from(k.clone()));
+				}
 				match *s {
 					"pi" => Ok(Real::pi()),
 					_ => Err(EvalError::InvalidConst(s.to_string()))
 				}
 			}
 			Expr::FnCall { name, args } => {
+				check_arity(name, args)?;
+				// Each nesting level tightens the tolerance of its arguments
+				let arg_tol = tol + FN_GUARD_BITS;
 				match *name {
-					"ln" => Ok(args[0].eval_internal(tol)?.ln(tol).map_err(|_| EvalError::ArithmeticError)?),
-					"sqrt" => Ok(args[0].eval_internal(tol)?.sqrt()),
+					"ln" => Ok(args[0].eval_internal(arg_tol, ctx, scope)?.ln(tol).map_err(|_| EvalError::ArithmeticError)?),
+					"exp" => {
+						let arg = args[0].eval_internal(arg_tol, ctx, scope)?;
+						let max_log2 = i64::try_from(ctx.max_log2).unwrap_or(i64::MAX);
+						arg.try_exp(max_log2).map_err(|_| EvalError::Overflow)
+					}
+					"sqrt" => Ok(args[0].eval_internal(arg_tol, ctx, scope)?.sqrt()),
+					"cbrt" => Ok(args[0].eval_internal(arg_tol, ctx, scope)?.nth_root(3, tol).map_err(|_| EvalError::ArithmeticError)?),
+					"root" => {
+						let n = args[1].eval_internal(arg_tol, ctx, scope)?;
+						let n = root_degree(&args[1], &n, tol, ctx.max_log2, scope)?;
+						Ok(args[0].eval_internal(arg_tol, ctx, scope)?.nth_root(n, tol).map_err(|_| EvalError::ArithmeticError)?)
+					}
+					"pow" => {
+						let n = args[1].eval_internal(arg_tol, ctx, scope)?;
+						let n = integer_operand(&args[1], &n, tol, ctx.max_log2, scope).map_err(|_| EvalError::InvalidExponent)?;
+						real_pow(args[0].eval_internal(arg_tol, ctx, scope)?, &n, tol, ctx.max_log2)
+					}
+					"sum" | "prod" => {
+						let (var, body) = range_var_and_body(name, args)?;
+						let bound = |arg: &'e Expr<'a>| -> Result<BigInt, EvalError> {
+							let x = arg.eval_internal(arg_tol, ctx, scope)?;
+							integer_operand(arg, &x, tol, ctx.max_log2, scope).map_err(|_| EvalError::InvalidRangeBound)
+						};
+						let mut k = bound(&args[1])?;
+						let len = range_len(&k, &bound(&args[2])?)?;
+
+						// The body depends on the bound variable, so its steps aren't traced
+						let body_ctx = EvalCtx { steps: None, ..ctx };
+						let is_sum = *name == "sum";
+						let mut terms = Vec::new();
+						// Bound on log2 of the product, like in real_pow
+						let mut max_bits = 0u64;
+						for _ in 0..len {
+							let binding = Binding { name: var, value: k.clone(), outer: scope };
+							let term = body.eval_internal(arg_tol, body_ctx, Some(&binding))?;
+							if !is_sum
+								&& let Some(log2) = term.eval(0).checked_ilog2().filter(|&log2| log2 >= 0)
+							{
+								max_bits += log2 as u64 + 2;
+								if max_bits >= ctx.max_log2 {
+									return Err(EvalError::Overflow);
+								}
+							}
+							terms.push(term);
+							k += 1u32;
+						}
+						Ok(if is_sum {
+							fold_balanced(terms, Real::from(0), |a, b| a + b)
+						} else {
+							fold_balanced(terms, Real::one(), |a, b| a * b)
+						})
+					}
 					_ => Err(EvalError::InvalidFnCall(name.to_string()))
 				}
 			}
 			Expr::Binary { op, left, right } => {
-				let l = left.eval_internal(tol)?;
-				let r = right.eval_internal(tol)?;
+				let l = left.eval_internal(tol, ctx, scope)?;
+				let r = right.eval_internal(tol, ctx, scope)?;
 				match *op {
 					BinOp::Add => { Ok(l + r) }
 					BinOp::Sub => { Ok(l - r) }
 					BinOp::Mul => { Ok(l * r) }
-					BinOp::Div => { Ok(l.div(r, tol).map_err(|_| EvalError::ArithmeticError)?) }
+					BinOp::Div => { Ok(l.div(r, tol).map_err(|_| EvalError::DivisionByZero)?) }
+					BinOp::Rem => { real_rem(&l, &r, tol) }
+					_ => {
+						let l = integer_operand(left, &l, tol, ctx.max_log2, scope)?;
+						let r = integer_operand(right, &r, tol, ctx.max_log2, scope)?;
+						Ok(Real::from(integer_op(op, &l, &r, ctx.max_log2)?))
+					}
 				}
 			}
 			Expr::Unary { op, operand } => {
-				let arg = operand.eval_internal(tol)?;
+				let arg = operand.eval_internal(tol, ctx, scope)?;
 				match *op {
 					UnaryOp::Neg => { Ok(-arg) }
 					UnaryOp::Pos => { Ok(arg) }
+					UnaryOp::Not => { Ok(Real::from(!integer_operand(operand, &arg, tol, ctx.max_log2, scope)?)) }
+					UnaryOp::Percent => { Ok(arg * Real::from(&Rational::ONE / 100)) }
 				}
 			}
 		}
 	}
-}
\ No newline at end of file
+}
+
+/// Parses a decimal number with an optional exponent, like "1.5e-3", exactly.
+/// Returns None if the exponent is too negative to represent the number, which then rounds to 0.
+fn parse_number(s: &str, max_log2: u64) -> Result<Option<Rational>, EvalError> {
+	let (mantissa, exponent) = s.split_once(['e', 'E']).unwrap_or((s, "0"));
+	let q = Rational::from_decimal_str(mantissa).map_err(|e| match e {
+		ParseRationalError::Empty => EvalError::ParseFloatError(ParseFloatError::Empty),
+		_ => EvalError::ParseFloatError(ParseFloatError::InvalidDigit),
+	})?;
+	if q.is_zero() {
+		return Ok(Some(q));
+	}
+
+	let is_negative = exponent.starts_with('-');
+	let scale = exponent
+		.parse::<i64>()
+		.ok()
+		.and_then(|exponent| BigUInt::from(10u32).checked_pow(exponent.unsigned_abs(), max_log2));
+	match scale {
+		Some(scale) if is_negative => Ok(Some(&q / &scale)),
+		Some(scale) => Ok(Some(&q * &scale)),
+		None if is_negative => Ok(None),
+		None => Err(EvalError::Overflow),
+	}
+}
+
+/// Evaluates a bitwise operation
+fn integer_op(op: &BinOp, l: &BigInt, r: &BigInt, max_log2: u64) -> Result<BigInt, EvalError> {
+	let shift = || u32::try_from(r).map_err(|_| EvalError::ArithmeticError);
+	match op {
+		BinOp::BitAnd => Ok(l & r),
+		BinOp::BitOr => Ok(l | r),
+		BinOp::BitXor => Ok(l ^ r),
+		BinOp::Shl => {
+			let shift = shift()?;
+			if !l.is_zero() && l.inner().ilog2() + shift as u64 >= max_log2 {
+				return Err(EvalError::Overflow);
+			}
+			Ok(l.clone() << shift)
+		}
+		BinOp::Shr => Ok(l.clone() >> shift()?),
+		_ => unreachable!("not an integer operation: {op}"),
+	}
+}
+
+/// Raises q to an integer power exactly, or returns None if that's not possible,
+/// in which case real_pow reports the error
+fn rational_pow(mut q: Rational, n: &BigInt, max_log2: u64) -> Option<Rational> {
+	q.reduce();
+	if q.is_zero() {
+		return (!n.is_negative()).then(|| if n.is_zero() { Rational::ONE } else { Rational::ZERO });
+	}
+	if q.numerator().inner().is_one() && q.denominator().is_one() {
+		// q is 1 or -1
+		let is_odd = n.inner().bit(0);
+		return Some(if q.is_negative() && is_odd { q } else { Rational::ONE });
+	}
+
+	q.checked_pow(i64::try_from(n).ok()?, max_log2)
+}
+
+/// Raises x to an integer power by repeated squaring
+fn real_pow(x: Real, n: &BigInt, tol: i64, max_log2: u64) -> Result<Real, EvalError> {
+	let mut base = if n.is_negative() {
+		Real::one().div(x, tol).map_err(|_| EvalError::DivisionByZero)?
+	} else {
+		x
+	};
+
+	// |base| < upper, which bounds the result from above
+	let upper = &base.eval(tol).abs() + &(BigFloat::ONE >> tol);
+	if upper >= BigFloat::ONE {
+		// The result is bounded by ceil(upper) to the n
+		let bound = upper.ceil_to_int();
+		let fits = u64::try_from(n.inner()).is_ok_and(|n| bound.inner().pow_fits(n, max_log2));
+		if !fits {
+			return Err(EvalError::Overflow);
+		}
+	} else {
+		// As ln(u) <= u - 1, the result is below e^(-n * (1 - upper)) < 2^(-n * (1 - upper)),
+		// so it rounds to 0 before the squarings get too small to compute
+		let shrink = &BigFloat::from(n.clone().abs()) * &(&BigFloat::ONE - &upper);
+		if shrink >= tol {
+			return Ok(Real::from(0));
+		}
+	}
+
+	let mut res = Real::one();
+	let mut n = n.inner().clone();
+	while !n.is_zero() {
+		if n.bit(0) {
+			res *= base.clone();
+		}
+		n >>= 1u32;
+		if !n.is_zero() {
+			base = base.clone() * base;
+		}
+	}
+	Ok(res)
+}
+
+/// Remainder of truncated division, which has the sign of l
+fn rational_rem(l: &Rational, r: &Rational) -> Rational {
+	// l % r = (l.n * r.d) % (r.n * l.d) / (l.d * r.d)
+	let mut n = l.numerator() * r.denominator();
+	let mut d = r.numerator() * l.denominator();
+	let (_, rem) = (&mut n).div_rem(&mut d);
+	Rational::new(rem, l.denominator() * r.denominator())
+}
+
+/// Remainder of truncated division. The remainder jumps by |r| where l / r is an integer,
+/// so results that close to a jump may land on either side.
+fn real_rem(l: &Real, r: &Real, tol: i64) -> Result<Real, EvalError> {
+	let r_round = r.eval(tol);
+	if r_round.is_zero() {
+		return Err(EvalError::DivisionByZero);
+	}
+	// The error of r is multiplied by the quotient, which is less than 2^q_log2
+	let q_log2 = l.eval(0).checked_ilog2().unwrap_or(0) - r_round.ilog2() + 2;
+	let prec = tol + 2 + i64::max(q_log2, 0);
+	let r = r.eval(prec);
+	if r.is_zero() {
+		return Err(EvalError::DivisionByZero);
+	}
+	Ok(Real::from(l.eval(prec).rem(&r)))
+}
+
+/// Checks the number of arguments of the functions taking a fixed number of them,
+/// sum, prod and unknown functions are checked where they are evaluated
+fn check_arity(name: &str, args: &[Expr]) -> Result<(), EvalError> {
+	let arity = match name {
+		"ln" | "exp" | "sqrt" | "cbrt" => 1,
+		"root" | "pow" => 2,
+		_ => return Ok(()),
+	};
+	if args.len() != arity {
+		return Err(EvalError::InvalidFnCall(name.to_string()));
+	}
+	Ok(())
+}
+
+/// Remembers the most precise value of x computed so far. Requests for a lower precision are
+/// answered by rounding it, which stays within 2^-prec as it was within 2^-(prec + 1).
+fn memoize(x: Real) -> Real {
+	let cache: Rc<RefCell<Option<(i64, BigFloat)>>> = Rc::default();
+	Real::new(move |prec| {
+		if let Some((cached_prec, value)) = &*cache.borrow()
+			&& *cached_prec >= prec
+		{
+			let mut value = value.clone();
+			value.round_to_precision(prec);
+			return value;
+		}
+		let value = x.eval(prec);
+		*cache.borrow_mut() = Some((prec, value.clone()));
+		value
+	})
+}
+
+/// The bound variable and body of sum(var, lo, hi, body) or prod(var, lo, hi, body)
+fn range_var_and_body<'e, 'a>(name: &str, args: &'e [Expr<'a>]) -> Result<(&'a str, &'e Expr<'a>), EvalError> {
+	match args {
+		[Expr::Const(var), _, _, body] => Ok((var, body)),
+		_ => Err(EvalError::InvalidFnCall(name.to_string())),
+	}
+}
+
+/// Number of integers from lo to hi inclusive, which is 0 if hi < lo
+fn range_len(lo: &BigInt, hi: &BigInt) -> Result<u64, EvalError> {
+	if hi < lo {
+		return Ok(0);
+	}
+	match u64::try_from(&(hi.clone() - lo)) {
+		Ok(n) if n < MAX_RANGE_TERMS => Ok(n + 1),
+		_ => Err(EvalError::TooManyTerms),
+	}
+}
+
+/// Combines the terms pairwise, so that the depth of the result and the precision
+/// it asks of the terms grow with the log of the number of terms instead of linearly
+fn fold_balanced(mut terms: Vec<Real>, empty: Real, op: fn(Real, Real) -> Real) -> Real {
+	while terms.len() > 1 {
+		let mut iter = terms.into_iter();
+		let mut next = Vec::new();
+		while let Some(a) = iter.next() {
+			next.push(match iter.next() {
+				Some(b) => op(a, b),
+				None => a,
+			});
+		}
+		terms = next;
+	}
+	terms.pop().unwrap_or(empty)
+}
+
+/// Rounds q to a multiple of 2^-prec, as exact quotients like 2^-100000 could otherwise
+/// have far more digits than requested
+fn rational_to_float(q: &Rational, prec: i64) -> BigFloat {
+	let mut x = q.to_float(prec + 1);
+	x.round_to_precision(prec);
+	x
+}
+
+fn rational_to_integer(mut q: Rational) -> Option<BigInt> {
+	q.reduce();
+	q.denominator().is_one().then(|| q.numerator().clone())
+}
+
+/// The integer within 2^-tol of x. x is evaluated one bit more precisely,
+/// so that approximations of integers like sqrt(4) = 1.99...9 qualify.
+fn real_to_integer(x: &Real, tol: i64) -> Result<BigInt, EvalError> {
+	let x = x.eval(tol + 1);
+	if !x.is_close_to_integer(tol) {
+		return Err(EvalError::NonIntegerOperand);
+	}
+	Ok(x.round_to_int())
+}
+
+/// The integer value of expr, which evaluated to x. If expr has an exact rational value,
+/// that has to be an integer, as only approximations are rounded by real_to_integer.
+fn integer_operand(expr: &Expr, x: &Real, tol: i64, max_log2: u64, scope: Scope) -> Result<BigInt, EvalError> {
+	match expr.eval_exact(max_log2, scope) {
+		Some(q) => rational_to_integer(q).ok_or(EvalError::NonIntegerOperand),
+		None => real_to_integer(x, tol),
+	}
+}
+
+fn root_degree(expr: &Expr, n: &Real, tol: i64, max_log2: u64, scope: Scope) -> Result<u32, EvalError> {
+	let n = integer_operand(expr, n, tol, max_log2, scope).map_err(|_| EvalError::InvalidRootDegree)?;
+	match u32::try_from(&n) {
+		Ok(n) if (1..=MAX_ROOT_DEGREE).contains(&n) => Ok(n),
+		_ => Err(EvalError::InvalidRootDegree),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::lexer::Lexer;
+	use crate::parser::{ParseError, Parser};
+
+	fn evaluate(input: &str, prec: i64) -> Evaluation {
+		let tokens: Vec<_> = Lexer::new(input).collect();
+		let expr = Parser::new(&tokens).parse().unwrap();
+		expr.evaluate(prec).unwrap()
+	}
+
+	#[test]
+	fn test_evaluate_rational() {
+		let res = evaluate("1/3", 128);
+		let third = Rational::new(BigInt::from(1), BigUInt::from(3u32));
+		assert_eq!(res.exact, Some(third.clone()));
+
+		let delta = (&res.approx.to_rational() - &third).abs();
+		let epsilon = Rational::new(BigInt::ONE, BigUInt::ONE << 128);
+		assert!(delta < epsilon);
+	}
+
+	#[test]
+	fn test_evaluate_decimal() {
+		let res = evaluate("-(0.5 + 1.25) * 2", 64);
+		assert_eq!(res.exact, Some(&Rational::from(-7) / 2));
+		assert_eq!(res.approx, BigFloat::try_from(-3.5).unwrap());
+	}
+
+	#[test]
+	fn test_evaluate_unicode_operators() {
+		assert_eq!(evaluate("6÷2", 64).exact, Some(Rational::from(3)));
+		assert_eq!(evaluate("3×4", 64).exact, Some(Rational::from(12)));
+		assert_eq!(evaluate("1 + 2×3 ÷ 4", 64).exact, Some(&Rational::from(5) / 2));
+		assert_eq!(evaluate("2 × 3 * 4 ÷ 6 / 2", 64).exact, Some(Rational::from(2)));
+		assert_eq!(evaluate("sqrt(8×2)", 64).approx, BigFloat::from(4));
+	}
+
+	#[test]
+	fn test_evaluate_exponent() {
+		assert_eq!(evaluate(".5e3", 64).exact, Some(Rational::from(500)));
+		assert_eq!(evaluate(".5e3 - 500", 64).exact, Some(Rational::ZERO));
+		assert_eq!(evaluate("1.25E-2", 64).exact, Some(&Rational::from(1) / 80));
+		assert_eq!(evaluate("-2e+3 * 3", 64).exact, Some(Rational::from(-6000)));
+		assert_eq!(evaluate("sqrt(.5e3 * 2e-1)", 64).approx, BigFloat::from(10));
+
+		let tokens: Vec<_> = Lexer::new("1e99999999999").collect();
+		assert_eq!(Parser::new(&tokens).parse().unwrap().evaluate(64).unwrap_err(), EvalError::Overflow);
+		// Numbers too small to represent exactly round to 0
+		let res = evaluate("1e-100000000", 64);
+		assert_eq!(res.exact, None);
+		assert_eq!(res.approx, BigFloat::ZERO);
+		assert_eq!(evaluate("1e-99999999999999999999 + 1", 64).approx, BigFloat::ONE);
+		assert_eq!(evaluate("0e-100000000", 64).exact, Some(Rational::ZERO));
+
+		let tokens: Vec<_> = Lexer::new(".e3").collect();
+		assert_eq!(Parser::new(&tokens).parse(), Err(ParseError::InvalidExpression));
+	}
+
+	#[test]
+	fn test_evaluate_irrational() {
+		let res = evaluate("sqrt(2)", 128);
+		assert_eq!(res.exact, None);
+
+		let delta = (&res.approx.mul_with_precision(&res.approx, 256) - 2u32).abs();
+		assert!(delta < (BigFloat::ONE >> 120));
+	}
+
+	#[test]
+	fn test_evaluate_bitwise() {
+		assert_eq!(evaluate("6 & 3", 64).exact, Some(Rational::from(2)));
+		assert_eq!(evaluate("6 | 3", 64).exact, Some(Rational::from(7)));
+		assert_eq!(evaluate("6 ^ 3", 64).exact, Some(Rational::from(5)));
+		assert_eq!(evaluate("1 << 10", 64).exact, Some(Rational::from(1024)));
+		assert_eq!(evaluate("-7 >> 1", 64).exact, Some(Rational::from(-4)));
+		assert_eq!(evaluate("~0", 64).exact, Some(Rational::from(-1)));
+		assert_eq!(evaluate("3/2 * 2 & 7", 64).exact, Some(Rational::from(3)));
+
+		// Integer operands that are not exact rationals
+		let res = evaluate("sqrt(4) * 0 | 5", 64);
+		assert_eq!(res.exact, None);
+		assert_eq!(res.approx, BigFloat::from(5));
+		assert_eq!(evaluate("sqrt(4) | 1", 64).approx, BigFloat::from(3));
+		assert_eq!(evaluate("~cbrt(-27)", 64).approx, BigFloat::from(2));
+		assert_eq!(evaluate("pow(sqrt(2), 6) << 1", 64).approx, BigFloat::from(16));
+	}
+
+	#[test]
+	fn test_evaluate_bitwise_errors() {
+		let eval = |input| {
+			let tokens: Vec<_> = Lexer::new(input).collect();
+			Parser::new(&tokens).parse().unwrap().evaluate(64)
+		};
+
+		assert_eq!(eval("1.5 & 1").unwrap_err(), EvalError::NonIntegerOperand);
+		assert_eq!(eval("~0.5").unwrap_err(), EvalError::NonIntegerOperand);
+		assert_eq!(eval("sqrt(2) | 1").unwrap_err(), EvalError::NonIntegerOperand);
+		// Exact operands are not rounded, however close they are to an integer
+		assert_eq!(eval("0.000000000000000000000000000001 | 1").unwrap_err(), EvalError::NonIntegerOperand);
+		assert_eq!(eval("1.00000000000000000001 & 3").unwrap_err(), EvalError::NonIntegerOperand);
+		assert_eq!(eval("1 << -1").unwrap_err(), EvalError::ArithmeticError);
+		assert_eq!(eval("1 << 100000000").unwrap_err(), EvalError::Overflow);
+	}
+
+	#[test]
+	fn test_evaluate_value() {
+		let value = |input| {
+			let tokens: Vec<_> = Lexer::new(input).collect();
+			Parser::new(&tokens).parse().unwrap().evaluate_value(64).unwrap()
+		};
+
+		let res = value("1/3 + 1/6");
+		assert_eq!(res, Value::Rational(&Rational::from(1) / 2));
+		assert_eq!(res.to_string(), "1/2");
+		assert_eq!(value("2/4 * 6").to_string(), "3");
+		assert_eq!(value("-(1/3 - 1/2)").to_string(), "1/6");
+		assert_eq!(value("1/3 + 1/6").to_float(64), BigFloat::try_from(0.5).unwrap());
+
+		let res = value("sqrt(2)");
+		assert!(matches!(res, Value::Float(_)));
+		assert!(res.to_string().starts_with("1.41421356"));
+		// Exact operands combined with a float give a float
+		assert!(matches!(value("1/3 + sqrt(2)"), Value::Float(_)));
+		assert!(matches!(value("sqrt(4)"), Value::Float(_)));
+		assert!(matches!(value("pi * 0"), Value::Float(_)));
+	}
+
+	fn assert_close(res: &Evaluation, expected: &BigFloat, prec: i64) {
+		let delta = (&res.approx - expected).abs();
+		assert!(delta < (BigFloat::ONE >> prec), "{} != {expected}", res.approx);
+	}
+
+	#[test]
+	fn test_evaluate_nested_fn_calls() {
+		let prec = 1024;
+
+		let res = evaluate("exp(ln(3))", prec);
+		assert_eq!(res.exact, None);
+		assert_close(&res, &BigFloat::from(3), prec);
+		assert_close(&evaluate("ln(sqrt(exp(6)))", prec), &BigFloat::from(3), prec);
+		assert_close(&evaluate("ln(exp(ln(exp(1))))", prec), &BigFloat::ONE, prec);
+
+		let pi = BigFloat::pi(prec + 16);
+		assert_close(&evaluate("exp(ln(pi))", prec), &pi, prec);
+	}
+
+	#[test]
+	fn test_evaluate_roots() {
+		let prec = 256;
+		assert_close(&evaluate("cbrt(27)", prec), &BigFloat::from(3), prec);
+		assert_close(&evaluate("root(16, 4)", prec), &BigFloat::from(2), prec);
+		assert_close(&evaluate("cbrt(-8)", prec), &BigFloat::from(-2), prec);
+		assert_close(&evaluate("root(-32, 5)", prec), &BigFloat::from(-2), prec);
+		assert_close(&evaluate("root(2, 1)", prec), &BigFloat::from(2), prec);
+		assert_close(&evaluate("root(9, sqrt(4))", prec), &BigFloat::from(3), prec);
+	}
+
+	#[test]
+	fn test_evaluate_root_errors() {
+		let eval = |input| {
+			let tokens: Vec<_> = Lexer::new(input).collect();
+			Parser::new(&tokens).parse().unwrap().evaluate(64)
+		};
+
+		assert_eq!(eval("root(-16, 4)").unwrap_err(), EvalError::ArithmeticError);
+		assert_eq!(eval("root(16, 0)").unwrap_err(), EvalError::InvalidRootDegree);
+		assert_eq!(eval("root(16, -2)").unwrap_err(), EvalError::InvalidRootDegree);
+		assert_eq!(eval("root(16, 1.5)").unwrap_err(), EvalError::InvalidRootDegree);
+		assert_eq!(eval("root(16, 2.000000000000000000000000000001)").unwrap_err(), EvalError::InvalidRootDegree);
+		assert_eq!(eval("root(2, 1001)").unwrap_err(), EvalError::InvalidRootDegree);
+		assert_eq!(eval("root(2, 4000000000)").unwrap_err(), EvalError::InvalidRootDegree);
+		assert!(eval("root(2, 1000)").is_ok());
+	}
+
+	#[test]
+	fn test_evaluate_pow() {
+		assert_eq!(evaluate("pow(2, 10)", 64).exact, Some(Rational::from(1024)));
+		assert_eq!(evaluate("pow(-2/3, 3)", 64).exact, Some(&Rational::from(-8) / 27));
+		assert_eq!(evaluate("pow(2, -2)", 64).exact, Some(&Rational::ONE / 4));
+		assert_eq!(evaluate("pow(0, 0)", 64).exact, Some(Rational::ONE));
+		assert_eq!(evaluate("pow(-1, 100000000001)", 64).exact, Some(Rational::from(-1)));
+		assert_eq!(evaluate("pow(10, 1000)", 64).exact, Some(Rational::from(BigInt::from(10).pow(1000))));
+
+		let prec = 64;
+		assert_close(&evaluate("pow(sqrt(2), 2)", prec), &BigFloat::from(2), prec);
+		assert_close(&evaluate("pow(sqrt(2), -4)", prec), &BigFloat::ONE.div(&BigFloat::from(4), prec), prec);
+		assert_close(&evaluate("pow(pi, 0)", prec), &BigFloat::ONE, prec);
+		let expected = BigFloat::from(3).sqrt(prec + 64).powi_with_precision(37, prec + 64);
+		assert_close(&evaluate("pow(sqrt(3), 37)", prec), &expected, prec);
+
+		// Powers of small bases round to 0 once they are below 2^-prec
+		assert_close(&evaluate("pow(sqrt(2) / 2, 40)", prec), &(BigFloat::ONE >> 20u32), prec);
+		assert_eq!(evaluate("pow(sqrt(2) / 2, 100000000)", prec).approx, BigFloat::ZERO);
+		let res = evaluate("pow(0.5, 1000000000000000000)", prec);
+		assert_eq!(res.exact, None);
+		assert_eq!(res.approx, BigFloat::ZERO);
+		assert_eq!(evaluate("pow(-2, -1000000000000000001)", prec).approx, BigFloat::ZERO);
+		// Exact results are rounded to the precision as well
+		assert_eq!(evaluate("pow(0.5, 100)", prec).approx, BigFloat::ZERO);
+	}
+
+	#[test]
+	fn test_evaluate_pow_errors() {
+		let eval = |input, max_log2| {
+			let tokens: Vec<_> = Lexer::new(input).collect();
+			Parser::new(&tokens).parse().unwrap().evaluate_with_max_log2(64, max_log2)
+		};
+
+		assert_eq!(eval("pow(10, 100000000)", DEFAULT_MAX_LOG2).unwrap_err(), EvalError::Overflow);
+		assert_eq!(eval("pow(1/10, -100000000)", DEFAULT_MAX_LOG2).unwrap_err(), EvalError::Overflow);
+		assert_eq!(eval("pow(sqrt(2), 1000000000)", DEFAULT_MAX_LOG2).unwrap_err(), EvalError::Overflow);
+		assert_eq!(eval("pow(3, 100000000000000000000000)", DEFAULT_MAX_LOG2).unwrap_err(), EvalError::Overflow);
+		assert_eq!(eval("pow(0, -1)", DEFAULT_MAX_LOG2).unwrap_err(), EvalError::DivisionByZero);
+		assert_eq!(eval("pow(2, 1/2)", DEFAULT_MAX_LOG2).unwrap_err(), EvalError::InvalidExponent);
+		assert_eq!(eval("pow(2, pi)", DEFAULT_MAX_LOG2).unwrap_err(), EvalError::InvalidExponent);
+		assert_eq!(eval("pow(2, 1 + 1e-30)", DEFAULT_MAX_LOG2).unwrap_err(), EvalError::InvalidExponent);
+		assert_eq!(eval("pow(sqrt(2), 1 + 1e-30)", DEFAULT_MAX_LOG2).unwrap_err(), EvalError::InvalidExponent);
+
+		// The guard is configurable, and applies to the other operations as well
+		assert!(eval("pow(10, 1000)", 5000).is_ok());
+		assert_eq!(eval("pow(10, 1000)", 1000).unwrap_err(), EvalError::Overflow);
+		// Same bound as BigUInt::checked_pow: 10 has 4 bits
+		assert!(eval("pow(10, 20)", 80).is_ok());
+		assert_eq!(eval("pow(10, 20)", 79).unwrap_err(), EvalError::Overflow);
+		assert_eq!(eval("pow(sqrt(10), 1000)", 1000).unwrap_err(), EvalError::Overflow);
+		assert_eq!(eval("1 << 2000", 1000).unwrap_err(), EvalError::Overflow);
+		assert_eq!(eval("1e400", 1000).unwrap_err(), EvalError::Overflow);
+		assert_eq!(eval("exp(1000)", 1000).unwrap_err(), EvalError::Overflow);
+		assert!(eval("exp(100) + 1e200", 1000).is_ok());
+	}
+
+	#[test]
+	fn test_evaluate_rem() {
+		assert_eq!(evaluate("5.5 % 2", 64).exact, Some(&Rational::from(3) / 2));
+		assert_eq!(evaluate("-5.5 % 2", 64).exact, Some(&Rational::from(-3) / 2));
+		assert_eq!(evaluate("5.5 % -2", 64).exact, Some(&Rational::from(3) / 2));
+		assert_eq!(evaluate("7 % -3", 64).exact, Some(Rational::from(1)));
+		assert_eq!(evaluate("7 % 3", 64).exact, Some(Rational::from(1)));
+		assert_eq!(evaluate("1/3 % 1/4", 64).exact, Some(&Rational::from(1) / 12));
+		assert_eq!(evaluate("2 * 7 % 4", 64).exact, Some(Rational::from(2)));
+
+		let prec = 256;
+		let res = evaluate("pi % 1", prec);
+		assert_eq!(res.exact, None);
+		let pi = BigFloat::pi(prec + 16);
+		assert_close(&res, &(&pi - &BigFloat::from(3)), prec);
+		assert_close(&evaluate("-pi % 2", prec), &(&BigFloat::from(2) - &pi), prec);
+		assert_close(&evaluate("1000 % sqrt(2)", prec), &BigFloat::from(1000).rem(&BigFloat::from(2).sqrt(prec + 32)), prec);
+
+		let tokens: Vec<_> = Lexer::new("1 % 0").collect();
+		assert_eq!(Parser::new(&tokens).parse().unwrap().evaluate(64).unwrap_err(), EvalError::DivisionByZero);
+	}
+
+	#[test]
+	fn test_evaluate_percent() {
+		assert_eq!(evaluate("50%", 64).exact, Some(&Rational::from(1) / 2));
+		assert_eq!(evaluate("200% * 3", 64).exact, Some(Rational::from(6)));
+		assert_eq!(evaluate("(12.5%) + 1", 64).exact, Some(&Rational::from(9) / 8));
+		// A sign followed by an operand after '%' starts the right operand of a modulo
+		assert_eq!(evaluate("12.5% + 1", 64).exact, Some(&Rational::from(1) / 2));
+		assert_eq!(evaluate("7 % 3", 64).exact, Some(Rational::from(1)));
+
+		let prec = 256;
+		let res = evaluate("pi * 50%", prec);
+		assert_eq!(res.exact, None);
+		assert_close(&res, &(BigFloat::pi(prec + 16) >> 1u32), prec);
+	}
+
+	#[test]
+	fn test_evaluate_sum_prod() {
+		assert_eq!(evaluate("sum(k, 1, 10, k)", 64).exact, Some(Rational::from(55)));
+		assert_eq!(evaluate("prod(k, 1, 5, k)", 64).exact, Some(Rational::from(120)));
+		assert_eq!(evaluate("sum(k, 1, 4, 1/k)", 64).exact, Some(&Rational::from(25) / 12));
+		assert_eq!(evaluate("sum(k, -3, 3, k * k)", 64).exact, Some(Rational::from(28)));
+		assert_eq!(evaluate("sum(k, 2 - 1, 2 * 5, k)", 64).exact, Some(Rational::from(55)));
+		// Empty ranges
+		assert_eq!(evaluate("sum(k, 5, 1, k)", 64).exact, Some(Rational::ZERO));
+		assert_eq!(evaluate("prod(k, 5, 1, k)", 64).exact, Some(Rational::ONE));
+		// Partial sums and products of zero
+		assert_eq!(evaluate("sum(k, 0, 0, k)", 64).exact, Some(Rational::ZERO));
+		assert_eq!(evaluate("sum(k, -1, 1, k)", 64).exact, Some(Rational::ZERO));
+		assert_eq!(evaluate("prod(k, 0, 3, k)", 64).exact, Some(Rational::ZERO));
+		// Nesting, where inner bounds and bodies see the outer variables
+		assert_eq!(evaluate("sum(i, 1, 3, prod(j, 1, i, j))", 64).exact, Some(Rational::from(9)));
+		assert_eq!(evaluate("sum(i, 1, 4, sum(j, 1, i, 1))", 64).exact, Some(Rational::from(10)));
+		// The innermost binding wins, also over constants
+		assert_eq!(evaluate("sum(k, 1, 3, sum(k, 1, 2, k))", 64).exact, Some(Rational::from(9)));
+		assert_eq!(evaluate("sum(pi, 1, 3, pi)", 64).exact, Some(Rational::from(6)));
+
+		let prec = 256;
+		let res = evaluate("sum(k, 1, 4, sqrt(k))", prec);
+		assert_eq!(res.exact, None);
+		let expected = (1..=4).map(|k| BigFloat::from(k).sqrt(prec + 16)).fold(BigFloat::ZERO, |a, b| &a + &b);
+		assert_close(&res, &expected, prec);
+		let pi = BigFloat::pi(prec + 16);
+		assert_close(&evaluate("prod(k, 1, 3, pi)", prec), &(&(&pi * &pi) * &pi), prec);
+		assert_close(&evaluate("prod(k, 1, 3, pi + k)", prec), &(&(&(&pi + 1) * &(&pi + 2)) * &(&pi + 3)), prec);
+		let res = evaluate("sum(k, 1, 1000, pi / 1000)", prec);
+		assert_close(&res, &pi, prec);
+	}
+
+	#[test]
+	fn test_evaluate_sum_prod_errors() {
+		let eval = |input, max_log2| {
+			let tokens: Vec<_> = Lexer::new(input).collect();
+			Parser::new(&tokens).parse().unwrap().evaluate_with_max_log2(64, max_log2)
+		};
+
+		assert_eq!(eval("sum(k, 1.5, 3, k)", DEFAULT_MAX_LOG2).unwrap_err(), EvalError::InvalidRangeBound);
+		assert_eq!(eval("prod(k, 1, pi, k)", DEFAULT_MAX_LOG2).unwrap_err(), EvalError::InvalidRangeBound);
+		assert_eq!(eval("sum(k, 1, 3 + 1e-30, k)", DEFAULT_MAX_LOG2).unwrap_err(), EvalError::InvalidRangeBound);
+		assert_eq!(eval("sum(1, 1, 3, 1)", DEFAULT_MAX_LOG2).unwrap_err(), EvalError::InvalidFnCall("sum".to_string()));
+		assert_eq!(eval("prod(k, 1, 3)", DEFAULT_MAX_LOG2).unwrap_err(), EvalError::InvalidFnCall("prod".to_string()));
+		assert_eq!(eval("sum(k, 1, 1000000, k)", DEFAULT_MAX_LOG2).unwrap_err(), EvalError::TooManyTerms);
+		assert_eq!(eval("sum(k, 1, 1e30, k)", DEFAULT_MAX_LOG2).unwrap_err(), EvalError::TooManyTerms);
+		assert_eq!(eval("sum(k, 1, 3, x)", DEFAULT_MAX_LOG2).unwrap_err(), EvalError::InvalidConst("x".to_string()));
+		// The variable is only bound inside the body
+		assert_eq!(eval("sum(k, 1, 3, k) + k", DEFAULT_MAX_LOG2).unwrap_err(), EvalError::InvalidConst("k".to_string()));
+		assert_eq!(eval("sum(k, 1, 3, 1/(k - 2))", DEFAULT_MAX_LOG2).unwrap_err(), EvalError::DivisionByZero);
+
+		// 1000! has more than 8000 bits
+		assert!(eval("prod(k, 1, 100, k)", 1000).is_ok());
+		assert_eq!(eval("prod(k, 1, 1000, k)", 1000).unwrap_err(), EvalError::Overflow);
+		assert_eq!(eval("prod(k, 1, 1000, k + sqrt(2))", 1000).unwrap_err(), EvalError::Overflow);
+	}
+
+	fn trace(input: &str, prec: i64) -> Result<(BigFloat, Vec<String>), EvalError> {
+		let tokens: Vec<_> = Lexer::new(input).collect();
+		let expr = Parser::new(&tokens).parse().unwrap();
+		let (evaluation, steps) = expr.eval_traced(prec)?;
+		let expected = expr.evaluate(prec)?;
+		assert_eq!(evaluation.exact, expected.exact);
+		assert_eq!(evaluation.approx, expected.approx);
+		Ok((evaluation.approx, steps.iter().map(|step| step.to_string()).collect()))
+	}
+
+	#[test]
+	fn test_eval_traced() {
+		let (value, steps) = trace("2 + 3 * 4", 64).unwrap();
+		assert_eq!(value, BigFloat::from(14));
+		assert_eq!(steps, ["(3 * 4) = 12", "(2 + (3 * 4)) = 14"]);
+
+		let (_, steps) = trace("sqrt(16) - -1", 64).unwrap();
+		assert_eq!(steps, ["sqrt(16) = 4", "(-1) = -1", "(sqrt(16) - (-1)) = 5"]);
+		let (_, steps) = trace("(1 + 2) * (3 + 4)", 64).unwrap();
+		assert_eq!(steps, ["(1 + 2) = 3", "(3 + 4) = 7", "((1 + 2) * (3 + 4)) = 21"]);
+		// Only the bounds of sum are traced, not its body
+		let (_, steps) = trace("sum(k, 1, 1 + 2, k * k)", 64).unwrap();
+		assert_eq!(steps, ["(1 + 2) = 3", "sum(k, 1, (1 + 2), (k * k)) = 14"]);
+
+		// Numbers and constants are not steps
+		let (value, steps) = trace("7", 64).unwrap();
+		assert_eq!(value, BigFloat::from(7));
+		assert!(steps.is_empty());
+		let (value, steps) = trace("pi", 64).unwrap();
+		assert_eq!(value, BigFloat::pi(64));
+		assert!(steps.is_empty());
+
+		// Inner steps are rounded from the values computed for the outer ones
+		let prec = 128;
+		let tokens: Vec<_> = Lexer::new("sqrt(exp(ln(3)) * 3)").collect();
+		let expr = Parser::new(&tokens).parse().unwrap();
+		let (evaluation, steps) = expr.eval_traced(prec).unwrap();
+		let three = BigFloat::from(3);
+		let expected = [three.ln(prec + 8), three.clone(), BigFloat::from(9), three.clone()];
+		assert_eq!(steps.len(), expected.len());
+		for (step, expected) in steps.iter().zip(&expected) {
+			assert!((&step.value - expected).abs() < (BigFloat::ONE >> prec), "{step}");
+		}
+		assert_eq!(evaluation.exact, None);
+		assert_eq!(evaluation.approx, steps[3].value);
+
+		assert_eq!(trace("2 + 1 / 0", 64).unwrap_err(), EvalError::DivisionByZero);
+		assert_eq!(trace("1 + x", 64).unwrap_err(), EvalError::InvalidConst("x".to_string()));
+	}
+}
@@ -1,6 +1,24 @@
+use std::iter::FusedIterator;
 
+/// Splits the input into tokens, followed by a single Token::Eof
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct Lexer<'a>(pub &'a str);
+pub struct Lexer<'a> {
+	src: &'a str,
+	finished: bool,
+	len: usize,
+	token_start: usize,
+}
+
+impl<'a> Lexer<'a> {
+	pub fn new(src: &'a str) -> Self {
+		Self { src, finished: false, len: src.len(), token_start: 0 }
+	}
+
+	/// Byte offset of the token last returned by next
+	pub fn offset(&self) -> usize {
+		self.token_start
+	}
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Token<'a> {
@@ -10,10 +28,20 @@ pub enum Token<'a> {
 	Minus,
 	Multiply,
 	Divide,
+	Percent,
 	LParen,
 	RParen,
 	Comma,
-	Error
+	BitAnd,
+	BitOr,
+	BitXor,
+	BitNot,
+	ShiftLeft,
+	ShiftRight,
+	/// An invalid character, or dots without digits, holding the offending text
+	Error(&'a str),
+	/// End of input
+	Eof,
 }
 
 impl<'a> Iterator for Lexer<'a> {
@@ -21,10 +49,17 @@ impl<'a> Iterator for Lexer<'a> {
 
 	fn next(&mut self) -> Option<Self::Item> {
 		let (c, rest) = loop {
-			let (c, rest) = split_first_char(self.0)?;
+			self.token_start = self.len - self.src.len();
+			let Some((c, rest)) = split_first_char(self.src) else {
+				if self.finished {
+					return None;
+				}
+				self.finished = true;
+				return Some(Token::Eof);
+			};
 
 			if c.is_whitespace() {
-				self.0 = rest;
+				self.src = rest;
 				continue;
 			}
 
@@ -32,74 +67,111 @@ impl<'a> Iterator for Lexer<'a> {
 		};
 
 		if c == '+' {
-			self.0 = rest;
+			self.src = rest;
 			return Some(Token::Plus);
 		}
 		if c == '-' {
-			self.0 = rest;
+			self.src = rest;
 			return Some(Token::Minus);
 		}
-		if c == '*' {
-			self.0 = rest;
+		if c == '*' || c == '×' {
+			self.src = rest;
 			return Some(Token::Multiply);
 		}
-		if c == '/' {
-			self.0 = rest;
+		if c == '/' || c == '÷' {
+			self.src = rest;
 			return Some(Token::Divide);
 		}
+		if c == '%' {
+			self.src = rest;
+			return Some(Token::Percent);
+		}
 		if c == '(' {
-			self.0 = rest;
+			self.src = rest;
 			return Some(Token::LParen);
 		}
 		if c == ')' {
-			self.0 = rest;
+			self.src = rest;
 			return Some(Token::RParen);
 		}
 		if c == ',' {
-			self.0 = rest;
+			self.src = rest;
 			return Some(Token::Comma);
 		}
+		if c == '&' {
+			self.src = rest;
+			return Some(Token::BitAnd);
+		}
+		if c == '|' {
+			self.src = rest;
+			return Some(Token::BitOr);
+		}
+		if c == '^' {
+			self.src = rest;
+			return Some(Token::BitXor);
+		}
+		if c == '~' {
+			self.src = rest;
+			return Some(Token::BitNot);
+		}
+		if (c == '<' || c == '>')
+			&& let Some((next, rest)) = split_first_char(rest)
+			&& next == c
+		{
+			self.src = rest;
+			return Some(if c == '<' { Token::ShiftLeft } else { Token::ShiftRight });
+		}
 
 		if c.is_digit(10) || c == '.' {
-			let mut iter = self.0.char_indices();
-			loop {
-				let Some((i, next)) = iter.next() else {
-					let res = self.0;
-					self.0 = "";
-					return Some(Token::Number(res));
-				};
+			let mantissa_len = self.src.find(|c: char| !c.is_digit(10) && c != '.').unwrap_or(self.src.len());
+			let mut len = mantissa_len;
 
-				if next.is_digit(10) || next == '.' {
-					continue;
+			// The exponent only belongs to the number if it has digits, "2e" is "2" followed by "e"
+			let tail = &self.src[mantissa_len..];
+			if let Some(exponent) = tail.strip_prefix(['e', 'E']) {
+				let digits = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+				let digits_len = digits.find(|c: char| !c.is_digit(10)).unwrap_or(digits.len());
+				if digits_len > 0 {
+					len += tail.len() - digits.len() + digits_len;
 				}
-				let res = self.0.get(..i).unwrap();
-				self.0 = self.0.get(i..).unwrap();
-				return Some(Token::Number(res));
 			}
+
+			let (res, rest) = self.src.split_at(len);
+			self.src = rest;
+			// Dots alone, like "." or ".e3", aren't a number
+			if !res[..mantissa_len].contains(|c: char| c.is_digit(10)) {
+				return Some(Token::Error(res));
+			}
+			return Some(Token::Number(res));
 		}
 
 		if c.is_alphabetic() {
-			let mut iter = self.0.char_indices();
+			let mut iter = self.src.char_indices();
 			loop {
 				let Some((i, next)) = iter.next() else {
-					let res = self.0;
-					self.0 = "";
+					let res = self.src;
+					self.src = "";
 					return Some(Token::Identifier(res));
 				};
 
 				if next.is_alphanumeric() {
 					continue;
 				}
-				let res = self.0.get(..i).unwrap();
-				self.0 = self.0.get(i..).unwrap();
+				let res = self.src.get(..i).unwrap();
+				self.src = self.src.get(i..).unwrap();
 				return Some(Token::Identifier(res));
 			}
 		}
 
-		Some(Token::Error)
+		// Skip the invalid character, so that lexing continues after it
+		let res = &self.src[..c.len_utf8()];
+		self.src = rest;
+		Some(Token::Error(res))
 	}
 }
 
+impl FusedIterator for Lexer<'_> {}
+
 fn split_first_char(s: &str) -> Option<(char, &str)> {
 	let mut chars = s.chars();
 	let first = chars.next()?;
@@ -114,40 +186,112 @@ mod tests {
 	#[test]
 	fn test_numbers() {
 		let input = "123 45.67 .89";
-		let mut lexer = Lexer(input);
+		let mut lexer = Lexer::new(input);
 		assert!(matches!(lexer.next(), Some(Token::Number("123"))));
 		assert!(matches!(lexer.next(), Some(Token::Number("45.67"))));
 		assert!(matches!(lexer.next(), Some(Token::Number(".89"))));
+		assert!(matches!(lexer.next(), Some(Token::Eof)));
 		assert!(matches!(lexer.next(), None));
 	}
 
+	#[test]
+	fn test_exponents() {
+		let tokens: Vec<_> = Lexer::new(".5e3 1E-7 2.5e+10 3e").collect();
+		assert_eq!(tokens, [
+			Token::Number(".5e3"),
+			Token::Number("1E-7"),
+			Token::Number("2.5e+10"),
+			Token::Number("3"),
+			Token::Identifier("e"),
+			Token::Eof,
+		]);
+
+		let tokens: Vec<_> = Lexer::new("2e-x").collect();
+		assert_eq!(tokens, [Token::Number("2"), Token::Identifier("e"), Token::Minus, Token::Identifier("x"), Token::Eof]);
+
+		// No digits before the exponent
+		let tokens: Vec<_> = Lexer::new(".e3 + .").collect();
+		assert_eq!(tokens, [Token::Error(".e3"), Token::Plus, Token::Error("."), Token::Eof]);
+	}
+
 	#[test]
 	fn test_identifiers() {
 		let input = "abc x123 y";
-		let mut lexer = Lexer(input);
+		let mut lexer = Lexer::new(input);
 		assert!(matches!(lexer.next(), Some(Token::Identifier("abc"))));
 		assert!(matches!(lexer.next(), Some(Token::Identifier("x123"))));
 		assert!(matches!(lexer.next(), Some(Token::Identifier("y"))));
+		assert!(matches!(lexer.next(), Some(Token::Eof)));
 		assert!(matches!(lexer.next(), None));
 	}
 
 	#[test]
 	fn test_operators() {
-		let input = "+-*/()";
-		let mut lexer = Lexer(input);
+		let input = "+-*/%()";
+		let mut lexer = Lexer::new(input);
 		assert!(matches!(lexer.next(), Some(Token::Plus)));
 		assert!(matches!(lexer.next(), Some(Token::Minus)));
 		assert!(matches!(lexer.next(), Some(Token::Multiply)));
 		assert!(matches!(lexer.next(), Some(Token::Divide)));
+		assert!(matches!(lexer.next(), Some(Token::Percent)));
 		assert!(matches!(lexer.next(), Some(Token::LParen)));
 		assert!(matches!(lexer.next(), Some(Token::RParen)));
+		assert!(matches!(lexer.next(), Some(Token::Eof)));
 		assert!(matches!(lexer.next(), None));
 	}
 
+	#[test]
+	fn test_unicode_operators() {
+		let tokens: Vec<_> = Lexer::new("6÷2").collect();
+		assert_eq!(tokens, [Token::Number("6"), Token::Divide, Token::Number("2"), Token::Eof]);
+		let tokens: Vec<_> = Lexer::new("3×4").collect();
+		assert_eq!(tokens, [Token::Number("3"), Token::Multiply, Token::Number("4"), Token::Eof]);
+
+		// Both are two bytes long in UTF-8
+		let mut lexer = Lexer::new("1×x÷ 2");
+		let mut offsets = Vec::new();
+		while let Some(token) = lexer.next() {
+			offsets.push((token, lexer.offset()));
+		}
+		assert_eq!(offsets, [
+			(Token::Number("1"), 0),
+			(Token::Multiply, 1),
+			(Token::Identifier("x"), 3),
+			(Token::Divide, 4),
+			(Token::Number("2"), 7),
+			(Token::Eof, 8),
+		]);
+	}
+
+	#[test]
+	fn test_bitwise_operators() {
+		let input = "& | ^ ~ << >>";
+		let mut lexer = Lexer::new(input);
+		assert!(matches!(lexer.next(), Some(Token::BitAnd)));
+		assert!(matches!(lexer.next(), Some(Token::BitOr)));
+		assert!(matches!(lexer.next(), Some(Token::BitXor)));
+		assert!(matches!(lexer.next(), Some(Token::BitNot)));
+		assert!(matches!(lexer.next(), Some(Token::ShiftLeft)));
+		assert!(matches!(lexer.next(), Some(Token::ShiftRight)));
+		assert!(matches!(lexer.next(), Some(Token::Eof)));
+		assert!(matches!(lexer.next(), None));
+
+		assert!(matches!(Lexer::new("< 1").next(), Some(Token::Error("<"))));
+	}
+
+	#[test]
+	fn test_percent() {
+		// The lexer doesn't tell percent signs from modulo, that's up to the parser
+		let tokens: Vec<_> = Lexer::new("50%*3").collect();
+		assert_eq!(tokens, [Token::Number("50"), Token::Percent, Token::Multiply, Token::Number("3"), Token::Eof]);
+		let tokens: Vec<_> = Lexer::new("7 % 3").collect();
+		assert_eq!(tokens, [Token::Number("7"), Token::Percent, Token::Number("3"), Token::Eof]);
+	}
+
 	#[test]
 	fn test_mixed_expression() {
 		let input = "2 * (x + 3.14)";
-		let mut lexer = Lexer(input);
+		let mut lexer = Lexer::new(input);
 		assert!(matches!(lexer.next(), Some(Token::Number("2"))));
 		assert!(matches!(lexer.next(), Some(Token::Multiply)));
 		assert!(matches!(lexer.next(), Some(Token::LParen)));
@@ -155,15 +299,63 @@ mod tests {
 		assert!(matches!(lexer.next(), Some(Token::Plus)));
 		assert!(matches!(lexer.next(), Some(Token::Number("3.14"))));
 		assert!(matches!(lexer.next(), Some(Token::RParen)));
+		assert!(matches!(lexer.next(), Some(Token::Eof)));
 		assert!(matches!(lexer.next(), None));
 	}
 
 	#[test]
 	fn test_whitespace() {
 		let input = "  123   abc  ";
-		let mut lexer = Lexer(input);
+		let mut lexer = Lexer::new(input);
 		assert!(matches!(lexer.next(), Some(Token::Number("123"))));
 		assert!(matches!(lexer.next(), Some(Token::Identifier("abc"))));
+		assert!(matches!(lexer.next(), Some(Token::Eof)));
 		assert!(matches!(lexer.next(), None));
 	}
+
+	#[test]
+	fn test_eof() {
+		for input in ["", "   ", "1 + 2", "$"] {
+			let tokens: Vec<_> = Lexer::new(input).collect();
+			assert_eq!(tokens.iter().filter(|t| **t == Token::Eof).count(), 1);
+			assert_eq!(tokens.last(), Some(&Token::Eof));
+		}
+
+		let mut lexer = Lexer::new("1");
+		assert_eq!(lexer.next(), Some(Token::Number("1")));
+		assert_eq!(lexer.next(), Some(Token::Eof));
+		assert_eq!(lexer.next(), None);
+		assert_eq!(lexer.next(), None);
+	}
+
+	#[test]
+	fn test_invalid_character() {
+		let tokens: Vec<_> = Lexer::new("1 $ 2").collect();
+		assert_eq!(tokens, [Token::Number("1"), Token::Error("$"), Token::Number("2"), Token::Eof]);
+		let tokens: Vec<_> = Lexer::new("1€").collect();
+		assert_eq!(tokens, [Token::Number("1"), Token::Error("€"), Token::Eof]);
+	}
+
+	#[test]
+	fn test_offsets() {
+		let mut lexer = Lexer::new(" 12 +  sqrt(x)");
+		let mut offsets = Vec::new();
+		while let Some(token) = lexer.next() {
+			offsets.push((token, lexer.offset()));
+		}
+		assert_eq!(offsets, [
+			(Token::Number("12"), 1),
+			(Token::Plus, 4),
+			(Token::Identifier("sqrt"), 7),
+			(Token::LParen, 11),
+			(Token::Identifier("x"), 12),
+			(Token::RParen, 13),
+			(Token::Eof, 14),
+		]);
+
+		let mut lexer = Lexer::new("π $");
+		assert_eq!(lexer.next(), Some(Token::Identifier("π")));
+		assert_eq!(lexer.next(), Some(Token::Error("$")));
+		assert_eq!(lexer.offset(), 3);
+	}
 }
@@ -1,6 +1,7 @@
 mod lexer;
 mod parser;
 mod eval;
+mod safe;
 
 extern crate iced;
 
@@ -9,10 +10,8 @@ use std::fmt::{Display, write};
 use std::sync::Arc;
 
 use iced::widget::text_editor::{Action, Edit};
-use iced::widget::{button, column, row, text, text_editor, text_input};
+use iced::widget::{button, checkbox, column, row, text, text_editor, text_input};
 use iced::{Application, Element, Size, application, window};
-use crate::lexer::Lexer;
-use crate::parser::Parser;
 
 fn main() {
 	application("Calculator", update, view)
@@ -45,11 +44,19 @@ fn update(state: &mut State, message: Message) {
 				state.input.perform(Action::Edit(Edit::Backspace));
 			}
 			CalcButton::Eval => {
-				eval(&state.input.text(), &state.prec, &mut state.ouptut);
+				eval(
+					&state.input.text(),
+					&state.prec,
+					state.snap_to_integer,
+					state.show_steps,
+					&mut state.ouptut,
+				);
 			}
 		},
 		Message::Edit(action) => state.input.perform(action),
 		Message::EditPrec(prec) => state.prec = prec,
+		Message::ToggleSnap(snap) => state.snap_to_integer = snap,
+		Message::ToggleSteps(show) => state.show_steps = show,
 	}
 }
 
@@ -64,6 +71,8 @@ fn view(state: &State) -> Element<Message> {
 				calc_button(CalcButton::Eval)
 			],
 		],
+		checkbox("Snap to integer", state.snap_to_integer).on_toggle(Message::ToggleSnap),
+		checkbox("Show steps", state.show_steps).on_toggle(Message::ToggleSteps),
 		text(&state.ouptut).height(100),
 		row![
 			calc_button(CalcButton::Number(7)),
@@ -89,13 +98,17 @@ struct State {
 	input: text_editor::Content,
 	prec: String,
 	ouptut: String,
+	snap_to_integer: bool,
+	show_steps: bool,
 }
 
 #[derive(Debug, Clone)]
 enum Message {
 	ButtonPressed(CalcButton),
 	Edit(Action),
-	EditPrec(String)
+	EditPrec(String),
+	ToggleSnap(bool),
+	ToggleSteps(bool),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -128,29 +141,15 @@ fn calc_button(but: CalcButton) -> iced::widget::Button<'static, Message> {
 		.height(70)
 }
 
-fn eval(input: &str, prec: &str, output: &mut String) {
-	output.clear();
+fn eval(input: &str, prec: &str, snap_to_integer: bool, show_steps: bool, output: &mut String) {
 	let prec = prec.parse::<i64>().unwrap_or(1024);
-	
-	let tokens = Lexer(input).collect::<Vec<_>>();
-	let mut parser = Parser::new(&tokens);
-	let expr = match parser.parse() {
-		Err(err) => {
-			output.push_str(&err.to_string());
-			return;
-		},
-		Ok(expr) => expr,
+	let res = if show_steps {
+		safe::evaluate_with_steps(input, prec, 10, snap_to_integer)
+	} else {
+		safe::evaluate(input, prec, 10, snap_to_integer)
 	};
-	
-	let res_str = std::panic::catch_unwind(|| {
-		match expr.eval(prec) {
-			Err(err) => err.to_string(),
-			Ok(expr) => expr.to_string(prec),
-		}
-	});
-	
-	match res_str {
-		Err(err) => output.push_str("Error"),
-		Ok(res) => output.push_str(&res),
-	}
-}
\ No newline at end of file
+	*output = match res {
+		Ok(res) => res,
+		Err(err) => safe::render_error(input, &err),
+	};
+}
@@ -26,15 +26,24 @@ pub enum BinOp {
 	Sub,
 	Mul,
 	Div,
+	Rem,
+	BitAnd,
+	BitOr,
+	BitXor,
+	Shl,
+	Shr,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum UnaryOp {
 	Neg,
 	Pos,
+	Not,
+	/// Postfix `%`, divides by 100
+	Percent,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ParseError {
 	UnexpectedToken(String),
 	UnexpectedEof,
@@ -64,15 +73,81 @@ impl<'a, 'b> Parser<'a, 'b> {
 	pub fn parse(&mut self) -> Result<Expr<'a>, ParseError> {
 		let expr = self.parse_expression()?;
 		if !self.is_at_end() {
-			return Err(ParseError::UnexpectedToken(format!("{:?}", self.peek())));
+			return Err(self.unexpected());
 		}
 		Ok(expr)
 	}
 
-	// Parse expressions with precedence (lowest to highest):
+	// Parse expressions with precedence (lowest to highest), same as in C and Rust:
+	// Bitwise or -> Bitwise xor -> Bitwise and -> Shifts ->
 	// Addition/Subtraction -> Multiplication/Division -> Unary -> Primary
 	fn parse_expression(&mut self) -> Result<Expr<'a>, ParseError> {
-		self.parse_addition()
+		self.parse_bit_or()
+	}
+
+	fn parse_bit_or(&mut self) -> Result<Expr<'a>, ParseError> {
+		let mut expr = self.parse_bit_xor()?;
+
+		while self.match_tokens(&[Token::BitOr]) {
+			let right = self.parse_bit_xor()?;
+			expr = Expr::Binary {
+				op: BinOp::BitOr,
+				left: Box::new(expr),
+				right: Box::new(right),
+			};
+		}
+
+		Ok(expr)
+	}
+
+	fn parse_bit_xor(&mut self) -> Result<Expr<'a>, ParseError> {
+		let mut expr = self.parse_bit_and()?;
+
+		while self.match_tokens(&[Token::BitXor]) {
+			let right = self.parse_bit_and()?;
+			expr = Expr::Binary {
+				op: BinOp::BitXor,
+				left: Box::new(expr),
+				right: Box::new(right),
+			};
+		}
+
+		Ok(expr)
+	}
+
+	fn parse_bit_and(&mut self) -> Result<Expr<'a>, ParseError> {
+		let mut expr = self.parse_shift()?;
+
+		while self.match_tokens(&[Token::BitAnd]) {
+			let right = self.parse_shift()?;
+			expr = Expr::Binary {
+				op: BinOp::BitAnd,
+				left: Box::new(expr),
+				right: Box::new(right),
+			};
+		}
+
+		Ok(expr)
+	}
+
+	fn parse_shift(&mut self) -> Result<Expr<'a>, ParseError> {
+		let mut expr = self.parse_addition()?;
+
+		while self.match_tokens(&[Token::ShiftLeft, Token::ShiftRight]) {
+			let op = match self.previous() {
+				Token::ShiftLeft => BinOp::Shl,
+				Token::ShiftRight => BinOp::Shr,
+				_ => unreachable!(),
+			};
+			let right = self.parse_addition()?;
+			expr = Expr::Binary {
+				op,
+				left: Box::new(expr),
+				right: Box::new(right),
+			};
+		}
+
+		Ok(expr)
 	}
 
 	fn parse_addition(&mut self) -> Result<Expr<'a>, ParseError> {
@@ -98,10 +173,11 @@ impl<'a, 'b> Parser<'a, 'b> {
 	fn parse_multiplication(&mut self) -> Result<Expr<'a>, ParseError> {
 		let mut expr = self.parse_unary()?;
 
-		while self.match_tokens(&[Token::Multiply, Token::Divide]) {
+		while self.match_tokens(&[Token::Multiply, Token::Divide, Token::Percent]) {
 			let op = match self.previous() {
 				Token::Multiply => BinOp::Mul,
 				Token::Divide => BinOp::Div,
+				Token::Percent => BinOp::Rem,
 				_ => unreachable!(),
 			};
 			let right = self.parse_unary()?;
@@ -116,10 +192,11 @@ impl<'a, 'b> Parser<'a, 'b> {
 	}
 
 	fn parse_unary(&mut self) -> Result<Expr<'a>, ParseError> {
-		if self.match_tokens(&[Token::Minus, Token::Plus]) {
+		if self.match_tokens(&[Token::Minus, Token::Plus, Token::BitNot]) {
 			let op = match self.previous() {
 				Token::Minus => UnaryOp::Neg,
 				Token::Plus => UnaryOp::Pos,
+				Token::BitNot => UnaryOp::Not,
 				_ => unreachable!(),
 			};
 			let operand = self.parse_unary()?;
@@ -136,7 +213,17 @@ impl<'a, 'b> Parser<'a, 'b> {
 		match self.peek() {
 			Token::Number(n) => {
 				self.advance();
-				Ok(Expr::Number(n))
+				let number = Expr::Number(n);
+
+				// A '%' right after a number is a percent sign unless an operand follows, then it's modulo
+				if self.check(&Token::Percent) && !self.starts_operand(self.current + 1) {
+					self.advance();
+					return Ok(Expr::Unary {
+						op: UnaryOp::Percent,
+						operand: Box::new(number),
+					});
+				}
+				Ok(number)
 			}
 			Token::Identifier(id) => {
 				let name = id;
@@ -158,7 +245,7 @@ impl<'a, 'b> Parser<'a, 'b> {
 					}
 
 					if !self.check(&Token::RParen) {
-						return Err(ParseError::UnexpectedToken("Expected ')'".to_string()));
+						return Err(self.expected("')'"));
 					}
 					self.advance(); // consume ')'
 
@@ -172,17 +259,49 @@ impl<'a, 'b> Parser<'a, 'b> {
 				self.advance(); // consume '('
 				let expr = self.parse_expression()?;
 				if !self.check(&Token::RParen) {
-					return Err(ParseError::UnexpectedToken("Expected ')'".to_string()));
+					return Err(self.expected("')'"));
 				}
 				self.advance(); // consume ')'
 				Ok(expr)
 			}
-			Token::Error => Err(ParseError::InvalidExpression),
-			_ => Err(ParseError::UnexpectedToken(format!("{:?}", self.peek()))),
+			Token::Error(_) => Err(ParseError::InvalidExpression),
+			Token::Eof => Err(ParseError::UnexpectedEof),
+			_ => Err(self.unexpected()),
 		}
 	}
 
+	/// Index of the token where parsing stopped, which is the offending token after an error
+	pub fn position(&self) -> usize {
+		self.current
+	}
+
 	// Helper methods
+	/// Error for a missing token, which is UnexpectedEof if the input ended instead
+	fn expected(&self, what: &str) -> ParseError {
+		if self.is_at_end() {
+			ParseError::UnexpectedEof
+		} else {
+			ParseError::UnexpectedToken(format!("Expected {what}"))
+		}
+	}
+
+	/// Error for the current token, naming the offending text of invalid input
+	fn unexpected(&self) -> ParseError {
+		match self.peek() {
+			Token::Error(text) => ParseError::UnexpectedToken(format!("'{text}'")),
+			token => ParseError::UnexpectedToken(format!("{token:?}")),
+		}
+	}
+
+	/// Whether the token at index i can begin an operand, including signs followed by one
+	fn starts_operand(&self, i: usize) -> bool {
+		match self.tokens.get(i) {
+			Some(Token::Number(_) | Token::Identifier(_) | Token::LParen | Token::BitNot) => true,
+			Some(Token::Minus | Token::Plus) => self.starts_operand(i + 1),
+			_ => false,
+		}
+	}
+
 	fn match_tokens(&mut self, tokens: &[Token]) -> bool {
 		for token in tokens {
 			if self.check(token) {
@@ -208,13 +327,14 @@ impl<'a, 'b> Parser<'a, 'b> {
 		self.previous()
 	}
 
+	/// The input may or may not end with Token::Eof
 	fn is_at_end(&self) -> bool {
-		self.current >= self.tokens.len()
+		self.current >= self.tokens.len() || self.tokens[self.current] == Token::Eof
 	}
 
 	fn peek(&self) -> Token<'a> {
 		if self.is_at_end() {
-			Token::Error // Use Error as EOF marker
+			Token::Eof
 		} else {
 			self.tokens[self.current]
 		}
@@ -244,6 +364,9 @@ impl<'a> fmt::Display for Expr<'a> {
 			Expr::Binary { op, left, right } => {
 				write!(f, "({} {} {})", left, op, right)
 			}
+			Expr::Unary { op: UnaryOp::Percent, operand } => {
+				write!(f, "({}%)", operand)
+			}
 			Expr::Unary { op, operand } => {
 				write!(f, "({}{})", op, operand)
 			}
@@ -258,6 +381,12 @@ impl fmt::Display for BinOp {
 			BinOp::Sub => write!(f, "-"),
 			BinOp::Mul => write!(f, "*"),
 			BinOp::Div => write!(f, "/"),
+			BinOp::Rem => write!(f, "%"),
+			BinOp::BitAnd => write!(f, "&"),
+			BinOp::BitOr => write!(f, "|"),
+			BinOp::BitXor => write!(f, "^"),
+			BinOp::Shl => write!(f, "<<"),
+			BinOp::Shr => write!(f, ">>"),
 		}
 	}
 }
@@ -267,6 +396,8 @@ impl fmt::Display for UnaryOp {
 		match self {
 			UnaryOp::Neg => write!(f, "-"),
 			UnaryOp::Pos => write!(f, "+"),
+			UnaryOp::Not => write!(f, "~"),
+			UnaryOp::Percent => write!(f, "%"),
 		}
 	}
 }
@@ -305,7 +436,7 @@ mod tests {
 
 	#[test]
 	fn test_repeated_addition() {
-		let tokens: Vec<_> = Lexer("2+5+6").collect();
+		let tokens: Vec<_> = Lexer::new("2+5+6").collect();
 		let mut parser = Parser::new(&tokens);
 		let result = parser.parse().unwrap();
 		
@@ -350,6 +481,46 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn test_bitwise_precedence() {
+		let parse = |input| {
+			let tokens: Vec<_> = Lexer::new(input).collect();
+			Parser::new(&tokens).parse().unwrap().to_string()
+		};
+
+		assert_eq!(parse("6 & 3"), "(6 & 3)");
+		assert_eq!(parse("1 << 10"), "(1 << 10)");
+		assert_eq!(parse("~0"), "(~0)");
+		assert_eq!(parse("1 << 2 + 3"), "(1 << (2 + 3))");
+		assert_eq!(parse("1 | 2 ^ 3 & 4 << 5"), "(1 | (2 ^ (3 & (4 << 5))))");
+		assert_eq!(parse("~1 & -2 * 3"), "((~1) & ((-2) * 3))");
+		assert_eq!(parse("1 + 7 % 3 * 2"), "(1 + ((7 % 3) * 2))");
+		assert_eq!(parse("-7 % 3"), "((-7) % 3)");
+	}
+
+	#[test]
+	fn test_percent() {
+		let parse = |input| {
+			let tokens: Vec<_> = Lexer::new(input).collect();
+			Parser::new(&tokens).parse().map(|e| e.to_string())
+		};
+
+		assert_eq!(parse("50%").unwrap(), "(50%)");
+		assert_eq!(parse("200% * 3").unwrap(), "((200%) * 3)");
+		assert_eq!(parse("(10%)").unwrap(), "(10%)");
+		assert_eq!(parse("-5% / 2").unwrap(), "((-(5%)) / 2)");
+		assert_eq!(parse("sqrt(50%, 1)").unwrap(), "sqrt((50%), 1)");
+		assert_eq!(parse("7 % 3").unwrap(), "(7 % 3)");
+		assert_eq!(parse("7 % -3").unwrap(), "(7 % (-3))");
+		assert_eq!(parse("7 % - -3").unwrap(), "(7 % (-(-3)))");
+		assert_eq!(parse("7 % -").unwrap_err(), ParseError::UnexpectedEof);
+		assert_eq!(parse("7 % ~3").unwrap(), "(7 % (~3))");
+		assert_eq!(parse("7 % (1 + 2)").unwrap(), "(7 % (1 + 2))");
+		// Only numbers take a percent sign
+		assert_eq!(parse("pi %"), Err(ParseError::UnexpectedEof));
+		assert_eq!(parse("50%%"), Err(ParseError::UnexpectedEof));
+	}
+
 	#[test]
 	fn test_parentheses() {
 		// (2 + 3) * 4
@@ -475,4 +646,23 @@ mod tests {
 			panic!("Expected addition at top level");
 		}
 	}
-}
\ No newline at end of file
+
+	#[test]
+	fn test_eof_vs_invalid_character() {
+		let parse = |input| {
+			let tokens: Vec<_> = Lexer::new(input).collect();
+			Parser::new(&tokens).parse()
+		};
+
+		assert_eq!(parse("1 +"), Err(ParseError::UnexpectedEof));
+		assert_eq!(parse(""), Err(ParseError::UnexpectedEof));
+		assert_eq!(parse("(1"), Err(ParseError::UnexpectedEof));
+		assert_eq!(parse("sqrt(1"), Err(ParseError::UnexpectedEof));
+		assert_eq!(parse("1 + $"), Err(ParseError::InvalidExpression));
+		assert_eq!(parse("$"), Err(ParseError::InvalidExpression));
+		assert_eq!(parse("(1 $"), Err(ParseError::UnexpectedToken("Expected ')'".to_string())));
+		assert_eq!(parse("1 $"), Err(ParseError::UnexpectedToken("'$'".to_string())));
+		assert_eq!(parse("1 .e3"), Err(ParseError::UnexpectedToken("'.e3'".to_string())));
+		assert!(parse("1 + 2").is_ok());
+	}
+}
@@ -0,0 +1,234 @@
+use std::fmt;
+
+use crate::eval::{EvalError, Value};
+use crate::lexer::{Lexer, Token};
+use crate::parser::{Expr, ParseError, Parser};
+
+/// Any error that can occur while evaluating user input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CalcError {
+	/// A syntax error at the given byte offset of the input
+	Parse(ParseError, usize),
+	/// An evaluation error, at the given byte offset if it can be attributed to a token
+	Eval(EvalError, Option<usize>),
+	/// The result is too large to compute
+	TooLarge,
+	/// Output radix is not between 2 and 36
+	InvalidRadix(u32),
+}
+
+impl fmt::Display for CalcError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			CalcError::Parse(e, _) => write!(f, "{}", e),
+			CalcError::Eval(e, _) => write!(f, "{}", e),
+			CalcError::TooLarge => write!(f, "Result is too large"),
+			CalcError::InvalidRadix(radix) => write!(f, "Invalid radix: {}", radix),
+		}
+	}
+}
+
+impl std::error::Error for CalcError {}
+
+impl From<EvalError> for CalcError {
+	fn from(e: EvalError) -> Self {
+		CalcError::from_eval(e, None)
+	}
+}
+
+impl CalcError {
+	fn from_eval(e: EvalError, offset: Option<usize>) -> Self {
+		match e {
+			EvalError::Overflow => CalcError::TooLarge,
+			e => CalcError::Eval(e, offset),
+		}
+	}
+
+	/// Byte offset of the input the error refers to, if known
+	pub fn offset(&self) -> Option<usize> {
+		match self {
+			CalcError::Parse(_, offset) => Some(*offset),
+			CalcError::Eval(_, offset) => *offset,
+			_ => None,
+		}
+	}
+}
+
+/// Renders the error below the input, with a caret under the offending column if known:
+/// ```text
+/// 1 + $
+///     ^ Invalid expression
+/// ```
+pub fn render_error(input: &str, err: &CalcError) -> String {
+	match err.offset() {
+		Some(offset) => {
+			let column = input.get(..offset).map_or(0, |s| s.chars().count());
+			format!("{input}\n{:column$}^ {err}", "")
+		}
+		None => err.to_string(),
+	}
+}
+
+/// Lexes, parses and evaluates input, formatting the result in the given radix.
+/// On top of the input, precision and radix, it takes the UI's option of snapping approximate
+/// results to a nearby integer, so that all of the formatting happens here.
+/// All failures are reported as a CalcError, evaluation never panics.
+pub fn evaluate(input: &str, prec: i64, radix: u32, snap_to_integer: bool) -> Result<String, CalcError> {
+	check_radix(radix)?;
+	let value = with_expr(input, |expr| expr.evaluate_value(prec))?;
+	Ok(format_value(value, prec, radix, snap_to_integer))
+}
+
+/// Same as evaluate, preceded by one `sub-expression = value` line per step of the computation,
+/// see Expr::eval_traced
+pub fn evaluate_with_steps(input: &str, prec: i64, radix: u32, snap_to_integer: bool) -> Result<String, CalcError> {
+	check_radix(radix)?;
+	let (steps, value) = with_expr(input, |expr| {
+		let (evaluation, steps) = expr.eval_traced(prec)?;
+		let steps: Vec<_> = steps
+			.iter()
+			.map(|step| format!("{} = {}", step.expr, step.value.to_string_radix(radix, false)))
+			.collect();
+		Ok((steps, evaluation.value()))
+	})?;
+	let mut res = steps.join("\n");
+	if !res.is_empty() {
+		res.push('\n');
+	}
+	res.push_str(&format_value(value, prec, radix, snap_to_integer));
+	Ok(res)
+}
+
+fn check_radix(radix: u32) -> Result<(), CalcError> {
+	if !(2..=36).contains(&radix) {
+		return Err(CalcError::InvalidRadix(radix));
+	}
+	Ok(())
+}
+
+/// Lexes and parses input and applies f to the expression, attributing errors to the input
+fn with_expr<T>(input: &str, f: impl FnOnce(&Expr) -> Result<T, EvalError>) -> Result<T, CalcError> {
+	let mut lexer = Lexer::new(input);
+	let mut tokens = Vec::new();
+	let mut offsets = Vec::new();
+	while let Some(token) = lexer.next() {
+		tokens.push(token);
+		offsets.push(lexer.offset());
+	}
+
+	let mut parser = Parser::new(&tokens);
+	let expr = parser.parse().map_err(|e| {
+		let offset = offsets.get(parser.position()).copied().unwrap_or(input.len());
+		CalcError::Parse(e, offset)
+	})?;
+	f(&expr).map_err(|e| {
+		let offset = eval_error_offset(&e, &tokens, &offsets);
+		CalcError::from_eval(e, offset)
+	})
+}
+
+/// Finds the identifier an evaluation error is about, other errors aren't attributed to a token
+fn eval_error_offset(e: &EvalError, tokens: &[Token], offsets: &[usize]) -> Option<usize> {
+	let name = match e {
+		EvalError::InvalidConst(name) | EvalError::InvalidFnCall(name) => name,
+		_ => return None,
+	};
+	let i = tokens.iter().position(|t| *t == Token::Identifier(name))?;
+	Some(offsets[i])
+}
+
+fn format_value(value: Value, prec: i64, radix: u32, snap_to_integer: bool) -> String {
+	match &value {
+		Value::Rational(q) if q.denominator().is_one() => q.numerator().to_string_radix(radix, false),
+		Value::Rational(q) => format!(
+			"{}/{} ≈ {}",
+			q.numerator().to_string_radix(radix, false),
+			q.denominator().to_string_radix(radix, false),
+			value.to_float(prec).to_string_radix(radix, false)
+		),
+		Value::Float(x) if snap_to_integer => x.snap_to_integer(prec).to_string_radix(radix, false),
+		Value::Float(x) => x.to_string_radix(radix, false),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_evaluate_valid() {
+		assert_eq!(evaluate("1 + 2 * 3", 64, 10, false), Ok("7".to_string()));
+		assert_eq!(evaluate("255", 64, 16, false), Ok("ff".to_string()));
+		assert_eq!(evaluate("-3/2", 64, 10, false), Ok("-3/2 ≈ -1.5".to_string()));
+		assert_eq!(evaluate("sqrt(4)", 64, 10, true), Ok("2".to_string()));
+		// Tiny results finish quickly instead of printing every binary digit
+		assert_eq!(evaluate("pow(0.5, 1000000000000000000)", 64, 10, false), Ok("0".to_string()));
+		assert_eq!(evaluate("pow(2, -100)", 64, 10, false), Ok("1/1267650600228229401496703205376 ≈ 0".to_string()));
+		assert_eq!(evaluate("1e-100000000", 64, 10, false), Ok("0".to_string()));
+	}
+
+	#[test]
+	fn test_evaluate_with_steps() {
+		assert_eq!(
+			evaluate_with_steps("2 + 3 * 4", 64, 10, false),
+			Ok("(3 * 4) = 12\n(2 + (3 * 4)) = 14\n14".to_string())
+		);
+		assert_eq!(
+			evaluate_with_steps("1 / 4 + 1", 64, 10, false),
+			Ok("(1 / 4) = 0.25\n((1 / 4) + 1) = 1.25\n5/4 ≈ 1.25".to_string())
+		);
+		assert_eq!(evaluate_with_steps("255", 64, 16, false), Ok("ff".to_string()));
+		assert_eq!(evaluate_with_steps("1 +", 64, 10, false), Err(CalcError::Parse(ParseError::UnexpectedEof, 3)));
+	}
+
+	#[test]
+	fn test_evaluate_errors() {
+		assert_eq!(evaluate("1 +", 64, 10, false), Err(CalcError::Parse(ParseError::UnexpectedEof, 3)));
+		assert_eq!(evaluate("(1", 64, 10, false), Err(CalcError::Parse(ParseError::UnexpectedEof, 2)));
+		assert_eq!(evaluate("1 $ 2", 64, 10, false), Err(CalcError::Parse(ParseError::UnexpectedToken("'$'".to_string()), 2)));
+		assert_eq!(
+			evaluate("1 / 0", 64, 10, false),
+			Err(CalcError::Eval(EvalError::DivisionByZero, None))
+		);
+		assert_eq!(
+			evaluate("2 * foo(1)", 64, 10, false),
+			Err(CalcError::Eval(EvalError::InvalidFnCall("foo".to_string()), Some(4)))
+		);
+		// Wrong argument counts are reported instead of panicking
+		assert_eq!(
+			evaluate("sqrt()", 64, 10, false),
+			Err(CalcError::Eval(EvalError::InvalidFnCall("sqrt".to_string()), Some(0)))
+		);
+		assert_eq!(
+			evaluate("1 + pow(2)", 64, 10, false),
+			Err(CalcError::Eval(EvalError::InvalidFnCall("pow".to_string()), Some(4)))
+		);
+		assert_eq!(
+			evaluate("root(8, 3, 1)", 64, 10, false),
+			Err(CalcError::Eval(EvalError::InvalidFnCall("root".to_string()), Some(0)))
+		);
+		assert_eq!(evaluate("1 << 100000000", 64, 10, false), Err(CalcError::TooLarge));
+		assert_eq!(evaluate("exp(100000000)", 64, 10, false), Err(CalcError::TooLarge));
+		assert_eq!(evaluate("pow(10, 100000000)", 64, 10, false), Err(CalcError::TooLarge));
+		// Ranges whose partial sums or products are zero
+		assert_eq!(evaluate("sum(k, 0, 0, k)", 64, 10, false), Ok("0".to_string()));
+		assert_eq!(evaluate("sum(k, -1, 1, k)", 64, 10, false), Ok("0".to_string()));
+		assert_eq!(evaluate("prod(k, 0, 3, k)", 64, 10, false), Ok("0".to_string()));
+		assert_eq!(evaluate("1", 64, 1, false), Err(CalcError::InvalidRadix(1)));
+	}
+
+	#[test]
+	fn test_render_error() {
+		let err = CalcError::Parse(ParseError::InvalidExpression, 4);
+		assert_eq!(render_error("1 + $", &err), "1 + $\n    ^ Invalid expression");
+		let err = CalcError::Parse(ParseError::UnexpectedEof, 0);
+		assert_eq!(render_error("", &err), "\n^ Unexpected end of input");
+		// Columns count chars, not bytes
+		let err = CalcError::Eval(EvalError::InvalidConst("e".to_string()), Some(5));
+		assert_eq!(render_error("π + e", &err), "π + e\n    ^ Invalid constant: e");
+
+		let input = "sqrt(2 +";
+		let err = evaluate(input, 64, 10, false).unwrap_err();
+		assert_eq!(render_error(input, &err), "sqrt(2 +\n        ^ Unexpected end of input");
+	}
+}